@@ -1,8 +1,8 @@
-use gdscript_formatter::linter::{
-    LintIssue, LintSeverity, LinterConfig, lint_gdscript_with_config,
-};
+use gdscript_formatter::linter::{LintIssue, LinterConfig, lint_gdscript_with_config};
 use gdscript_formatter::{
-    FormatterConfiguration, PrinterConfiguration, QuoteStyle, format_gdscript,
+    FormatterConfiguration, LineEnding, PrinterConfiguration, QuoteStyle, ReorderConfiguration,
+    ReorderRegionMode, ReorderSortMode, format_gdscript, format_gdscript_range,
+    line_range_to_byte_range,
 };
 use godot::builtin::{Array, PackedStringArray};
 use godot::prelude::{
@@ -39,6 +39,32 @@ impl GDScriptFormatter {
         }
     }
 
+    /// Formats only the statements on lines `first_line` to `last_line`
+    /// (1-based, inclusive) and returns the whole source code.
+    #[func]
+    pub fn format_gdscript_range(
+        &self,
+        source: GString,
+        first_line: i64,
+        last_line: i64,
+        config: Dictionary<Variant, Variant>,
+    ) -> GString {
+        let formatter_config = dict_to_formatter_config(&config);
+        let source = source.to_string();
+        let byte_range = line_range_to_byte_range(
+            &source,
+            first_line.max(1) as usize,
+            last_line.max(1) as usize,
+        );
+        match format_gdscript_range(&source, &byte_range, &formatter_config) {
+            Ok(formatted) => GString::from(&formatted),
+            Err(error) => {
+                godot_error!("Formatter error: {}", error);
+                GString::new()
+            }
+        }
+    }
+
     #[func]
     pub fn lint_gdscript(
         &self,
@@ -79,6 +105,7 @@ macro_rules! extract_field {
 fn dict_to_formatter_config(dict: &Dictionary<Variant, Variant>) -> FormatterConfiguration {
     let mut result = FormatterConfiguration::default();
     extract_field!(dict, safe, bool, result);
+    extract_field!(dict, verify_idempotent, bool, result);
     extract_field!(dict, reorder_code, bool, result);
     extract_field!(dict, blank_lines_around_definitions, u16, result);
     if let Some(variant) = dict.get("printer") {
@@ -87,6 +114,12 @@ fn dict_to_formatter_config(dict: &Dictionary<Variant, Variant>) -> FormatterCon
             Err(error) => godot_error!("Config 'printer' is invalid: {}", error),
         }
     }
+    if let Some(variant) = dict.get("reorder") {
+        match variant.try_to::<Dictionary<Variant, Variant>>() {
+            Ok(sub_dict) => result.reorder = dict_to_reorder_config(&sub_dict),
+            Err(error) => godot_error!("Config 'reorder' is invalid: {}", error),
+        }
+    }
     if let Some(variant) = dict.get("quote_style") {
         match variant
             .try_to::<GString>()
@@ -110,6 +143,70 @@ fn dict_to_printer_config(dict: &Dictionary<Variant, Variant>) -> PrinterConfigu
     extract_field!(dict, indent_blank_lines, bool, result);
     extract_field!(dict, maximum_blank_lines, u16, result);
     extract_field!(dict, continuation_indent_level, u16, result);
+    extract_field!(dict, byte_order_mark, bool, result);
+    if let Some(variant) = dict.get("line_ending") {
+        match variant
+            .try_to::<GString>()
+            .ok()
+            .and_then(|gstr| LineEnding::from_name(&gstr.to_string()))
+        {
+            Some(line_ending) => result.line_ending = line_ending,
+            None => godot_error!("Config 'line_ending' is invalid"),
+        }
+    }
+    result
+}
+
+fn dict_to_reorder_config(dict: &Dictionary<Variant, Variant>) -> ReorderConfiguration {
+    let mut result = ReorderConfiguration::default();
+    if let Some(variant) = dict.get("category_order") {
+        match variant.try_to::<PackedStringArray>() {
+            Ok(names) => {
+                let names: Vec<String> = names
+                    .as_slice()
+                    .iter()
+                    .map(|gd_str| gd_str.to_string())
+                    .collect();
+                match ReorderConfiguration::parse_category_order(&names) {
+                    Ok(category_order) => result.category_order = category_order,
+                    Err(error) => godot_error!("Config 'category_order' is invalid: {}", error),
+                }
+            }
+            Err(error) => godot_error!("Config 'category_order' is invalid: {}", error),
+        }
+    }
+    if let Some(variant) = dict.get("sort_mode") {
+        match variant
+            .try_to::<GString>()
+            .ok()
+            .and_then(|gstr| ReorderSortMode::from_name(&gstr.to_string()))
+        {
+            Some(sort_mode) => result.sort_mode = sort_mode,
+            None => godot_error!("Config 'sort_mode' is invalid"),
+        }
+    }
+    if let Some(variant) = dict.get("region_mode") {
+        match variant
+            .try_to::<GString>()
+            .ok()
+            .and_then(|gstr| ReorderRegionMode::from_name(&gstr.to_string()))
+        {
+            Some(region_mode) => result.region_mode = region_mode,
+            None => godot_error!("Config 'region_mode' is invalid"),
+        }
+    }
+    if let Some(variant) = dict.get("virtual_method_order") {
+        match variant.try_to::<PackedStringArray>() {
+            Ok(names) => {
+                result.virtual_method_order = names
+                    .as_slice()
+                    .iter()
+                    .map(|gd_str| gd_str.to_string())
+                    .collect()
+            }
+            Err(error) => godot_error!("Config 'virtual_method_order' is invalid: {}", error),
+        }
+    }
     result
 }
 
@@ -137,14 +234,10 @@ fn issues_to_array(issues: &Vec<LintIssue>) -> Array<Dictionary<Variant, Variant
         let mut gd_issue = Dictionary::<Variant, Variant>::new();
         gd_issue.set("line", issue.line as i64);
         gd_issue.set("column", issue.column as i64);
+        gd_issue.set("end_line", issue.end_line as i64);
+        gd_issue.set("end_column", issue.end_column as i64);
         gd_issue.set("rule", &GString::from(&issue.rule));
-        gd_issue.set(
-            "severity",
-            match issue.severity {
-                LintSeverity::Error => "error",
-                LintSeverity::Warning => "warning",
-            },
-        );
+        gd_issue.set("severity", issue.severity.name());
         gd_issue.set("message", &GString::from(&issue.message));
         gd_issues.push(&gd_issue);
     }
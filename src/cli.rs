@@ -5,7 +5,9 @@
 //!
 //! NB: do not replace with a dependency like clap: it brings too many
 //! dependencies only to save a little straightforward code.
-use gdscript_formatter::QuoteStyle;
+use gdscript_formatter::linter::LintSeverity;
+use gdscript_formatter::linter::output_formats::LintOutputFormat;
+use gdscript_formatter::{LineEnding, QuoteStyle};
 use std::path::PathBuf;
 
 const HELP_FORMATTER: &str = "\
@@ -13,6 +15,7 @@ const HELP_FORMATTER: &str = "\
 
 	Usage: gdscript-formatter [OPTIONS] [FILES]...
 	       gdscript-formatter lint [OPTIONS] [FILES]...
+	       gdscript-formatter lsp
 
 	Arguments:
 	  <FILES>...  GDScript files or directories to format. If empty, uses
@@ -20,8 +23,10 @@ const HELP_FORMATTER: &str = "\
 
 	Options:
 	  -c, --check                                Check if files are formatted, exit 1 if not
+	      --diff                                 Print a unified diff of the changes instead of writing files
 	  -x, --exclude <PATH>                       Exclude one file or directory (you can repeat this option multiple times)
 	      --verify-structure                     Verify formatted output has the same structure as the input
+	      --verify-idempotent                    Verify formatting the output again doesn't change it
 	      --stdout                               Write to stdout instead of overwriting files
 	  -v, --verbose                              Print one status line for each processed file
 	      --use-spaces                           Use spaces instead of tabs for indentation
@@ -30,12 +35,15 @@ const HELP_FORMATTER: &str = "\
 	      --max-line-length <NUM>                Maximum line length before wrapping (default: 100)
 	      --blank-lines-around-definitions <NUM> Blank lines between top-level definitions (default: 2)
 	      --continuation-indent-level <NUM>      Extra indent for line continuations (default: 2)
-	      --quote-style <STYLE>                  String quotes: preserve, single, or double (default: preserve)
+	      --quote-style <STYLE>                  String quotes: preserve, single, double, or minimize-escapes (default: preserve)
+	      --line-ending <ENDING>                 Line endings: auto, lf, or crlf (default: auto, keeps the file's)
+	      --lines <START:END>                    Only format the statements on lines START to END (1-based, inclusive)
 	  -h, --help                                 Print help
 	  -V, --version                              Print version
 
 	Subcommands:
 	  lint                     Lint GDScript files for style issues
+	  lsp                      Run a language server over stdio for code editors
 
 	Run 'gdscript-formatter lint --help' for lint options.
 ";
@@ -53,12 +61,43 @@ Options:
       --disable <RULES>       Disable specific rules (comma-separated)
       --max-line-length <NUM>  Maximum line length allowed (default: 100)
       --list-rules            List all available linting rules
+      --severity <RULES>      Override rule severities (comma-separated
+                              rule=severity pairs, like unused-argument=error).
+                              Severities: error, warning, info, or hint
+      --error-on <SEVERITY>   Exit with an error code only for issues of this
+                              severity or higher (default: hint, any issue)
+      --fix                   Fix the issues that can be fixed automatically, then
+                              format the fixed files
       --pretty                Use pretty formatting for lint output
+      --output-format <FORMAT> Output format: text, pretty, json, sarif, checkstyle,
+                               github, or junit (default: text)
   -h, --help                  Print help
 ";
 
+/// A span of 1-based line numbers, both ends included.
+#[derive(Clone, Copy)]
+pub struct LineRange {
+    pub first_line: usize,
+    pub last_line: usize,
+}
+
+const HELP_LANGUAGE_SERVER: &str = "\
+Run a language server that formats and lints GDScript files for code editors.
+
+The server communicates over stdin and stdout using the Language Server
+Protocol. It supports document and range formatting and publishes linter
+issues as diagnostics. Settings come from .editorconfig files like on the
+command line.
+
+Usage: gdscript-formatter lsp
+
+Options:
+  -h, --help  Print help
+";
+
 /// Represents the parsed command-line arguments for the GDScript formatter. You
-/// can use exactly one command: currently, Format (the default) or Lint.
+/// can use exactly one command: currently, Format (the default), Lint, or
+/// LanguageServer.
 pub struct CliArguments {
     /// List of input file paths or directories to process.
     pub input_file_paths: Vec<PathBuf>,
@@ -82,6 +121,9 @@ pub enum Command {
         /// them. Returns error code ERROR_CODE_NOT_FORMATTED if any of the input
         /// files are not formatted.
         do_check_formatted_only: bool,
+        /// If true, prints a unified diff for each file that needs formatting
+        /// instead of modifying files.
+        do_print_diff: bool,
         /// If set, uses spaces for indentation instead of tabs.
         use_spaces: Option<bool>,
         /// Number of spaces to use for indentation.
@@ -89,6 +131,9 @@ pub enum Command {
         /// If true, the formatter will re-parse the formatted code and verify it
         /// has the same structure as the original before writing it to files.
         use_verify_structure: bool,
+        /// If true, the formatter will format the formatted code a second time
+        /// and fail with a diff if the second pass changes it.
+        use_verify_idempotent: bool,
         /// If true, the formatter will reorder code to follow the official
         /// GDScript style guide's recommended order of code elements.
         do_reorder_code: bool,
@@ -103,6 +148,12 @@ pub enum Command {
         /// If set to `single` or `double`, the formatter will try to use that
         /// quote style for strings.
         quote_style: Option<QuoteStyle>,
+        /// If set to `lf` or `crlf`, the formatter writes that line ending
+        /// instead of keeping the one of each file.
+        line_ending: Option<LineEnding>,
+        /// If set, only formats the statements that overlap these lines and
+        /// leaves the rest of each file untouched.
+        line_range: Option<LineRange>,
     },
     /// Lint GDScript files for style and convention issues.
     Lint {
@@ -113,10 +164,21 @@ pub enum Command {
         /// If true, the linter program will list all available linting rules and
         /// exit.
         do_list_rules: bool,
-        /// If true, the linter program will print the list of available linting
-        /// rules in a human-readable format.
-        do_pretty_print: bool,
+        /// How to print the issues: one line per issue by default, or a
+        /// human-readable or machine-readable report.
+        output_format: LintOutputFormat,
+        /// If true, the linter fixes the issues it can and writes the fixed
+        /// files before reporting the remaining issues.
+        do_fix: bool,
+        /// Optional list of comma-separated `rule=severity` pairs that
+        /// override the default severity of rules.
+        rule_severities: Option<String>,
+        /// The linter exits with an error code if it finds an issue with this
+        /// severity or a higher one.
+        error_on_severity: LintSeverity,
     },
+    /// Run the language server over stdio.
+    LanguageServer,
 }
 
 /// Internal discriminator used during parsing to track which command's flags
@@ -124,6 +186,7 @@ pub enum Command {
 enum ActiveCommand {
     Format,
     Lint,
+    LanguageServer,
 }
 
 pub fn parse_args() -> CliArguments {
@@ -135,26 +198,37 @@ pub fn parse_args() -> CliArguments {
     let mut format_do_print_to_stdout = false;
     let mut format_use_verbose_output = false;
     let mut format_do_check_formatted_only = false;
+    let mut format_do_print_diff = false;
     let mut format_use_spaces: Option<bool> = None;
     let mut format_indent_size: Option<usize> = None;
     let mut format_use_verify_structure = false;
+    let mut format_use_verify_idempotent = false;
     let mut format_do_reorder_code = false;
     let mut format_max_line_length: Option<usize> = None;
     let mut format_blank_lines_around_definitions: Option<u16> = None;
     let mut format_continuation_indent_level: Option<u16> = None;
     let mut format_quote_style: Option<QuoteStyle> = None;
+    let mut format_line_ending: Option<LineEnding> = None;
+    let mut format_line_range: Option<LineRange> = None;
 
     let mut lint_disabled_rules: Option<String> = None;
     let mut lint_max_line_length: Option<usize> = None;
     let mut lint_list_rules = false;
-    let mut lint_pretty_print = false;
+    let mut lint_output_format = LintOutputFormat::Text;
+    let mut lint_do_fix = false;
+    let mut lint_rule_severities: Option<String> = None;
+    let mut lint_error_on_severity = LintSeverity::Hint;
 
     // The first positional argument optionally selects a command. If it is
-    // "lint", we run the linter program. Defaults to the formatter.
+    // "lint", we run the linter program, and if it is "lsp", the language
+    // server. Defaults to the formatter.
     let mut current_argument_index = 1;
     if argument_list.len() > 1 && argument_list[1] == "lint" {
         active_command = ActiveCommand::Lint;
         current_argument_index = 2;
+    } else if argument_list.len() > 1 && argument_list[1] == "lsp" {
+        active_command = ActiveCommand::LanguageServer;
+        current_argument_index = 2;
     }
 
     while current_argument_index < argument_list.len() {
@@ -175,6 +249,7 @@ pub fn parse_args() -> CliArguments {
             match active_command {
                 ActiveCommand::Format => print!("{}", HELP_FORMATTER),
                 ActiveCommand::Lint => print!("{}", HELP_LINTER),
+                ActiveCommand::LanguageServer => print!("{}", HELP_LANGUAGE_SERVER),
             }
             std::process::exit(0);
         }
@@ -209,6 +284,10 @@ pub fn parse_args() -> CliArguments {
                         require_no_value(assigned_value, "--check");
                         format_do_check_formatted_only = true;
                     }
+                    "diff" => {
+                        require_no_value(assigned_value, "--diff");
+                        format_do_print_diff = true;
+                    }
                     "use-spaces" => {
                         require_no_value(assigned_value, "--use-spaces");
                         format_use_spaces = Some(true);
@@ -217,6 +296,10 @@ pub fn parse_args() -> CliArguments {
                         require_no_value(assigned_value, "--verify-structure");
                         format_use_verify_structure = true;
                     }
+                    "verify-idempotent" => {
+                        require_no_value(assigned_value, "--verify-idempotent");
+                        format_use_verify_idempotent = true;
+                    }
                     "safe" => {
                         // DEPRECATED: We keep this flag for anyone who already
                         // used it as part of a CI or of their workflow. But
@@ -299,7 +382,37 @@ pub fn parse_args() -> CliArguments {
                         format_quote_style = match QuoteStyle::from_name(&value) {
                             Some(quote_style) => Some(quote_style),
                             None => print_error_invalid_argument(&format!(
-                                "--quote-style expects preserve, single, or double, got '{}'",
+                                "--quote-style expects preserve, single, double, or minimize-escapes, got '{}'",
+                                value
+                            )),
+                        };
+                    }
+                    "line-ending" => {
+                        let value = consume_flag_value(
+                            assigned_value,
+                            &argument_list,
+                            &mut current_argument_index,
+                            "--line-ending",
+                        );
+                        format_line_ending = match LineEnding::from_name(&value) {
+                            Some(line_ending) => Some(line_ending),
+                            None => print_error_invalid_argument(&format!(
+                                "--line-ending expects auto, lf, or crlf, got '{}'",
+                                value
+                            )),
+                        };
+                    }
+                    "lines" => {
+                        let value = consume_flag_value(
+                            assigned_value,
+                            &argument_list,
+                            &mut current_argument_index,
+                            "--lines",
+                        );
+                        format_line_range = match parse_line_range(&value) {
+                            Some(line_range) => Some(line_range),
+                            None => print_error_invalid_argument(&format!(
+                                "--lines expects START:END with 1 <= START <= END, got '{}'",
                                 value
                             )),
                         };
@@ -349,14 +462,69 @@ pub fn parse_args() -> CliArguments {
                     }
                     "pretty" => {
                         require_no_value(assigned_value, "--pretty");
-                        lint_pretty_print = true;
+                        lint_output_format = LintOutputFormat::Pretty;
+                    }
+                    "fix" => {
+                        require_no_value(assigned_value, "--fix");
+                        lint_do_fix = true;
+                    }
+                    "severity" => {
+                        let value = consume_flag_value(
+                            assigned_value,
+                            &argument_list,
+                            &mut current_argument_index,
+                            "--severity",
+                        );
+                        lint_rule_severities = Some(value);
+                    }
+                    "error-on" => {
+                        let value = consume_flag_value(
+                            assigned_value,
+                            &argument_list,
+                            &mut current_argument_index,
+                            "--error-on",
+                        );
+                        lint_error_on_severity = match LintSeverity::from_name(&value) {
+                            Some(severity) => severity,
+                            None => print_error_invalid_argument(&format!(
+                                "--error-on expects error, warning, info, or hint, got '{}'",
+                                value
+                            )),
+                        };
+                    }
+                    "output-format" => {
+                        let value = consume_flag_value(
+                            assigned_value,
+                            &argument_list,
+                            &mut current_argument_index,
+                            "--output-format",
+                        );
+                        lint_output_format = match LintOutputFormat::from_name(&value) {
+                            Some(output_format) => output_format,
+                            None => print_error_invalid_argument(&format!(
+                                "--output-format expects text, pretty, json, sarif, checkstyle, github, or junit, got '{}'",
+                                value
+                            )),
+                        };
                     }
                     _ => print_error_invalid_argument(&format!(
                         "unexpected argument '--{}'",
                         flag_name
                     )),
                 },
+                ActiveCommand::LanguageServer => {
+                    // Some editors pass --stdio to select the transport. It is
+                    // the only transport we support.
+                    if flag_name != "stdio" {
+                        print_error_invalid_argument(&format!(
+                            "unexpected argument '--{}'",
+                            flag_name
+                        ));
+                    }
+                }
             }
+        } else if matches!(active_command, ActiveCommand::LanguageServer) {
+            print_error_invalid_argument(&format!("unexpected argument '{}'", current_argument));
         } else if current_argument.starts_with('-') && current_argument.len() > 1 {
             let short_flags = &current_argument[1..];
             if short_flags == "x" {
@@ -410,14 +578,18 @@ pub fn parse_args() -> CliArguments {
                 do_print_to_stdout: format_do_print_to_stdout,
                 use_verbose_output: format_use_verbose_output,
                 do_check_formatted_only: format_do_check_formatted_only,
+                do_print_diff: format_do_print_diff,
                 use_spaces: format_use_spaces,
                 indent_size: format_indent_size,
                 use_verify_structure: format_use_verify_structure,
+                use_verify_idempotent: format_use_verify_idempotent,
                 do_reorder_code: format_do_reorder_code,
                 max_line_length: format_max_line_length,
                 blank_lines_around_definitions: format_blank_lines_around_definitions,
                 continuation_indent_level: format_continuation_indent_level,
                 quote_style: format_quote_style,
+                line_ending: format_line_ending,
+                line_range: format_line_range,
             },
         },
         ActiveCommand::Lint => CliArguments {
@@ -427,9 +599,17 @@ pub fn parse_args() -> CliArguments {
                 disabled_linter_rules: lint_disabled_rules,
                 max_line_length: lint_max_line_length,
                 do_list_rules: lint_list_rules,
-                do_pretty_print: lint_pretty_print,
+                output_format: lint_output_format,
+                do_fix: lint_do_fix,
+                rule_severities: lint_rule_severities,
+                error_on_severity: lint_error_on_severity,
             },
         },
+        ActiveCommand::LanguageServer => CliArguments {
+            input_file_paths,
+            excluded_paths,
+            command: Command::LanguageServer,
+        },
     }
 }
 
@@ -441,6 +621,21 @@ fn split_flag_and_value(flag: &str) -> (&str, Option<&str>) {
     }
 }
 
+/// Parses a `START:END` line span. Returns `None` if either number is missing,
+/// is zero, or if START is greater than END.
+fn parse_line_range(value: &str) -> Option<LineRange> {
+    let separator_position = value.find(':')?;
+    let first_line = value[..separator_position].parse::<usize>().ok()?;
+    let last_line = value[separator_position + 1..].parse::<usize>().ok()?;
+    if first_line == 0 || first_line > last_line {
+        return None;
+    }
+    Some(LineRange {
+        first_line,
+        last_line,
+    })
+}
+
 /// Resolves the value for a flag that takes an argument.
 ///
 /// If the value was already assigned using an equal sign (e.g. `--flag=value`),
@@ -0,0 +1,82 @@
+//! Measures how many columns text takes up in a code editor.
+//!
+//! Counting one column per `char` doesn't match what users see: CJK
+//! characters and most emoji take two columns, combining marks take none, and
+//! a family emoji is several characters joined by zero-width joiners that
+//! display as one. We measure each grapheme cluster, the unit a user
+//! perceives as one character, by its Unicode display width.
+//!
+//! Tabs advance to the next tab stop, so a tab after two characters with a
+//! tab size of 4 takes two columns, like in code editors.
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Returns the column after writing `text` at `column`. `text` must not
+/// contain line breaks.
+pub fn advance_column(column: usize, text: &str, tab_size: usize) -> usize {
+    let mut column = column;
+    // Most GDScript code is ASCII, so we skip grapheme segmentation for it.
+    if text.is_ascii() {
+        for byte in text.bytes() {
+            if byte == b'\t' {
+                column = next_tab_stop(column, tab_size);
+            } else {
+                column = column.saturating_add(1);
+            }
+        }
+        return column;
+    }
+    for grapheme in text.graphemes(true) {
+        if grapheme == "\t" {
+            column = next_tab_stop(column, tab_size);
+        } else {
+            column = column.saturating_add(grapheme.width());
+        }
+    }
+    column
+}
+
+/// Returns the display width of `text` starting at column 0.
+pub fn text_width(text: &str, tab_size: usize) -> usize {
+    advance_column(0, text, tab_size)
+}
+
+fn next_tab_stop(column: usize, tab_size: usize) -> usize {
+    let tab_size = tab_size.max(1);
+    column.saturating_add(tab_size - column % tab_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabs_advance_to_the_next_tab_stop() {
+        assert_eq!(text_width("\t", 4), 4);
+        assert_eq!(text_width("ab\t", 4), 4);
+        assert_eq!(text_width("abcd\tx", 4), 9);
+        assert_eq!(advance_column(3, "\t", 4), 4);
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(text_width("こんにちは", 4), 10);
+        assert_eq!(text_width("你好, world", 4), 11);
+        assert_eq!(text_width("🎮", 4), 2);
+    }
+
+    #[test]
+    fn combining_marks_and_joined_emoji_take_one_character() {
+        // "e" followed by a combining acute accent.
+        assert_eq!(text_width("e\u{301}", 4), 1);
+        // Family emoji: three emoji joined by zero-width joiners.
+        assert_eq!(text_width("👨\u{200D}👩\u{200D}👧", 4), 2);
+        assert_eq!(text_width("\u{200B}", 4), 0);
+    }
+
+    #[test]
+    fn tabs_after_wide_characters_use_display_columns() {
+        assert_eq!(text_width("你\t", 4), 4);
+        assert_eq!(text_width("你好你\t", 4), 8);
+    }
+}
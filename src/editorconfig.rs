@@ -5,9 +5,11 @@
 //! editorconfig's features. Custom keys are read as plain strings and parsed
 //! manually.
 
-use crate::{FormatterConfiguration, QuoteStyle, linter::LinterConfig};
+use crate::{FormatterConfiguration, LineEnding, QuoteStyle, linter::LinterConfig};
 use ec4rs::Properties;
-use ec4rs::property::{FinalNewline, IndentSize, IndentStyle, MaxLineLen, TrimTrailingWs};
+use ec4rs::property::{
+    EndOfLine, FinalNewline, IndentSize, IndentStyle, MaxLineLen, TrimTrailingWs,
+};
 use std::path::Path;
 
 fn load_editorconfig_properties(editorconfig_file_path: &Path) -> Option<Properties> {
@@ -66,6 +68,13 @@ pub fn apply_editorconfig_to_formatter_config(
         config.printer.trim_trailing_whitespace = trim_trailing_whitespace;
     }
 
+    // Godot doesn't support old Mac line endings, so we ignore `cr`.
+    match properties.get::<EndOfLine>() {
+        Ok(EndOfLine::Lf) => config.printer.line_ending = LineEnding::Lf,
+        Ok(EndOfLine::CrLf) => config.printer.line_ending = LineEnding::CrLf,
+        _ => {}
+    }
+
     // These keys are custom to this program and not part of the standard
     // editorconfig keys, but according to the editor config specification, all
     // values are read which allows us to add custom key value pairs.
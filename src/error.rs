@@ -0,0 +1,198 @@
+//! The error type of the formatter and linter library API.
+//!
+//! Each variant stands for one way formatting or linting can fail, so tools
+//! can tell a file with syntax errors from a formatter bug caught by safe mode
+//! without matching on message text. Messages of the safe mode variants end
+//! with "Keeping original source." as the CLI leaves these files untouched.
+use crate::parser::SyntaxError;
+use crate::verify_structure::{
+    CommentMismatch, ExportGroupMismatch, StructureMismatch, UnmatchedRegion,
+};
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input has syntax errors that prevent the operation. Each error has
+    /// its location in the input.
+    Parse(Vec<SyntaxError>),
+    /// Safe mode: the formatted output has syntax errors the input didn't
+    /// have. Locations point into the formatted output.
+    OutputParse(Vec<SyntaxError>),
+    /// Safe mode: the formatted output parses to a different structure than
+    /// the input. Holds the first node where the two trees differ.
+    StructureMismatch(StructureMismatch),
+    /// Safe mode: the formatter changed code it should have kept as is
+    /// because it contains syntax errors. `line` is the first line of that
+    /// code in the input.
+    SyntaxErrorCodeChanged { line: usize },
+    /// Safe mode: writing the output with its line ending would change the
+    /// line breaks inside a string, and so its value. This happens in files
+    /// with mixed line endings or when converting the line endings. `line` is
+    /// the first line of the string in the input.
+    StringLineEndingChanged { line: usize },
+    /// Safe mode: the formatted output lost a comment or has an extra one.
+    CommentMismatch(CommentMismatch),
+    /// Reordering code moved an exported variable to a different inspector
+    /// group. We check this every time we reorder code, even without safe
+    /// mode.
+    ExportGroupMismatch(ExportGroupMismatch),
+    /// Reordering code left a `#region` marker without its matching
+    /// `#endregion`, or the reverse. We check this every time we reorder code.
+    UnmatchedRegion(UnmatchedRegion),
+    /// Formatting the formatted output again changed it. `diff` is a unified
+    /// diff from the first pass to the second.
+    NotIdempotent { diff: String },
+    /// Invalid configuration, like an unreadable project configuration file
+    /// or a parser that failed to load the GDScript grammar and produced no
+    /// syntax tree.
+    Config(String),
+    /// Reading or writing a file failed.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+}
+
+impl Error {
+    /// The error for a parser that produced no syntax tree. Tree-sitter always
+    /// produces a tree, even for invalid code, unless it can't use the
+    /// GDScript grammar.
+    pub(crate) fn parser_failed() -> Self {
+        Error::Config(
+            "Failed to parse GDScript code: the parser produced no syntax tree".to_string(),
+        )
+    }
+
+    /// Formats the error for a file, with the file path in front of the
+    /// location of each syntax error so terminals and editors can link to it.
+    pub fn format(&self, file_path: &str) -> String {
+        let mut message = String::new();
+        self.write_message(Some(file_path), &mut message);
+        message
+    }
+
+    /// Returns the syntax errors the error reports, if any.
+    pub fn syntax_errors(&self) -> &[SyntaxError] {
+        match self {
+            Error::Parse(syntax_errors) | Error::OutputParse(syntax_errors) => syntax_errors,
+            _ => &[],
+        }
+    }
+
+    fn write_message(&self, file_path: Option<&str>, message: &mut String) {
+        match self {
+            Error::Parse(syntax_errors) if syntax_errors.is_empty() => {
+                message.push_str("Failed to parse input");
+            }
+            Error::Parse(_) => message.push_str(
+                "The input has syntax errors, so the formatter can't verify the output. \
+                 Keeping original source. Fix these errors and format again:",
+            ),
+            Error::OutputParse(_) => message.push_str(
+                "Verify structure: formatted output has syntax errors. Keeping original source.",
+            ),
+            Error::StructureMismatch(mismatch) => {
+                message.push_str(
+                    "Verify structure: formatted output is structurally different from input. \
+                     Keeping original source.\n",
+                );
+                message.push_str(&mismatch.to_string());
+            }
+            Error::SyntaxErrorCodeChanged { line } => message.push_str(&format!(
+                "Verify structure: the formatter changed the code with syntax errors starting \
+                 on line {}. Keeping original source.",
+                line
+            )),
+            Error::StringLineEndingChanged { line } => message.push_str(&format!(
+                "Verify structure: the formatter changed the line endings inside the string \
+                 starting on line {}, which changes its value. Keeping original source.",
+                line
+            )),
+            Error::CommentMismatch(mismatch) => {
+                message.push_str("Verify structure: ");
+                message.push_str(&mismatch.to_string());
+                message.push_str(". Keeping original source.");
+            }
+            Error::ExportGroupMismatch(mismatch) => {
+                message.push_str("Reorder code: ");
+                message.push_str(&mismatch.to_string());
+                message.push_str(". Keeping original source.");
+            }
+            Error::UnmatchedRegion(unmatched_region) => {
+                message.push_str("Reorder code: ");
+                message.push_str(&unmatched_region.to_string());
+                message.push_str(". Keeping original source.");
+            }
+            Error::NotIdempotent { diff } => {
+                message.push_str(
+                    "Verify idempotence: formatting the output again changes it. \
+                     Keeping original source.\n",
+                );
+                message.push_str(diff.trim_end());
+            }
+            Error::Config(config_message) => message.push_str(config_message),
+            Error::Io { path, error } => {
+                message.push_str(&format!(
+                    "Failed to access file {}: {}",
+                    path.display(),
+                    error
+                ));
+            }
+        }
+
+        for syntax_error in self.syntax_errors() {
+            message.push('\n');
+            match file_path {
+                Some(file_path) => message.push_str(&syntax_error.format(file_path)),
+                None => message.push_str(&syntax_error.to_string()),
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut message = String::new();
+        self.write_message(None, &mut message);
+        formatter.write_str(&message)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_syntax_errors_with_file_path() {
+        let error = Error::Parse(vec![SyntaxError {
+            line: 2,
+            column: 12,
+            end_line: 2,
+            end_column: 13,
+            message: "unexpected `:`".to_string(),
+            snippet: "func broken(:".to_string(),
+        }]);
+
+        assert_eq!(
+            error.format("player.gd"),
+            "The input has syntax errors, so the formatter can't verify the output. \
+             Keeping original source. Fix these errors and format again:\n\
+             player.gd:2:12: syntax error: unexpected `:`\n    func broken(:\n               ^"
+        );
+        assert!(
+            error
+                .to_string()
+                .contains("\n2:12: syntax error: unexpected `:`\n")
+        );
+    }
+}
@@ -211,6 +211,95 @@ fn has_own_annotations_child(node: tree_sitter::Node) -> bool {
     false
 }
 
+/// Returns true for the blocks of statements the formatter processes one
+/// statement at a time: the source file, class bodies, and code blocks. Lambda
+/// bodies don't count as their layout depends on the expression around them.
+fn is_statement_block(node: tree_sitter::Node) -> bool {
+    let kind = GDScriptNodeKind::get_kind_from_ast_node(node);
+    matches!(
+        kind,
+        GDScriptNodeKind::Body | GDScriptNodeKind::ClassBody | GDScriptNodeKind::MatchBody
+    ) && node.parent().is_none_or(|parent| {
+        GDScriptNodeKind::get_kind_from_ast_node(parent) != GDScriptNodeKind::Lambda
+    })
+}
+
+/// Returns true if a statement contains a syntax error the formatter can't
+/// work around. Tree-sitter recovers from syntax errors with ERROR and MISSING
+/// nodes, and formatting around them would rely on AST relationships that may
+/// no longer describe the source, so we keep such statements as they are.
+///
+/// Errors inside a statement block don't count: the formatter formats the
+/// statement normally and keeps only the statements of the block that contain
+/// the errors as they are. For example, a function with a broken line in its
+/// body gets its header and all its other lines formatted.
+fn has_error_outside_statement_blocks(node: tree_sitter::Node) -> bool {
+    if node.is_error() || node.is_missing() {
+        return true;
+    }
+    if !node.has_error() {
+        return false;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error()
+            && !is_statement_block(child)
+            && has_error_outside_statement_blocks(child)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns the source ranges the formatter outputs as they are because they
+/// contain syntax errors: the smallest statements or declarations around each
+/// error, in source order. Safe mode uses them to check that the formatter
+/// left these ranges untouched.
+pub fn find_ranges_kept_for_syntax_errors(input: &ParseInput) -> Vec<RangeSourceBytes> {
+    let mut ranges = Vec::new();
+    if input.has_parse_errors {
+        collect_ranges_kept_for_syntax_errors(input.tree.root_node(), &mut ranges);
+    }
+    ranges
+}
+
+fn collect_ranges_kept_for_syntax_errors(
+    statement_block: tree_sitter::Node,
+    ranges: &mut Vec<RangeSourceBytes>,
+) {
+    let mut cursor = statement_block.walk();
+    for statement in statement_block.children(&mut cursor) {
+        if has_error_outside_statement_blocks(statement) {
+            ranges.push(RangeSourceBytes {
+                start_byte: statement.start_byte(),
+                end_byte: statement.end_byte(),
+            });
+        } else if statement.has_error() {
+            collect_nested_ranges_kept_for_syntax_errors(statement, ranges);
+        }
+    }
+}
+
+/// Finds the statement blocks with syntax errors inside a statement that the
+/// formatter formats, like the body of a function or of an if statement.
+fn collect_nested_ranges_kept_for_syntax_errors(
+    node: tree_sitter::Node,
+    ranges: &mut Vec<RangeSourceBytes>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if !child.has_error() {
+            continue;
+        }
+        if is_statement_block(child) {
+            collect_ranges_kept_for_syntax_errors(child, ranges);
+        } else {
+            collect_nested_ranges_kept_for_syntax_errors(child, ranges);
+        }
+    }
+}
+
 /// Checks the type of an AST node and passes it to the formatter builder
 /// function that handles this node kind. This function is called recursively
 /// to process all children of the AST node.
@@ -260,7 +349,13 @@ fn process_node(
             let string_source = &input.source[node.start_byte()..node.end_byte()];
             if let Some(formatted_string) = format_string_literal(string_source, input.quote_style)
             {
-                render_elements.push(RenderElement::TextProducedByFormatter(formatted_string));
+                render_elements.push(RenderElement::TextProducedByFormatter {
+                    text: formatted_string,
+                    range: RangeSourceBytes {
+                        start_byte: node.start_byte(),
+                        end_byte: node.end_byte(),
+                    },
+                });
                 return;
             }
         }
@@ -337,6 +432,9 @@ fn process_node(
                 process_container(input, node, render_elements);
             }
         }
+        GDScriptNodeKind::ClassBody if can_reorder_code(input) => {
+            process_class_body_reorder(input, node, render_elements)
+        }
         GDScriptNodeKind::Body | GDScriptNodeKind::ClassBody | GDScriptNodeKind::MatchBody => {
             process_body(input, node, render_elements, false)
         }
@@ -419,55 +517,165 @@ fn process_function(
 
 /// Returns the string with the preferred string delimiters if the user used the
 /// option to prefer a specific quote style (' or "). Returns `None` when the
-/// original string already uses the preferred quote style to avoid unnecessary
+/// original string already uses the preferred quote style, or when changing
+/// the delimiters would change the string's value, to avoid unnecessary
 /// processing.
+///
+/// To change delimiters, we read the contents into characters and escape
+/// sequences, then write them back with the new delimiter: quotes that match
+/// the new delimiter get escaped, and escaped quotes that don't need it
+/// anymore lose their backslash. Raw strings don't process escapes, so we
+/// only change their delimiters when no quote needs escaping.
 fn format_string_literal(source: &str, quote_style: QuoteStyle) -> Option<String> {
+    // String names (&), node paths (^), and raw strings (r) have a prefix
+    // before the opening quote.
+    let prefix_length = source
+        .bytes()
+        .take_while(|byte| matches!(byte, b'&' | b'^' | b'r'))
+        .count();
+    let prefix = &source[..prefix_length];
+    let is_raw = prefix.contains('r');
+    let source_quote = source[prefix_length..].chars().next()?;
+    let is_triple_quoted =
+        source[prefix_length..].starts_with("\"\"\"") || source[prefix_length..].starts_with("'''");
+    let delimiter_length = if is_triple_quoted { 3 } else { 1 };
+    let content_start = prefix_length + delimiter_length;
+    let content_end = source.len().checked_sub(delimiter_length)?;
+    if content_end < content_start {
+        return None;
+    }
+    let parts = split_string_content(&source[content_start..content_end], is_raw);
+
+    let other_quote = if source_quote == '"' { '\'' } else { '"' };
     let preferred_quote = match quote_style {
-        QuoteStyle::Single => b'\'',
-        QuoteStyle::Double => b'"',
+        QuoteStyle::Single => '\'',
+        QuoteStyle::Double => '"',
+        QuoteStyle::MinimizeEscapes => {
+            let source_escape_count =
+                count_escaped_quotes(&parts, source_quote, is_triple_quoted, is_raw);
+            let other_escape_count =
+                count_escaped_quotes(&parts, other_quote, is_triple_quoted, is_raw);
+            match (source_escape_count, other_escape_count) {
+                (Some(source_count), Some(other_count)) if other_count < source_count => {
+                    other_quote
+                }
+                _ => source_quote,
+            }
+        }
         QuoteStyle::Preserve => return None,
     };
-    let prefix_length = if source.starts_with('&') || source.starts_with('^') {
-        1
-    } else {
-        0
-    };
-    let source_quote = source.as_bytes()[prefix_length];
     if source_quote == preferred_quote {
         return None;
     }
 
-    let delimiter_length = if source[prefix_length..].starts_with("\"\"\"")
-        || source[prefix_length..].starts_with("'''")
-    {
-        3
-    } else {
-        1
-    };
-    let preferred_delimiter = match (preferred_quote, delimiter_length) {
-        (b'\'', 1) => "'",
-        (b'\'', 3) => "'''",
-        (b'"', 1) => "\"",
-        (b'"', 3) => "\"\"\"",
-        _ => unreachable!(),
-    };
-    let content_start = prefix_length + delimiter_length;
-    let content_end = source.len() - delimiter_length;
-    let content = &source[content_start..content_end];
+    let mut output = String::with_capacity(source.len() + 2);
+    output.push_str(prefix);
+    for _ in 0..delimiter_length {
+        output.push(preferred_quote);
+    }
+    for (index, part) in parts.iter().enumerate() {
+        match part {
+            StringContentPart::Character(character) => {
+                if must_escape_quote(&parts, index, preferred_quote, is_triple_quoted) {
+                    if is_raw {
+                        return None;
+                    }
+                    output.push('\\');
+                }
+                output.push(*character);
+            }
+            StringContentPart::EscapeSequence(text) => output.push_str(text),
+        }
+    }
+    for _ in 0..delimiter_length {
+        output.push(preferred_quote);
+    }
+    Some(output)
+}
 
-    // For now we don't switch delimiters when the contents include the
-    // preferred quote signs. This would require parsing and editing the string
-    // to rewrite escapes. Can be implemented later if users need it.
-    if content.contains(preferred_delimiter) {
-        return None;
+/// A piece of the contents of a string literal.
+enum StringContentPart<'a> {
+    /// A character of the string's value. Escaped quotes in regular strings
+    /// become characters, as whether they need a backslash depends on the
+    /// delimiter.
+    Character(char),
+    /// An escape sequence we copy as is, like `\n` or `\\`.
+    EscapeSequence(&'a str),
+}
+
+/// Splits the contents of a string literal, without its delimiters, into
+/// characters and escape sequences. In raw strings, a backslash followed by a
+/// quote or a backslash stays in the value, so we copy these pairs as is.
+fn split_string_content(content: &str, is_raw: bool) -> Vec<StringContentPart<'_>> {
+    let mut parts = Vec::with_capacity(content.len());
+    let mut characters = content.char_indices();
+    while let Some((index, character)) = characters.next() {
+        if character != '\\' {
+            parts.push(StringContentPart::Character(character));
+            continue;
+        }
+        let Some((next_index, next_character)) = characters.next() else {
+            parts.push(StringContentPart::EscapeSequence(&content[index..]));
+            break;
+        };
+        if !is_raw && (next_character == '"' || next_character == '\'') {
+            parts.push(StringContentPart::Character(next_character));
+        } else {
+            let escape_end = next_index + next_character.len_utf8();
+            parts.push(StringContentPart::EscapeSequence(
+                &content[index..escape_end],
+            ));
+        }
     }
+    parts
+}
 
-    let mut output = String::with_capacity(source.len());
-    output.push_str(&source[..prefix_length]);
-    output.push_str(preferred_delimiter);
-    output.push_str(content);
-    output.push_str(preferred_delimiter);
-    Some(output)
+/// Returns true if the quote character at `index` needs a backslash in a
+/// string delimited by `quote`. In triple-quoted strings, we only escape
+/// quotes that would otherwise start a closing delimiter: the first of three
+/// quotes in a row, and quotes at the end of the contents.
+fn must_escape_quote(
+    parts: &[StringContentPart],
+    index: usize,
+    quote: char,
+    is_triple_quoted: bool,
+) -> bool {
+    let is_quote = |part: &StringContentPart| is_quote_character(part, quote);
+    if !is_quote(&parts[index]) {
+        return false;
+    }
+    if !is_triple_quoted {
+        return true;
+    }
+    let following_parts = &parts[index + 1..];
+    let starts_three_quotes = following_parts.len() >= 2
+        && is_quote(&following_parts[0])
+        && is_quote(&following_parts[1]);
+    starts_three_quotes || following_parts.iter().all(is_quote)
+}
+
+fn is_quote_character(part: &StringContentPart, quote: char) -> bool {
+    matches!(part, StringContentPart::Character(character) if *character == quote)
+}
+
+/// Returns how many quotes need a backslash with `quote` as the delimiter, or
+/// None if the string is raw and would need one.
+fn count_escaped_quotes(
+    parts: &[StringContentPart],
+    quote: char,
+    is_triple_quoted: bool,
+    is_raw: bool,
+) -> Option<usize> {
+    let mut count = 0;
+    for index in 0..parts.len() {
+        if must_escape_quote(parts, index, quote, is_triple_quoted) {
+            if is_raw {
+                return None;
+            }
+            count += 1;
+        }
+    }
+    Some(count)
 }
 
 /// When the node's child located at start_index onward are annotations, this
@@ -802,7 +1010,18 @@ fn process_body(
             }
         }
 
-        process_node(input, child, render_elements);
+        // Like in process_source(), we keep statements with syntax errors
+        // intact and format the valid statements around them.
+        if input.has_parse_errors && has_error_outside_statement_blocks(child) {
+            render_elements.push(RenderElement::UnformattedSource {
+                range: RangeSourceBytes {
+                    start_byte: child.start_byte(),
+                    end_byte: child.end_byte(),
+                },
+            });
+        } else {
+            process_node(input, child, render_elements);
+        }
         if current_child_kind == GDScriptNodeKind::Comment {
             // Check if this comment sits on the same line as the previous child.
             // To do so, we check if there's a newline between the end of the previous
@@ -949,6 +1168,12 @@ fn classify_disabled_region_overlap(
     })
 }
 
+/// Returns true if the formatter should reorder declarations in the script and
+/// its inner classes. We don't reorder code with syntax errors.
+fn can_reorder_code(input: &ParseInput) -> bool {
+    input.reorder_code && !input.has_parse_errors
+}
+
 /// Starts formatting code from the source node (which is the topmost
 /// tree-sitter AST node).
 fn process_source(
@@ -956,18 +1181,9 @@ fn process_source(
     node: tree_sitter::Node,
     render_elements: &mut Vec<RenderElement>,
 ) {
-    // Reordering moves declarations around based on their kind, which would
-    // pull code into or out of a # fmt: off disabled region. For now we
-    // skip reordering for disabled regions, but in the future we may want
-    // to reorder code around disabled regions as well?
-    if input.reorder_code && !input.has_parse_errors {
-        if input.disabled_regions.is_empty() {
-            process_source_reorder(input, node, render_elements);
-            return;
-        }
-        println!(
-            "The code uses disabled regions. Reordering is currently incompatible with disabled formatting as it can span any lines and reordering may break the disabled regions. Skipping reordering."
-        );
+    if can_reorder_code(input) {
+        process_source_reorder(input, node, render_elements);
+        return;
     }
     let source = input.source;
     let child_count = node.child_count();
@@ -994,7 +1210,10 @@ fn process_source(
         // in an ERROR node. Formatting inside that subtree would rely on AST
         // relationships that may no longer describe the source. Keep the
         // malformed declaration intact while formatting its valid siblings.
-        let contains_parse_error = input.has_parse_errors && child.has_error();
+        // Declarations with errors only in their body get formatted, and
+        // process_body() keeps the malformed statements intact.
+        let contains_parse_error =
+            input.has_parse_errors && has_error_outside_statement_blocks(child);
 
         // This code is similar to the one in process_body(). See comments
         // there for some explanation of what this does and why it's needed.
@@ -3142,14 +3361,46 @@ fn process_source_reorder(
     node: tree_sitter::Node,
     render_elements: &mut Vec<RenderElement>,
 ) {
-    let source = input.source;
-    let plan = reorder::build_reorder_plan(node, source);
+    let plan =
+        reorder::build_reorder_plan(node, input.source, &input.disabled_regions, &input.reorder);
     if plan.items.is_empty() {
         // Without a declaration, standalone annotations have nothing to attach
         // to.
         process_children_with_spacing(input, node, render_elements);
         return;
     }
+    process_reorder_plan(input, node, &plan, render_elements);
+}
+
+/// Formats an inner class body when the reorder_code option is enabled. Inner
+/// classes follow the same declaration order as the script, and nested inner
+/// classes get reordered when we format their own bodies.
+fn process_class_body_reorder(
+    input: &ParseInput,
+    node: tree_sitter::Node,
+    render_elements: &mut Vec<RenderElement>,
+) {
+    let plan =
+        reorder::build_reorder_plan(node, input.source, &input.disabled_regions, &input.reorder);
+    if plan.items.is_empty() {
+        process_body(input, node, render_elements, false);
+        return;
+    }
+    let indent_index = begin_indent(render_elements, 1);
+    process_reorder_plan(input, node, &plan, render_elements);
+    finish_indent(render_elements, indent_index);
+}
+
+/// Outputs the children of `node` in the order of the reorder plan, with the
+/// comments and annotations attached to each declaration, and the blank lines
+/// between declaration groups.
+fn process_reorder_plan(
+    input: &ParseInput,
+    node: tree_sitter::Node,
+    plan: &reorder::ReorderPlan,
+    render_elements: &mut Vec<RenderElement>,
+) {
+    let source = input.source;
     let mut previous_classification: Option<DeclarationKind> = None;
     let mut previous_is_double_spaced = false;
     let mut previous_child_index: Option<usize> = None;
@@ -3220,8 +3471,19 @@ fn process_source_reorder(
             {
                 let child_index_attached_before_declaration = item
                     .child_indices_attached_before_declaration[attached_before_declaration_index];
-                if let Some(child) = node.child(child_index_attached_before_declaration as u32) {
-                    process_node(input, child, render_elements);
+                if node
+                    .child(child_index_attached_before_declaration as u32)
+                    .is_some()
+                {
+                    process_reorder_plan_child(
+                        input,
+                        node,
+                        plan,
+                        child_index_attached_before_declaration,
+                        render_elements,
+                    );
+                    let child_end_byte =
+                        plan.child_end_byte(node, child_index_attached_before_declaration);
                     let next_child_start_byte = if attached_before_declaration_index + 1
                         < item.child_indices_attached_before_declaration.len()
                     {
@@ -3235,7 +3497,7 @@ fn process_source_reorder(
                     } else {
                         declaration_start_byte
                     };
-                    if has_newline(source, child.end_byte(), next_child_start_byte) {
+                    if has_newline(source, child_end_byte, next_child_start_byte) {
                         render_elements.push(RenderElement::HardLine);
                     } else {
                         render_elements.push(RenderElement::Space);
@@ -3250,8 +3512,14 @@ fn process_source_reorder(
             while docstring_index < item.child_indices_attached_before_declaration.len() {
                 let docstring_child_index =
                     item.child_indices_attached_before_declaration[docstring_index];
-                if let Some(child) = node.child(docstring_child_index as u32) {
-                    process_node(input, child, render_elements);
+                if node.child(docstring_child_index as u32).is_some() {
+                    process_reorder_plan_child(
+                        input,
+                        node,
+                        plan,
+                        docstring_child_index,
+                        render_elements,
+                    );
                     render_elements.push(RenderElement::HardLine);
                 }
                 docstring_index += 1;
@@ -3292,14 +3560,11 @@ fn process_source_reorder(
                     child_index += 1;
                 }
             } else {
-                process_node(input, child, render_elements);
+                process_reorder_plan_child(input, node, plan, item.child_index, render_elements);
             }
         }
 
-        let declaration_end_byte: usize = match node.child(item.child_index as u32) {
-            Some(child) => child.end_byte(),
-            None => 0,
-        };
+        let declaration_end_byte = plan.child_end_byte(node, item.child_index);
         let mut attached_after_declaration_index = 0;
         while attached_after_declaration_index < item.child_indices_attached_after_declaration.len()
         {
@@ -3311,7 +3576,13 @@ fn process_source_reorder(
                 } else {
                     render_elements.push(RenderElement::Space);
                 }
-                process_node(input, child, render_elements);
+                process_reorder_plan_child(
+                    input,
+                    node,
+                    plan,
+                    child_index_attached_after_declaration,
+                    render_elements,
+                );
             }
             attached_after_declaration_index += 1;
         }
@@ -3319,6 +3590,50 @@ fn process_source_reorder(
     }
 }
 
+/// Outputs the child of `node` at `child_index` for the reorder plan. If a
+/// disabled region unit starts at this child, outputs all the unit's children
+/// the same way process_source() does: covered code as it is in the source,
+/// and children the region only partially covers formatted.
+fn process_reorder_plan_child(
+    input: &ParseInput,
+    node: tree_sitter::Node,
+    plan: &reorder::ReorderPlan,
+    child_index: usize,
+    render_elements: &mut Vec<RenderElement>,
+) {
+    let Some(unit) = plan.disabled_region_unit_at(child_index) else {
+        if let Some(child) = node.child(child_index as u32) {
+            process_node(input, child, render_elements);
+        }
+        return;
+    };
+    let mut current_index = unit.first_child_index;
+    while current_index <= unit.last_child_index {
+        let Some(child) = node.child(current_index as u32) else {
+            current_index += 1;
+            continue;
+        };
+        match classify_disabled_region_overlap(input, node, child, current_index) {
+            DisabledRegionOverlapKind::CoveredFully(disabled_run) => {
+                let region = disabled_run.region;
+                if child.start_byte() == region.start {
+                    render_elements.push(RenderElement::UnformattedSource {
+                        range: RangeSourceBytes {
+                            start_byte: region.start,
+                            end_byte: region.end,
+                        },
+                    });
+                }
+                current_index = disabled_run.last_covered_index + 1;
+            }
+            DisabledRegionOverlapKind::PartiallyCovered | DisabledRegionOverlapKind::None => {
+                process_node(input, child, render_elements);
+                current_index += 1;
+            }
+        }
+    }
+}
+
 /// Entry point for formatting a code file. Takes the root parsed GDScript AST
 /// node and starts walking through the AST. This function directly populates
 /// the `render_elements` argument passed in with tokens that form an intermediate
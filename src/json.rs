@@ -0,0 +1,446 @@
+//! Minimal JSON reading and writing.
+//!
+//! The language server reads JSON-RPC messages and the linter writes
+//! machine-readable reports. Both only need a small subset of what a JSON
+//! library offers, so we keep this module instead of adding serde_json, which
+//! would bring serde and its derive macros into the builds of the formatter
+//! and of the Godot add-on.
+//!
+//! This module is only public because the language server lives in the
+//! command line binary, which uses the library like any other crate. It's
+//! hidden from the documentation and isn't part of the library's stable API:
+//! it can change in any release.
+//!
+//! Objects keep their members in insertion order, which makes the output
+//! deterministic and easy to test.
+
+/// A parsed JSON value, or a value to write as JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<JsonMember>),
+}
+
+/// A key-value pair in a JSON object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonMember {
+    pub key: String,
+    pub value: JsonValue,
+}
+
+impl JsonMember {
+    pub fn new(key: &str, value: JsonValue) -> Self {
+        Self {
+            key: key.to_string(),
+            value,
+        }
+    }
+}
+
+impl JsonValue {
+    /// Parses a complete JSON document. Returns an error message with the byte
+    /// offset of the first invalid character.
+    pub fn parse(text: &str) -> Result<JsonValue, String> {
+        let mut parser = Parser {
+            bytes: text.as_bytes(),
+            text,
+            current_index: 0,
+        };
+        parser.skip_whitespace();
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.current_index != parser.bytes.len() {
+            return Err(parser.error("unexpected trailing characters"));
+        }
+        Ok(value)
+    }
+
+    pub fn string(text: &str) -> JsonValue {
+        JsonValue::String(text.to_string())
+    }
+
+    pub fn number(value: usize) -> JsonValue {
+        JsonValue::Number(value as f64)
+    }
+
+    /// Returns the value of the member named `key` if this is an object.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        let JsonValue::Object(members) = self else {
+            return None;
+        };
+        for member in members {
+            if member.key == key {
+                return Some(&member.value);
+            }
+        }
+        None
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as a `usize` if it is a non-negative integer.
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            JsonValue::Number(value) if *value >= 0.0 && value.fract() == 0.0 => {
+                Some(*value as usize)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Appends the compact JSON text of this value to `output`.
+    pub fn write(&self, output: &mut String) {
+        match self {
+            JsonValue::Null => output.push_str("null"),
+            JsonValue::Bool(true) => output.push_str("true"),
+            JsonValue::Bool(false) => output.push_str("false"),
+            JsonValue::Number(value) => {
+                // Integers are by far the most common numbers we write (lines,
+                // columns, request ids). We write them without a decimal part.
+                if value.fract() == 0.0 && value.abs() < 1e15 {
+                    output.push_str(&(*value as i64).to_string());
+                } else {
+                    output.push_str(&value.to_string());
+                }
+            }
+            JsonValue::String(text) => write_json_string(text, output),
+            JsonValue::Array(values) => {
+                output.push('[');
+                for (current_index, value) in values.iter().enumerate() {
+                    if current_index > 0 {
+                        output.push(',');
+                    }
+                    value.write(output);
+                }
+                output.push(']');
+            }
+            JsonValue::Object(members) => {
+                output.push('{');
+                for (current_index, member) in members.iter().enumerate() {
+                    if current_index > 0 {
+                        output.push(',');
+                    }
+                    write_json_string(&member.key, output);
+                    output.push(':');
+                    member.value.write(output);
+                }
+                output.push('}');
+            }
+        }
+    }
+
+    pub fn to_json_string(&self) -> String {
+        let mut output = String::new();
+        self.write(&mut output);
+        output
+    }
+}
+
+/// Appends `text` to `output` as a quoted JSON string, escaping quotes,
+/// backslashes, and control characters.
+pub fn write_json_string(text: &str, output: &mut String) {
+    output.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                output.push_str(&format!("\\u{:04x}", character as u32));
+            }
+            character => output.push(character),
+        }
+    }
+    output.push('"');
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    text: &'a str,
+    current_index: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        format!("Invalid JSON at byte {}: {}", self.current_index, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.current_index < self.bytes.len()
+            && matches!(self.bytes[self.current_index], b' ' | b'\t' | b'\n' | b'\r')
+        {
+            self.current_index += 1;
+        }
+    }
+
+    fn consume_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, String> {
+        if self.text[self.current_index..].starts_with(literal) {
+            self.current_index += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("unknown literal"))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, String> {
+        let Some(&byte) = self.bytes.get(self.current_index) else {
+            return Err(self.error("unexpected end of input"));
+        };
+        match byte {
+            b'n' => self.consume_literal("null", JsonValue::Null),
+            b't' => self.consume_literal("true", JsonValue::Bool(true)),
+            b'f' => self.consume_literal("false", JsonValue::Bool(false)),
+            b'"' => Ok(JsonValue::String(self.parse_string()?)),
+            b'[' => self.parse_array(),
+            b'{' => self.parse_object(),
+            b'-' | b'0'..=b'9' => self.parse_number(),
+            _ => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, String> {
+        let start = self.current_index;
+        while self.current_index < self.bytes.len()
+            && matches!(
+                self.bytes[self.current_index],
+                b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'
+            )
+        {
+            self.current_index += 1;
+        }
+        match self.text[start..self.current_index].parse::<f64>() {
+            Ok(value) => Ok(JsonValue::Number(value)),
+            Err(_) => Err(self.error("invalid number")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        // Skip the opening quote.
+        self.current_index += 1;
+        let mut result = String::new();
+        loop {
+            let Some(&byte) = self.bytes.get(self.current_index) else {
+                return Err(self.error("unterminated string"));
+            };
+            match byte {
+                b'"' => {
+                    self.current_index += 1;
+                    return Ok(result);
+                }
+                b'\\' => {
+                    self.current_index += 1;
+                    let Some(&escaped) = self.bytes.get(self.current_index) else {
+                        return Err(self.error("unterminated escape sequence"));
+                    };
+                    self.current_index += 1;
+                    match escaped {
+                        b'"' => result.push('"'),
+                        b'\\' => result.push('\\'),
+                        b'/' => result.push('/'),
+                        b'b' => result.push('\u{8}'),
+                        b'f' => result.push('\u{c}'),
+                        b'n' => result.push('\n'),
+                        b'r' => result.push('\r'),
+                        b't' => result.push('\t'),
+                        b'u' => result.push(self.parse_unicode_escape()?),
+                        _ => return Err(self.error("invalid escape sequence")),
+                    }
+                }
+                _ => {
+                    // Copy the whole run of unescaped characters at once so
+                    // multi-byte UTF-8 characters stay intact.
+                    let start = self.current_index;
+                    while self.current_index < self.bytes.len()
+                        && self.bytes[self.current_index] != b'"'
+                        && self.bytes[self.current_index] != b'\\'
+                    {
+                        self.current_index += 1;
+                    }
+                    result.push_str(&self.text[start..self.current_index]);
+                }
+            }
+        }
+    }
+
+    /// Parses the four hex digits after `\u`, combining UTF-16 surrogate pairs.
+    fn parse_unicode_escape(&mut self) -> Result<char, String> {
+        let first_unit = self.parse_hex_code_unit()?;
+        if !(0xD800..0xDC00).contains(&first_unit) {
+            return char::from_u32(first_unit).ok_or_else(|| self.error("invalid code point"));
+        }
+        if !self.text[self.current_index..].starts_with("\\u") {
+            return Err(self.error("unpaired surrogate"));
+        }
+        self.current_index += 2;
+        let second_unit = self.parse_hex_code_unit()?;
+        if !(0xDC00..0xE000).contains(&second_unit) {
+            return Err(self.error("unpaired surrogate"));
+        }
+        let code_point = 0x10000 + ((first_unit - 0xD800) << 10) + (second_unit - 0xDC00);
+        char::from_u32(code_point).ok_or_else(|| self.error("invalid code point"))
+    }
+
+    fn parse_hex_code_unit(&mut self) -> Result<u32, String> {
+        let end = self.current_index + 4;
+        if end > self.bytes.len() {
+            return Err(self.error("truncated unicode escape"));
+        }
+        // We check the digits first: `from_str_radix` accepts a leading `+`,
+        // and slicing the text could split a multi-byte character.
+        let digits = &self.bytes[self.current_index..end];
+        if !digits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(self.error("invalid unicode escape"));
+        }
+        let code_unit = digits.iter().fold(0, |code_unit, &digit| {
+            // The digit is valid hex, so `to_digit` always succeeds.
+            code_unit * 16 + char::from(digit).to_digit(16).unwrap_or(0)
+        });
+        self.current_index = end;
+        Ok(code_unit)
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, String> {
+        // Skip the opening bracket.
+        self.current_index += 1;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.current_index) == Some(&b']') {
+            self.current_index += 1;
+            return Ok(JsonValue::Array(values));
+        }
+        loop {
+            self.skip_whitespace();
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.bytes.get(self.current_index) {
+                Some(b',') => self.current_index += 1,
+                Some(b']') => {
+                    self.current_index += 1;
+                    return Ok(JsonValue::Array(values));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, String> {
+        // Skip the opening brace.
+        self.current_index += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.current_index) == Some(&b'}') {
+            self.current_index += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.bytes.get(self.current_index) != Some(&b'"') {
+                return Err(self.error("expected a string key"));
+            }
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            if self.bytes.get(self.current_index) != Some(&b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.current_index += 1;
+            self.skip_whitespace();
+            let value = self.parse_value()?;
+            members.push(JsonMember { key, value });
+            self.skip_whitespace();
+            match self.bytes.get(self.current_index) {
+                Some(b',') => self.current_index += 1,
+                Some(b'}') => {
+                    self.current_index += 1;
+                    return Ok(JsonValue::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values() {
+        let value = JsonValue::parse(
+            r#"{"id": 1, "params": {"text": "a\n\"b\"", "items": [true, null, -2.5]}}"#,
+        )
+        .expect("valid JSON should parse");
+
+        assert_eq!(value.get("id").and_then(JsonValue::as_usize), Some(1));
+        let params = value.get("params").expect("params should exist");
+        assert_eq!(
+            params.get("text").and_then(JsonValue::as_str),
+            Some("a\n\"b\"")
+        );
+        assert_eq!(
+            params.get("items"),
+            Some(&JsonValue::Array(vec![
+                JsonValue::Bool(true),
+                JsonValue::Null,
+                JsonValue::Number(-2.5),
+            ]))
+        );
+    }
+
+    #[test]
+    fn decodes_unicode_escapes_and_surrogate_pairs() {
+        let value = JsonValue::parse(r#""\u00e9\ud83d\ude00""#).expect("valid JSON should parse");
+        assert_eq!(value.as_str(), Some("é😀"));
+    }
+
+    #[test]
+    fn rejects_invalid_unicode_escapes() {
+        // The four bytes after `\u` end in the middle of the two bytes of "é".
+        assert!(JsonValue::parse("\"\\u000\u{e9}\"").is_err());
+        assert!(JsonValue::parse(r#""\u+0e9""#).is_err());
+        assert!(JsonValue::parse(r#""\u00""#).is_err());
+    }
+
+    #[test]
+    fn writes_compact_json_with_escapes() {
+        let value = JsonValue::Object(vec![
+            JsonMember::new("line", JsonValue::number(3)),
+            JsonMember::new("text", JsonValue::string("tab\there \"quoted\"")),
+        ]);
+        assert_eq!(
+            value.to_json_string(),
+            r#"{"line":3,"text":"tab\there \"quoted\""}"#
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        assert!(JsonValue::parse("{\"a\": }").is_err());
+        assert!(JsonValue::parse("[1, 2").is_err());
+        assert!(JsonValue::parse("true false").is_err());
+    }
+}
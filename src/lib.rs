@@ -11,19 +11,34 @@
 //! maintain), the formatter builds an intermediate representation of the
 //! formatted code, and the renderer produces the final string.
 //!
+//! To format only part of a file, for example a selection in a code editor,
+//! use [format_gdscript_range].
+//!
 //! If you turn safe mode on, the output is reparsed and an error is returned
-//! if it contains syntax errors. Use this to prevent formatting errors.
+//! if it contains syntax errors. Use this to prevent formatting errors. The
+//! returned [Error] tells why a file couldn't be formatted, with the location
+//! of any syntax errors.
 
+pub mod display_width;
 pub mod editorconfig;
+pub mod error;
 pub mod formatter;
+// Public for the language server in the binary, not part of the stable API.
+#[doc(hidden)]
+pub mod json;
 pub mod linter;
 pub mod node_kind;
 pub mod parser;
+pub mod project_config;
 pub mod renderer;
 pub mod reorder;
 pub mod verify_structure;
 
-pub use renderer::{PrinterConfiguration, RenderElement};
+pub use error::Error;
+pub use renderer::{LineEnding, PrinterConfiguration, RangeSourceBytes, RenderElement};
+pub use reorder::{ReorderConfiguration, ReorderRegionMode, ReorderSortMode};
+
+use node_kind::GDScriptNodeKind;
 
 /// Selects which delimiters the formatter prefers for string literals.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -34,6 +49,9 @@ pub enum QuoteStyle {
     Single,
     /// Prefer double quote delimiters.
     Double,
+    /// Use the delimiter that needs the fewest escaped quotes, keeping the
+    /// source delimiter on ties.
+    MinimizeEscapes,
 }
 
 impl QuoteStyle {
@@ -42,6 +60,7 @@ impl QuoteStyle {
             "preserve" => Some(Self::Preserve),
             "single" => Some(Self::Single),
             "double" => Some(Self::Double),
+            "minimize-escapes" => Some(Self::MinimizeEscapes),
             _ => None,
         }
     }
@@ -54,7 +73,13 @@ impl QuoteStyle {
 pub struct FormatterConfiguration {
     pub printer: PrinterConfiguration,
     pub safe: bool,
+    /// If true, the formatter formats its output a second time and returns
+    /// an error with the diff between the two passes if they differ. Only
+    /// [format_gdscript] and [format_gdscript_with_buffers] run this check.
+    pub verify_idempotent: bool,
     pub reorder_code: bool,
+    /// The order of declarations when `reorder_code` is true.
+    pub reorder: ReorderConfiguration,
     /// Number of blank lines around top-level function and inner class
     /// declarations. We apply 2 by default following the GDScript style guide,
     /// set this to 1 to reduce the number of blank lines.
@@ -69,7 +94,9 @@ impl Default for FormatterConfiguration {
         Self {
             printer: PrinterConfiguration::default(),
             safe: false,
+            verify_idempotent: false,
             reorder_code: false,
+            reorder: ReorderConfiguration::default(),
             blank_lines_around_definitions: 2,
             quote_style: QuoteStyle::Preserve,
         }
@@ -81,7 +108,7 @@ impl Default for FormatterConfiguration {
 ///
 /// For formatting multiple files, prefer [format_gdscript_with_buffers] to
 /// reuse pre-allocated buffers across multiple calls.
-pub fn format_gdscript(source: &str, config: &FormatterConfiguration) -> Result<String, String> {
+pub fn format_gdscript(source: &str, config: &FormatterConfiguration) -> Result<String, Error> {
     let mut render_elements = Vec::new();
     let mut output = String::new();
     format_gdscript_with_buffers(source, config, &mut render_elements, &mut output)?;
@@ -96,37 +123,537 @@ pub fn format_gdscript_with_buffers(
     config: &FormatterConfiguration,
     render_elements: &mut Vec<RenderElement>,
     output: &mut String,
-) -> Result<(), String> {
-    let parsed = parser::ParseInput::new(source, config)
-        .ok_or_else(|| "Failed to parse input".to_string())?;
+) -> Result<(), Error> {
+    let original_source = source;
+    let encoding = parser::SourceEncoding::detect(original_source);
+    let source = parser::normalize_source(original_source);
+    let parsed = parser::ParseInput::new(&source, config).ok_or_else(Error::parser_failed)?;
     formatter::build_formatter_intermediate_representation(&parsed, render_elements);
+    let printer_config = make_printer_configuration(config, encoding);
+    let mut unformatted_sources = Vec::new();
+    renderer::render_tracking_source_offsets(
+        render_elements,
+        &source,
+        &printer_config,
+        &[],
+        output,
+        &mut Vec::new(),
+        &mut unformatted_sources,
+    );
+
+    if config.reorder_code && !parsed.has_parse_errors {
+        verify_reordered_code(&parsed, &parser::normalize_source(output), config)?;
+    }
+    if config.safe {
+        verify_formatted_structure(
+            &parsed,
+            &parser::normalize_source(output),
+            config,
+            &unformatted_sources,
+        )?;
+        verify_string_line_endings(
+            &parsed,
+            &parser::find_crlf_line_breaks(original_source),
+            printer_config.line_ending,
+        )?;
+    }
+    if config.verify_idempotent {
+        verify_idempotent_output(output, config)?;
+    }
 
-    // The renderer clamps every blank-line run to `maximum_blank_lines`. If a
-    // user configures more blank lines around definitions than that cap
-    // allows, the separator the formatter emits between declarations would be
-    // silently truncated back down. Raise the cap to match so the configured
-    // value is always honored.
+    Ok(())
+}
+
+/// Formats only the statements and declarations that overlap `range` and
+/// leaves the rest of the source byte-identical. Use this to format a selection
+/// in a code editor.
+///
+/// The range grows to the smallest list of sibling statements that covers it:
+/// selecting a line inside a function body formats only that statement, while
+/// a selection that spans several functions formats each function completely.
+/// The formatter still builds the intermediate representation for the whole
+/// file so statements get the same indentation and line wrapping as when
+/// formatting the entire file.
+///
+/// Code reordering is ignored here as it moves declarations across the whole
+/// file. Returns the source unchanged when the range only covers whitespace.
+/// If the configured line ending differs from the one of the file, the whole
+/// result uses the configured line ending.
+pub fn format_gdscript_range(
+    original_source: &str,
+    range: &RangeSourceBytes,
+    config: &FormatterConfiguration,
+) -> Result<String, Error> {
+    let mut range_config = config.clone();
+    range_config.reorder_code = false;
+    // We format and splice code with LF line endings and convert the result
+    // at the end, so the range has to point into the normalized source.
+    let encoding = parser::SourceEncoding::detect(original_source);
+    let normalized_source = parser::normalize_source(original_source);
+    let source: &str = &normalized_source;
+    let range = RangeSourceBytes {
+        start_byte: parser::normalize_source_offset(original_source, range.start_byte),
+        end_byte: parser::normalize_source_offset(original_source, range.end_byte),
+    };
+    let parsed = parser::ParseInput::new(source, &range_config).ok_or_else(Error::parser_failed)?;
+    let Some(statements) = find_statements_in_range(&parsed, &range) else {
+        return Ok(original_source.to_string());
+    };
+
+    let mut render_elements = Vec::new();
+    formatter::build_formatter_intermediate_representation(&parsed, &mut render_elements);
+    let output_printer_config = make_printer_configuration(&range_config, encoding);
+    let mut printer_config = output_printer_config.clone();
+    printer_config.line_ending = renderer::LineEnding::Lf;
+    printer_config.byte_order_mark = false;
+    let tracked_source_offsets = [statements.start_byte, statements.end_byte];
+    let mut formatted = String::new();
+    let mut tracked_output_offsets = Vec::with_capacity(tracked_source_offsets.len());
+    let mut unformatted_sources = Vec::new();
+    renderer::render_tracking_source_offsets(
+        &render_elements,
+        source,
+        &printer_config,
+        &tracked_source_offsets,
+        &mut formatted,
+        &mut tracked_output_offsets,
+        &mut unformatted_sources,
+    );
+
+    // The first tracked offset points at the end of the code before the
+    // statements, so we skip the line breaks and indentation that follow it.
+    let mut output_start = tracked_output_offsets[0];
+    let formatted_bytes = formatted.as_bytes();
+    while output_start < formatted_bytes.len()
+        && matches!(formatted_bytes[output_start], b' ' | b'\t' | b'\n')
+    {
+        output_start += 1;
+    }
+    let mut output_end = tracked_output_offsets[1];
+    if output_end < output_start {
+        output_end = output_start;
+    }
+
+    // When the statements start their line, we also replace the indentation in
+    // front of them to normalize it.
+    let mut source_start = statements.start_byte;
+    let source_line_start = find_line_start(source, source_start);
+    let output_line_start = find_line_start(&formatted, output_start);
+    if is_indentation(&source[source_line_start..source_start])
+        && is_indentation(&formatted[output_line_start..output_start])
+    {
+        source_start = source_line_start;
+        output_start = output_line_start;
+    }
+
+    let mut result = String::with_capacity(source.len());
+    result.push_str(&source[..source_start]);
+    result.push_str(&formatted[output_start..output_end]);
+    result.push_str(&source[statements.end_byte..]);
+
+    if config.safe {
+        // The code before and after the statements is the source as is. We
+        // move the unformatted code of the statements to its place in the
+        // result.
+        let formatted_length = output_end - output_start;
+        let mut result_unformatted_sources = vec![
+            renderer::UnformattedSourceOutput {
+                source_start: 0,
+                source_end: source_start,
+                output_start: 0,
+            },
+            renderer::UnformattedSourceOutput {
+                source_start: statements.end_byte,
+                source_end: source.len(),
+                output_start: source_start + formatted_length,
+            },
+        ];
+        for unformatted_source in &unformatted_sources {
+            let length = unformatted_source.source_end - unformatted_source.source_start;
+            if unformatted_source.output_start >= output_start
+                && unformatted_source.output_start + length <= output_end
+            {
+                result_unformatted_sources.push(renderer::UnformattedSourceOutput {
+                    output_start: source_start + unformatted_source.output_start - output_start,
+                    ..*unformatted_source
+                });
+            }
+        }
+        verify_formatted_structure(&parsed, &result, &range_config, &result_unformatted_sources)?;
+        verify_string_line_endings(
+            &parsed,
+            &parser::find_crlf_line_breaks(original_source),
+            output_printer_config.line_ending,
+        )?;
+    }
+
+    renderer::convert_line_endings_and_byte_order_mark(&mut result, &output_printer_config);
+    Ok(result)
+}
+
+/// Converts a span of 1-based, inclusive line numbers into the byte range that
+/// covers these lines, without the last line's line break. Lines past the end
+/// of the source clamp to the end of the source.
+pub fn line_range_to_byte_range(
+    source: &str,
+    first_line: usize,
+    last_line: usize,
+) -> RangeSourceBytes {
+    let mut range = RangeSourceBytes {
+        start_byte: source.len(),
+        end_byte: source.len(),
+    };
+    if first_line <= 1 {
+        range.start_byte = 0;
+    }
+    let mut current_line = 1;
+    for (index, byte) in source.bytes().enumerate() {
+        if byte != b'\n' {
+            continue;
+        }
+        if current_line >= last_line {
+            range.end_byte = index;
+            break;
+        }
+        current_line += 1;
+        if current_line == first_line {
+            range.start_byte = index + 1;
+        }
+    }
+    range
+}
+
+/// Returns a unified diff between the `original` and `formatted` code, with
+/// three lines of context around each change. The headers name the two
+/// sides, usually with the same file path. Returns an empty string if the two
+/// texts are equal.
+pub fn make_unified_diff(
+    original: &str,
+    formatted: &str,
+    original_header: &str,
+    formatted_header: &str,
+) -> String {
+    if original == formatted {
+        return String::new();
+    }
+    similar::TextDiff::from_lines(original, formatted)
+        .unified_diff()
+        .context_radius(3)
+        .header(original_header, formatted_header)
+        .to_string()
+}
+
+/// Copies the printer configuration for rendering.
+///
+/// The renderer clamps every blank-line run to `maximum_blank_lines`. If a
+/// user configures more blank lines around definitions than that cap allows,
+/// the separator the formatter emits between declarations would be silently
+/// truncated back down. We raise the cap to match so the configured value is
+/// always honored.
+///
+/// It also resolves the automatic line ending to the one of the input file
+/// and keeps the input's byte order mark.
+fn make_printer_configuration(
+    config: &FormatterConfiguration,
+    encoding: parser::SourceEncoding,
+) -> PrinterConfiguration {
     let mut printer_config = config.printer.clone();
+    if printer_config.line_ending == renderer::LineEnding::Auto {
+        printer_config.line_ending = encoding.line_ending;
+    }
+    printer_config.byte_order_mark |= encoding.has_byte_order_mark;
     if printer_config.maximum_blank_lines < config.blank_lines_around_definitions {
         printer_config.maximum_blank_lines = config.blank_lines_around_definitions;
     }
-    renderer::render(render_elements, source, &printer_config, output);
+    printer_config
+}
 
-    if config.safe {
-        let reparsed = parser::ParseInput::new(output, config)
-            .ok_or_else(|| "Verify structure: formatted output does not parse".to_string())?;
-        if !verify_structure::trees_structurally_equal(
-            &parsed.tree,
-            &reparsed.tree,
-            parsed.kind_lookup,
-        ) {
-            return Err(
-                "Verify structure: formatted output is structurally different from input. \
-                 Keeping original source."
-                    .to_string(),
-            );
+/// Reparses the formatted output and checks that it has the same structure and
+/// the same comments as the input. `unformatted_sources` tells where the
+/// renderer wrote the code it kept as-is in `output`.
+fn verify_formatted_structure(
+    parsed: &parser::ParseInput,
+    output: &str,
+    config: &FormatterConfiguration,
+    unformatted_sources: &[renderer::UnformattedSourceOutput],
+) -> Result<(), Error> {
+    let reparsed = parser::ParseInput::new(output, config).ok_or_else(Error::parser_failed)?;
+    // Code with syntax errors must come out exactly as it went in, as we can't
+    // check its structure. We compare it to the output where the renderer
+    // wrote it.
+    for range in formatter::find_ranges_kept_for_syntax_errors(parsed) {
+        let kept_code = &parsed.source[range.start_byte..range.end_byte];
+        let kept_output = unformatted_sources
+            .iter()
+            .find(|unformatted_source| {
+                unformatted_source.source_start <= range.start_byte
+                    && range.end_byte <= unformatted_source.source_end
+            })
+            .and_then(|unformatted_source| {
+                let output_start = unformatted_source.output_start + range.start_byte
+                    - unformatted_source.source_start;
+                output.get(output_start..output_start + kept_code.len())
+            });
+        if kept_output != Some(kept_code) {
+            let line = parsed.source[..range.start_byte].matches('\n').count() + 1;
+            return Err(Error::SyntaxErrorCodeChanged { line });
+        }
+    }
+    if !parsed.has_parse_errors && reparsed.has_parse_errors {
+        return Err(Error::OutputParse(parser::find_syntax_errors(
+            reparsed.tree.root_node(),
+            output,
+        )));
+    }
+    if let Some(mismatch) = verify_structure::find_first_structure_mismatch(
+        &parsed.tree,
+        &reparsed.tree,
+        parsed.kind_lookup,
+    ) {
+        if parsed.has_parse_errors {
+            // The formatter keeps code with syntax errors as it is, but the
+            // errors can change how the parser reads the code around them.
+            // Users need to fix the errors first.
+            return Err(Error::Parse(parser::find_syntax_errors(
+                parsed.tree.root_node(),
+                parsed.source,
+            )));
         }
+        return Err(Error::StructureMismatch(mismatch));
     }
+    if let Some(mismatch) =
+        verify_structure::find_comment_mismatch(&parsed.tree, parsed.source, &reparsed.tree, output)
+    {
+        return Err(Error::CommentMismatch(mismatch));
+    }
+    Ok(())
+}
+
+/// Checks that writing the output with `line_ending` keeps the line breaks
+/// inside every string of the input. We parse and format the source with LF
+/// line endings, so a string with line breaks of the other kind would change
+/// its value. `crlf_line_breaks` are the offsets of the input's CRLF line
+/// breaks, from [parser::find_crlf_line_breaks].
+fn verify_string_line_endings(
+    parsed: &parser::ParseInput,
+    crlf_line_breaks: &[usize],
+    line_ending: renderer::LineEnding,
+) -> Result<(), Error> {
+    let uses_crlf = line_ending == renderer::LineEnding::CrLf;
+    if crlf_line_breaks.is_empty() && !uses_crlf {
+        return Ok(());
+    }
+    match find_string_with_changed_line_ending(
+        parsed.tree.root_node(),
+        parsed,
+        crlf_line_breaks,
+        uses_crlf,
+    ) {
+        Some(line) => Err(Error::StringLineEndingChanged { line }),
+        None => Ok(()),
+    }
+}
+
+/// Returns the first line of the first string under `node` with a line break
+/// that isn't CRLF when `uses_crlf` is true, or that is CRLF otherwise.
+fn find_string_with_changed_line_ending(
+    node: tree_sitter::Node,
+    parsed: &parser::ParseInput,
+    crlf_line_breaks: &[usize],
+    uses_crlf: bool,
+) -> Option<usize> {
+    if matches!(
+        GDScriptNodeKind::get_kind_from_ast_node(node),
+        GDScriptNodeKind::String | GDScriptNodeKind::StringName | GDScriptNodeKind::NodePath
+    ) {
+        let has_changed_line_break =
+            parsed.source[node.byte_range()]
+                .match_indices('\n')
+                .any(|(offset, _)| {
+                    let is_crlf = crlf_line_breaks
+                        .binary_search(&(node.start_byte() + offset))
+                        .is_ok();
+                    is_crlf != uses_crlf
+                });
+        return has_changed_line_break.then(|| node.start_position().row + 1);
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find_map(|child| {
+        find_string_with_changed_line_ending(child, parsed, crlf_line_breaks, uses_crlf)
+    })
+}
+
+/// Reparses the reordered output and checks that every exported variable is
+/// still in the same inspector group, and that every `#region` marker still
+/// has its `#endregion`.
+fn verify_reordered_code(
+    parsed: &parser::ParseInput,
+    output: &str,
+    config: &FormatterConfiguration,
+) -> Result<(), Error> {
+    let reparsed = parser::ParseInput::new(output, config).ok_or_else(Error::parser_failed)?;
+    if let Some(mismatch) = verify_structure::find_export_group_mismatch(
+        &parsed.tree,
+        parsed.source,
+        &reparsed.tree,
+        output,
+    ) {
+        return Err(Error::ExportGroupMismatch(mismatch));
+    }
+    if verify_structure::find_unmatched_region(&parsed.tree, parsed.source).is_none()
+        && let Some(unmatched_region) =
+            verify_structure::find_unmatched_region(&reparsed.tree, output)
+    {
+        return Err(Error::UnmatchedRegion(unmatched_region));
+    }
+    Ok(())
+}
 
+/// Formats the formatted output again and checks that the second pass doesn't
+/// change it.
+fn verify_idempotent_output(output: &str, config: &FormatterConfiguration) -> Result<(), Error> {
+    let mut second_pass_config = config.clone();
+    second_pass_config.safe = false;
+    second_pass_config.verify_idempotent = false;
+    let second_pass = format_gdscript(output, &second_pass_config)?;
+    if second_pass != output {
+        let diff = make_unified_diff(output, &second_pass, "first pass", "second pass");
+        return Err(Error::NotIdempotent { diff });
+    }
     Ok(())
 }
+
+/// Finds the consecutive sibling statements that cover the trimmed `range`
+/// and returns the byte range from the start of the first one to the end of
+/// the last one.
+///
+/// We look for the closest code block around the range: the source file, a
+/// class body, or a block of statements. Lambda bodies are skipped because
+/// their layout depends on the expression that contains them.
+fn find_statements_in_range(
+    parsed: &parser::ParseInput,
+    range: &RangeSourceBytes,
+) -> Option<RangeSourceBytes> {
+    let source_bytes = parsed.source.as_bytes();
+    let mut start_byte = range.start_byte.min(source_bytes.len());
+    let mut end_byte = range.end_byte.min(source_bytes.len());
+    while start_byte < end_byte && source_bytes[start_byte].is_ascii_whitespace() {
+        start_byte += 1;
+    }
+    while end_byte > start_byte && source_bytes[end_byte - 1].is_ascii_whitespace() {
+        end_byte -= 1;
+    }
+    if start_byte == end_byte {
+        return None;
+    }
+
+    let root = parsed.tree.root_node();
+    let mut block = root.descendant_for_byte_range(start_byte, end_byte)?;
+    while let Some(parent) = block.parent() {
+        let kind = GDScriptNodeKind::get_kind_from_ast_node(block);
+        let is_statement_block =
+            matches!(kind, GDScriptNodeKind::Body | GDScriptNodeKind::ClassBody)
+                && GDScriptNodeKind::get_kind_from_ast_node(parent) != GDScriptNodeKind::Lambda;
+        if is_statement_block {
+            break;
+        }
+        block = parent;
+    }
+
+    let mut statements: Option<RangeSourceBytes> = None;
+    let mut cursor = block.walk();
+    for child in block.children(&mut cursor) {
+        if child.end_byte() <= start_byte || child.start_byte() >= end_byte {
+            continue;
+        }
+        match statements.as_mut() {
+            Some(statements) => statements.end_byte = child.end_byte(),
+            None => {
+                statements = Some(RangeSourceBytes {
+                    start_byte: child.start_byte(),
+                    end_byte: child.end_byte(),
+                });
+            }
+        }
+    }
+    statements
+}
+
+fn find_line_start(text: &str, byte_offset: usize) -> usize {
+    match text[..byte_offset].rfind('\n') {
+        Some(newline_position) => newline_position + 1,
+        None => 0,
+    }
+}
+
+fn is_indentation(text: &str) -> bool {
+    for byte in text.bytes() {
+        if byte != b' ' && byte != b'\t' {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    /// Runs the safe mode checks on `output` as if formatting `source`
+    /// produced it. Lets us test errors a correct formatter never returns.
+    fn verify_output(source: &str, output: &str) -> Result<(), Error> {
+        let config = FormatterConfiguration {
+            safe: true,
+            ..Default::default()
+        };
+        let parsed = parser::ParseInput::new(source, &config).unwrap();
+        let mut render_elements = Vec::new();
+        formatter::build_formatter_intermediate_representation(&parsed, &mut render_elements);
+        let printer_config =
+            make_printer_configuration(&config, parser::SourceEncoding::detect(source));
+        let mut unformatted_sources = Vec::new();
+        renderer::render_tracking_source_offsets(
+            &render_elements,
+            source,
+            &printer_config,
+            &[],
+            &mut String::new(),
+            &mut Vec::new(),
+            &mut unformatted_sources,
+        );
+        verify_formatted_structure(&parsed, output, &config, &unformatted_sources)
+    }
+
+    #[test]
+    fn changed_structure_is_a_structure_mismatch() {
+        let result = verify_output("var a = 1\n", "var a = x\n");
+
+        let Err(Error::StructureMismatch(mismatch)) = result else {
+            panic!("expected a structure mismatch, got {:?}", result);
+        };
+        let input = mismatch.input.unwrap();
+        let output = mismatch.output.unwrap();
+        assert_eq!((input.kind, input.start_line), ("integer", 1));
+        assert_eq!((output.kind, output.start_line), ("identifier", 1));
+    }
+
+    #[test]
+    fn changed_structure_around_syntax_errors_is_a_parse_error_with_locations() {
+        // The formatter keeps the broken second line as is, so only the first
+        // line differs.
+        let result = verify_output(
+            "var a = 1\nvar name.bla = value\n",
+            "var a = x\nvar name.bla = value\n",
+        );
+
+        let Err(Error::Parse(syntax_errors)) = result else {
+            panic!("expected a parse error, got {:?}", result);
+        };
+        assert!(!syntax_errors.is_empty());
+        for syntax_error in &syntax_errors {
+            assert_eq!(syntax_error.line, 2);
+            assert_eq!(syntax_error.snippet, "var name.bla = value");
+        }
+    }
+}
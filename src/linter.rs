@@ -1,11 +1,16 @@
 use crate::node_kind::GDScriptNodeKind;
+use crate::project_config::ProjectConfigCache;
+use crate::{Error, FormatterConfiguration, ReorderConfiguration};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::{fs, io::IsTerminal};
-use tree_sitter::{Node, Parser};
+use tree_sitter::{Node, Parser, Range, Tree};
 
+pub mod fixes;
 pub mod ignore_patterns;
 pub mod lib;
+pub mod output_formats;
+pub mod project_rules;
 pub mod regex_patterns;
 pub mod rule_config;
 pub mod rules;
@@ -13,49 +18,138 @@ pub mod rules;
 #[cfg(test)]
 mod tests;
 
-use ignore_patterns::{parse_ignore_patterns, should_ignore_rule};
+use fixes::apply_fixes;
+use ignore_patterns::Suppressions;
+use output_formats::{FileLintResult, LintOutputFormat, write_lint_report};
+use project_rules::{ALL_PROJECT_RULES, Project, ProjectFile};
+use rule_config::{NamingConvention, RULES_DISABLED_BY_DEFAULT};
 use rules::{ALL_RULES, Rule};
 
+/// Maximum number of times `lint --fix` lints and fixes a file.
+const MAX_FIX_PASSES: usize = 10;
+
+/// How serious a lint issue is, from the most to the least severe.
 #[derive(Debug, Clone, PartialEq)]
 pub enum LintSeverity {
     Error,
     Warning,
+    Info,
+    Hint,
+}
+
+impl LintSeverity {
+    /// Returns the lowercase name used in lint output, like "error".
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintSeverity::Error => "error",
+            LintSeverity::Warning => "warning",
+            LintSeverity::Info => "info",
+            LintSeverity::Hint => "hint",
+        }
+    }
+
+    /// Parses a severity from its name, as returned by `name()`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(LintSeverity::Error),
+            "warning" => Some(LintSeverity::Warning),
+            "info" => Some(LintSeverity::Info),
+            "hint" => Some(LintSeverity::Hint),
+            _ => None,
+        }
+    }
+
+    /// Returns true if this severity is as severe as `threshold` or more.
+    pub fn is_at_least(&self, threshold: &LintSeverity) -> bool {
+        self.rank() >= threshold.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            LintSeverity::Hint => 0,
+            LintSeverity::Info => 1,
+            LintSeverity::Warning => 2,
+            LintSeverity::Error => 3,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct LintIssue {
     pub line: usize,
     pub column: usize,
+    /// Line and column where the flagged code ends (exclusive), 1-based like
+    /// `line` and `column`. Equal to the start when the issue has no span.
+    pub end_line: usize,
+    pub end_column: usize,
     pub rule: String,
+    /// Rules don't choose the severity of their issues: the linter sets it
+    /// from the rule definition's default severity or the config.
     pub severity: LintSeverity,
     pub message: String,
+    /// Edits that fix the issue, applied all together by `lint --fix`. Empty
+    /// if the rule can't fix this issue automatically.
+    pub fix: Vec<TextEdit>,
+}
+
+/// Replaces the bytes between `start_byte` and `end_byte` of the linted source
+/// code with `new_text`. An empty range inserts text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub new_text: String,
 }
 
 impl LintIssue {
-    pub fn new(
-        line: usize,
-        column: usize,
-        rule: String,
-        severity: LintSeverity,
-        message: String,
-    ) -> Self {
+    pub fn new(line: usize, column: usize, rule: String, message: String) -> Self {
         Self {
             line,
             column,
+            end_line: line,
+            end_column: column,
             rule,
-            severity,
+            severity: LintSeverity::Warning,
             message,
+            fix: Vec::new(),
         }
     }
 
+    /// Creates an issue that spans the source code of `node`.
+    pub fn from_node(node: &Node, rule: String, message: String) -> Self {
+        Self::from_range(&node.range(), rule, message)
+    }
+
+    /// Creates an issue that spans a range of the parsed tree. Rules that
+    /// store locations to report them later, in `finalize()`, can keep the
+    /// range instead of the node.
+    pub fn from_range(range: &Range, rule: String, message: String) -> Self {
+        Self {
+            line: range.start_point.row + 1,
+            column: range.start_point.column + 1,
+            end_line: range.end_point.row + 1,
+            end_column: range.end_point.column + 1,
+            rule,
+            severity: LintSeverity::Warning,
+            message,
+            fix: Vec::new(),
+        }
+    }
+
+    /// Attaches the edits that fix this issue.
+    pub fn with_fix(mut self, edits: Vec<TextEdit>) -> Self {
+        self.fix = edits;
+        self
+    }
+
     pub fn format(&self, file_path: &str) -> String {
-        let severity_str = match self.severity {
-            LintSeverity::Error => "error",
-            LintSeverity::Warning => "warning",
-        };
         format!(
             "{}:{}:{}:{}: {}",
-            file_path, self.line, self.rule, severity_str, self.message
+            file_path,
+            self.line,
+            self.rule,
+            self.severity.name(),
+            self.message
         )
     }
 }
@@ -64,6 +158,13 @@ impl LintIssue {
 pub struct LinterConfig {
     pub disabled_rules: HashSet<String>,
     pub max_line_length: usize,
+    /// Severities that replace the default severity of rules, by rule name.
+    pub rule_severities: HashMap<String, LintSeverity>,
+    /// Custom naming conventions for naming rules, by rule name.
+    pub naming_conventions: HashMap<String, NamingConvention>,
+    /// Declaration order the class-definitions-order rule checks against. It
+    /// should match the formatter's reorder settings.
+    pub reorder: ReorderConfiguration,
 }
 
 impl Default for LinterConfig {
@@ -71,7 +172,31 @@ impl Default for LinterConfig {
         Self {
             disabled_rules: HashSet::new(),
             max_line_length: 100,
+            rule_severities: HashMap::new(),
+            naming_conventions: HashMap::new(),
+            reorder: ReorderConfiguration::default(),
+        }
+    }
+}
+
+impl LinterConfig {
+    /// Returns the naming convention configured for `rule_name`, or an empty
+    /// one that keeps the rule's built-in pattern.
+    pub fn naming_convention(&self, rule_name: &str) -> NamingConvention {
+        self.naming_conventions
+            .get(rule_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the linter should run `rule_name`. Rules that are off
+    /// by default only run when the config gives them a severity.
+    pub fn is_rule_enabled(&self, rule_name: &str) -> bool {
+        if self.disabled_rules.contains(rule_name) {
+            return false;
         }
+        !RULES_DISABLED_BY_DEFAULT.contains(&rule_name)
+            || self.rule_severities.contains_key(rule_name)
     }
 }
 
@@ -81,31 +206,56 @@ pub struct GDScriptLinter {
 }
 
 impl GDScriptLinter {
-    pub fn new(config: LinterConfig) -> Result<Self, String> {
+    pub fn new(config: LinterConfig) -> Result<Self, Error> {
         let mut parser = Parser::new();
         let language = tree_sitter_gdscript::LANGUAGE.into();
         parser
             .set_language(&language)
-            .map_err(|e| format!("Failed to set language: {}", e))?;
+            .map_err(|e| Error::Config(format!("Failed to set language: {}", e)))?;
         GDScriptNodeKind::populate_lookup_table();
         Ok(Self { config, parser })
     }
 
-    pub fn lint(&mut self, source_code: &str, _file_path: &str) -> Result<Vec<LintIssue>, String> {
+    pub fn lint(&mut self, source_code: &str, _file_path: &str) -> Result<Vec<LintIssue>, Error> {
         let tree = self
             .parser
             .parse(source_code, None)
-            .ok_or("Failed to parse GDScript code")?;
+            .ok_or_else(Error::parser_failed)?;
+        let mut suppressions = Suppressions::parse(source_code);
+        let (mut issues, mut file_rules) = self.lint_tree(&tree, source_code, &mut suppressions);
+        Self::check_suppressions(&mut file_rules, &mut suppressions, &mut issues);
+        sort_issues(&mut issues);
+        Ok(issues)
+    }
 
+    /// Runs the rules of the current configuration on a parsed file and
+    /// records which suppression comments they used. Returns the issues and
+    /// the rules, to report on the suppression comments with
+    /// [`Self::check_suppressions`] once every rule ran.
+    fn lint_tree(
+        &mut self,
+        tree: &Tree,
+        source_code: &str,
+        suppressions: &mut Suppressions,
+    ) -> (Vec<LintIssue>, FileRules) {
         let root_node = tree.root_node();
         let mut issues = Vec::new();
 
-        let ignore_map = parse_ignore_patterns(source_code);
-
+        // Each rule reports issues with its default severity unless the
+        // config overrides it. We store the severity next to each checker.
         let mut checkers: Vec<Box<dyn Rule>> = Vec::new();
+        let mut checker_severities: Vec<LintSeverity> = Vec::new();
+        let mut checked_rules: HashSet<&'static str> = HashSet::new();
         for current_rule in ALL_RULES {
-            if !self.config.disabled_rules.contains(current_rule.name) {
+            if self.config.is_rule_enabled(current_rule.name) {
                 checkers.push((current_rule.create)(&self.config));
+                checked_rules.insert(current_rule.name);
+                let severity = self
+                    .config
+                    .rule_severities
+                    .get(current_rule.name)
+                    .unwrap_or(&current_rule.default_severity);
+                checker_severities.push(severity.clone());
             }
         }
 
@@ -126,14 +276,25 @@ impl GDScriptLinter {
             }
         }
 
-        // First we run the rules that only care about the source code. Then we
-        // visit each node in the AST, calling the relevant rules. Finally we
-        // call the finalize method on each rule in case a rule needs to collect
-        // state while visiting nodes and report issues at the end.
+        // First we run the rules that only care about the source code and the
+        // rules that check the whole tree. Then we visit each node in the AST,
+        // calling the relevant rules. Finally we call the finalize method on
+        // each rule in case a rule needs to collect state while visiting nodes
+        // and report issues at the end.
         for &current_index in &source_only_rules {
             let rule_issues = checkers[current_index].check_source(source_code);
-            for issue in rule_issues {
-                if !should_ignore_rule(&ignore_map, issue.line, &issue.rule) {
+            for mut issue in rule_issues {
+                if !suppressions.suppresses(issue.line, &issue.rule) {
+                    issue.severity = checker_severities[current_index].clone();
+                    issues.push(issue);
+                }
+            }
+        }
+        for (current_index, checker) in checkers.iter_mut().enumerate() {
+            let rule_issues = checker.check_tree(&root_node, source_code);
+            for mut issue in rule_issues {
+                if !suppressions.suppresses(issue.line, &issue.rule) {
+                    issue.severity = checker_severities[current_index].clone();
                     issues.push(issue);
                 }
             }
@@ -142,24 +303,46 @@ impl GDScriptLinter {
             &root_node,
             source_code,
             &mut checkers,
+            &checker_severities,
             &node_kind_map,
             &mut issues,
-            &ignore_map,
+            suppressions,
         );
-        for checker in checkers.iter_mut() {
+        for (current_index, checker) in checkers.iter_mut().enumerate() {
             let rule_issues = checker.finalize(source_code);
-            for issue in rule_issues {
-                if !should_ignore_rule(&ignore_map, issue.line, &issue.rule) {
+            for mut issue in rule_issues {
+                if !suppressions.suppresses(issue.line, &issue.rule) {
+                    issue.severity = checker_severities[current_index].clone();
                     issues.push(issue);
                 }
             }
         }
 
-        // Sort issues by line number. Rules that run on the source code like
-        // line length check will otherwise appear at the end.
-        issues.sort_by(|a, b| a.line.cmp(&b.line).then(a.column.cmp(&b.column)));
+        let file_rules = FileRules {
+            checkers,
+            checker_severities,
+            checked_rules,
+        };
+        (issues, file_rules)
+    }
 
-        Ok(issues)
+    /// Lets rules report on the suppression comments themselves, like
+    /// comments that suppress nothing. Call it once every rule that uses the
+    /// comments ran, so we know which comments were used.
+    fn check_suppressions(
+        file_rules: &mut FileRules,
+        suppressions: &mut Suppressions,
+        issues: &mut Vec<LintIssue>,
+    ) {
+        for (current_index, checker) in file_rules.checkers.iter_mut().enumerate() {
+            let rule_issues = checker.check_suppressions(suppressions, &file_rules.checked_rules);
+            for mut issue in rule_issues {
+                if !suppressions.suppresses(issue.line, &issue.rule) {
+                    issue.severity = file_rules.checker_severities[current_index].clone();
+                    issues.push(issue);
+                }
+            }
+        }
     }
 
     pub fn lint_files(
@@ -167,146 +350,418 @@ impl GDScriptLinter {
         gdscript_files: &[PathBuf],
         do_pretty_print: bool,
     ) -> Result<bool, Box<dyn std::error::Error>> {
-        self.lint_files_with_editorconfig(gdscript_files, do_pretty_print, None)
+        let output_format = if do_pretty_print {
+            LintOutputFormat::Pretty
+        } else {
+            LintOutputFormat::Text
+        };
+        let highest_severity = self.lint_files_with_editorconfig(
+            gdscript_files,
+            output_format,
+            None,
+            &HashMap::new(),
+            false,
+        )?;
+        Ok(highest_severity.is_some())
     }
 
+    /// Lints each file with its editorconfig settings applied and prints the
+    /// issues in the requested format. Returns the severity of the most severe
+    /// issue found, or None if there are no issues.
+    ///
+    /// After checking files one by one, this runs the project rules on all the
+    /// files together (see [`project_rules`]).
+    ///
+    /// If `do_fix` is true, this first fixes the issues it can in each file and
+    /// writes the fixed code back. It then only reports the remaining issues.
     pub fn lint_files_with_editorconfig(
         &mut self,
         gdscript_files: &[PathBuf],
-        do_pretty_print: bool,
+        output_format: LintOutputFormat,
         max_line_length_override: Option<usize>,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
+        rule_severity_overrides: &HashMap<String, LintSeverity>,
+        do_fix: bool,
+    ) -> Result<Option<LintSeverity>, Box<dyn std::error::Error>> {
         let with_colors = std::io::stdout().is_terminal();
         let base_config = self.config.clone();
+        let mut project_configs = ProjectConfigCache::default();
 
-        if do_pretty_print {
-            self.lint_files_pretty(
-                gdscript_files,
-                with_colors,
+        let mut results = Vec::new();
+        let mut linted_files = Vec::new();
+        let mut file_checks = Vec::new();
+        for file_path in gdscript_files {
+            let (linted_file, file_check, issues) = self.lint_file(
+                file_path,
                 &base_config,
+                &mut project_configs,
                 max_line_length_override,
-            )
-        } else {
-            self.lint_files_standard(gdscript_files, &base_config, max_line_length_override)
+                rule_severity_overrides,
+                do_fix,
+            )?;
+            results.push(FileLintResult {
+                file_path: file_path.to_string_lossy().to_string(),
+                issues,
+            });
+            linted_files.push(linted_file);
+            file_checks.push(file_check);
         }
-    }
-
-    fn apply_file_config(
-        &mut self,
-        base_config: &LinterConfig,
-        file_path: &Path,
-        max_line_length_override: Option<usize>,
-    ) {
-        self.config = base_config.clone();
-        crate::editorconfig::apply_editorconfig_to_linter_config(&mut self.config, file_path);
-        if let Some(max_line_length) = max_line_length_override {
-            self.config.max_line_length = max_line_length;
+        self.config = base_config;
+        Self::lint_project(linted_files, &mut file_checks, &mut results);
+
+        // Project rules use the suppression comments too, so we report on the
+        // comments once they ran.
+        for (result, file_check) in results.iter_mut().zip(&mut file_checks) {
+            for rule_definition in ALL_PROJECT_RULES {
+                if file_check.config.is_rule_enabled(rule_definition.name) {
+                    file_check
+                        .file_rules
+                        .checked_rules
+                        .insert(rule_definition.name);
+                }
+            }
+            Self::check_suppressions(
+                &mut file_check.file_rules,
+                &mut file_check.suppressions,
+                &mut result.issues,
+            );
+            sort_issues(&mut result.issues);
         }
-    }
-
-    fn lint_files_pretty(
-        &mut self,
-        gdscript_files: &[PathBuf],
-        with_colors: bool,
-        base_config: &LinterConfig,
-        max_line_length_override: Option<usize>,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        use std::collections::HashMap;
-        let mut file_issues: HashMap<String, Vec<_>> = HashMap::new();
-        let mut has_issues = false;
-
-        for file_path in gdscript_files {
-            self.apply_file_config(base_config, file_path, max_line_length_override);
-            let source_code = fs::read_to_string(file_path).map_err(|error| {
-                format!("Failed to read file {}: {}", file_path.display(), error)
-            })?;
 
-            let issues = self.lint(&source_code, &file_path.to_string_lossy())?;
+        let mut highest_severity = None;
+        for result in &results {
+            update_highest_severity(&mut highest_severity, &result.issues);
+        }
 
-            if !issues.is_empty() {
-                has_issues = true;
-                file_issues.insert(file_path.to_string_lossy().to_string(), issues);
+        match output_format {
+            LintOutputFormat::Text => print_standard_report(&results),
+            LintOutputFormat::Pretty => print_pretty_report(&results, with_colors),
+            _ => {
+                let mut report = String::new();
+                write_lint_report(output_format, &results, &mut report);
+                print!("{}", report);
             }
         }
+        Ok(highest_severity)
+    }
 
-        // Print pretty output grouped by file and line
-        let mut file_iter = file_issues.iter().peekable();
-        while let Some((file_path, issues)) = file_iter.next() {
-            let bold = if with_colors { "\x1b[1m" } else { "" };
-            let reset = if with_colors { "\x1b[0m" } else { "" };
+    /// Runs the project rules on the linted files, grouped by Godot project,
+    /// and adds the issues to the results of each file. Each issue follows the
+    /// configuration and ignore comments of the file it belongs to.
+    fn lint_project(
+        linted_files: Vec<LintedFile>,
+        file_checks: &mut [FileCheck],
+        results: &mut [FileLintResult],
+    ) {
+        let mut projects: Vec<Project> = Vec::new();
+        let mut result_indices: Vec<Vec<usize>> = Vec::new();
+        for (result_index, linted_file) in linted_files.into_iter().enumerate() {
+            let canonical_path = linted_file
+                .path
+                .canonicalize()
+                .unwrap_or_else(|_| linted_file.path.clone());
+            let project_file = ProjectFile {
+                path: linted_file.path,
+                canonical_path,
+                source_code: linted_file.source_code,
+                tree: linted_file.tree,
+            };
+
+            let project_index = if let Some(project_index) = projects
+                .iter()
+                .position(|project| project.root == linted_file.project_root)
+            {
+                project_index
+            } else {
+                projects.push(Project {
+                    root: linted_file.project_root,
+                    files: Vec::new(),
+                });
+                result_indices.push(Vec::new());
+                projects.len() - 1
+            };
+            projects[project_index].files.push(project_file);
+            result_indices[project_index].push(result_index);
+        }
 
-            println!("{}{}{}", bold, file_path, reset);
+        for (project_index, project) in projects.iter().enumerate() {
+            let project_result_indices = &result_indices[project_index];
+            for rule_definition in ALL_PROJECT_RULES {
+                // The rule runs if it is on for at least one file. We then
+                // only keep the issues of the files it is on for.
+                let Some(&rule_result_index) = project_result_indices.iter().find(|&&index| {
+                    file_checks[index]
+                        .config
+                        .is_rule_enabled(rule_definition.name)
+                }) else {
+                    continue;
+                };
+                let mut rule = (rule_definition.create)(&file_checks[rule_result_index].config);
+                for project_issue in rule.check_project(project) {
+                    let result_index = project_result_indices[project_issue.file_index];
+                    let file_check = &mut file_checks[result_index];
+                    let mut issue = project_issue.issue;
+                    if !file_check.config.is_rule_enabled(rule_definition.name)
+                        || file_check.suppressions.suppresses(issue.line, &issue.rule)
+                    {
+                        continue;
+                    }
+                    issue.severity = file_check
+                        .config
+                        .rule_severities
+                        .get(rule_definition.name)
+                        .unwrap_or(&rule_definition.default_severity)
+                        .clone();
+                    results[result_index].issues.push(issue);
+                }
+            }
+        }
+    }
 
-            // Group issues by line number
-            let mut line_issues: HashMap<usize, Vec<_>> = HashMap::new();
-            for issue in issues {
-                line_issues.entry(issue.line).or_default().push(issue);
+    /// Applies the fixes of all fixable issues in `source_code`, then formats
+    /// the result with `formatter_config` so the fixed code follows the style
+    /// guide. Returns the source code unchanged if there is nothing to fix.
+    pub fn fix(
+        &mut self,
+        source_code: &str,
+        formatter_config: &FormatterConfiguration,
+    ) -> Result<String, Error> {
+        let mut fixed_source = source_code.to_string();
+        let mut applied_fix_count = 0;
+        // Fixes that overlap get skipped, so we lint and fix again until there
+        // is nothing left to fix.
+        for _ in 0..MAX_FIX_PASSES {
+            let issues = self.lint(&fixed_source, "")?;
+            let fix_result = apply_fixes(&fixed_source, &issues);
+            if fix_result.applied_fix_count == 0 {
+                break;
             }
+            applied_fix_count += fix_result.applied_fix_count;
+            fixed_source = fix_result.source_code;
+        }
 
-            // Sort by line number and print
-            let mut sorted_lines: Vec<_> = line_issues.keys().collect();
-            sorted_lines.sort();
-
-            for (i, &line_num) in sorted_lines.iter().enumerate() {
-                if let Some(line_issues) = line_issues.get(line_num) {
-                    println!("    {}:{}", file_path, line_num);
-                    for issue in line_issues {
-                        let (severity_str, severity_color) = match issue.severity {
-                            LintSeverity::Error => ("ERROR", "\x1b[31m"),  // Red
-                            LintSeverity::Warning => ("WARN", "\x1b[33m"), // Yellow
-                        };
-
-                        if with_colors {
-                            println!(
-                                "        {}{}\x1b[0m: `{}`",
-                                severity_color, severity_str, issue.rule
-                            );
-                        } else {
-                            println!("        {}: `{}`", severity_str, issue.rule);
-                        }
-                        println!("        {}", issue.message);
-                    }
+        if applied_fix_count == 0 {
+            return Ok(fixed_source);
+        }
+        crate::format_gdscript(&fixed_source, formatter_config)
+    }
 
-                    // Add newline between line groups (except for the last line group)
-                    if i < sorted_lines.len() - 1 {
-                        println!();
+    /// Lints one file after applying its configuration. With `do_fix`, fixes
+    /// the file first and returns the issues that remain. Also returns the
+    /// parsed file, for the project rules, and what we need to report on its
+    /// suppression comments after them.
+    fn lint_file(
+        &mut self,
+        file_path: &Path,
+        base_config: &LinterConfig,
+        project_configs: &mut ProjectConfigCache,
+        max_line_length_override: Option<usize>,
+        rule_severity_overrides: &HashMap<String, LintSeverity>,
+        do_fix: bool,
+    ) -> Result<(LintedFile, FileCheck, Vec<LintIssue>), Box<dyn std::error::Error>> {
+        self.config = Self::apply_file_config(
+            base_config,
+            file_path,
+            project_configs,
+            max_line_length_override,
+            rule_severity_overrides,
+        )?;
+        let mut source_code = fs::read_to_string(file_path).map_err(|error| Error::Io {
+            path: file_path.to_path_buf(),
+            error,
+        })?;
+
+        if do_fix {
+            let mut formatter_config = FormatterConfiguration {
+                safe: true,
+                ..Default::default()
+            };
+            project_configs.apply_to_formatter_config(&mut formatter_config, file_path)?;
+            crate::editorconfig::apply_editorconfig_to_formatter_config(
+                &mut formatter_config,
+                file_path,
+            );
+            match self.fix(&source_code, &formatter_config) {
+                Ok(fixed_source) => {
+                    if fixed_source != source_code {
+                        fs::write(file_path, &fixed_source).map_err(|error| Error::Io {
+                            path: file_path.to_path_buf(),
+                            error,
+                        })?;
+                        source_code = fixed_source;
                     }
                 }
-            }
-
-            // Add separator between files (except for the last file)
-            if file_iter.peek().is_some() {
-                println!("\n{}", "-".repeat(60));
-                println!();
+                // We still report the issues of files we can't fix.
+                Err(error) => {
+                    let path = file_path.to_string_lossy();
+                    eprintln!("Failed to fix file {}: {}", path, error.format(&path));
+                }
             }
         }
 
-        Ok(has_issues)
+        let tree = self
+            .parser
+            .parse(&source_code, None)
+            .ok_or_else(Error::parser_failed)?;
+        let mut suppressions = Suppressions::parse(&source_code);
+        let (issues, file_rules) = self.lint_tree(&tree, &source_code, &mut suppressions);
+        let linted_file = LintedFile {
+            path: file_path.to_path_buf(),
+            project_root: project_configs.find_project_root(file_path)?,
+            source_code,
+            tree,
+        };
+        let file_check = FileCheck {
+            config: self.config.clone(),
+            suppressions,
+            file_rules,
+        };
+        Ok((linted_file, file_check, issues))
     }
 
-    fn lint_files_standard(
-        &mut self,
-        gdscript_files: &[PathBuf],
+    /// Returns the configuration for one file: the project configuration file
+    /// overrides the base config, then `.editorconfig`, then the CLI overrides.
+    /// Rules disabled in the base config stay disabled.
+    fn apply_file_config(
         base_config: &LinterConfig,
+        file_path: &Path,
+        project_configs: &mut ProjectConfigCache,
         max_line_length_override: Option<usize>,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        let mut has_issues = false;
+        rule_severity_overrides: &HashMap<String, LintSeverity>,
+    ) -> Result<LinterConfig, Error> {
+        let mut config = base_config.clone();
+        project_configs.apply_to_linter_config(&mut config, file_path)?;
+        crate::editorconfig::apply_editorconfig_to_linter_config(&mut config, file_path);
+        if let Some(max_line_length) = max_line_length_override {
+            config.max_line_length = max_line_length;
+        }
+        for (rule_name, severity) in rule_severity_overrides {
+            config.disabled_rules.remove(rule_name);
+            config
+                .rule_severities
+                .insert(rule_name.clone(), severity.clone());
+        }
+        config
+            .disabled_rules
+            .extend(base_config.disabled_rules.iter().cloned());
+        Ok(config)
+    }
+}
 
-        for file_path in gdscript_files {
-            self.apply_file_config(base_config, file_path, max_line_length_override);
-            let source_code = fs::read_to_string(file_path).map_err(|error| {
-                format!("Failed to read file {}: {}", file_path.display(), error)
-            })?;
+/// A file the linter checked, kept for the project rules.
+struct LintedFile {
+    path: PathBuf,
+    /// The Godot project the file belongs to, see [`Project::root`].
+    project_root: Option<PathBuf>,
+    source_code: String,
+    tree: Tree,
+}
+
+/// The rules that checked a file, kept to report on the file's suppression
+/// comments once every rule ran.
+struct FileRules {
+    checkers: Vec<Box<dyn Rule>>,
+    checker_severities: Vec<LintSeverity>,
+    /// Names of the rules that checked the file.
+    checked_rules: HashSet<&'static str>,
+}
+
+/// What the linter keeps of a file until the project rules ran.
+struct FileCheck {
+    /// The linter configuration of this file, with the project configuration
+    /// and editorconfig applied.
+    config: LinterConfig,
+    /// The suppression comments of the file, with the comments the rules
+    /// used so far.
+    suppressions: Suppressions,
+    file_rules: FileRules,
+}
+
+/// Sorts issues by line and column. Rules that run on the source code, like
+/// the line length check, would otherwise appear at the end.
+fn sort_issues(issues: &mut [LintIssue]) {
+    issues.sort_by(|a, b| a.line.cmp(&b.line).then(a.column.cmp(&b.column)));
+}
+
+/// Prints one line per issue.
+fn print_standard_report(results: &[FileLintResult]) {
+    for result in results {
+        for issue in &result.issues {
+            println!("{}", issue.format(&result.file_path));
+        }
+    }
+}
+
+/// Prints issues grouped by file and line.
+fn print_pretty_report(results: &[FileLintResult], with_colors: bool) {
+    let mut file_iter = results
+        .iter()
+        .filter(|result| !result.issues.is_empty())
+        .peekable();
+    while let Some(result) = file_iter.next() {
+        let file_path = &result.file_path;
+        let bold = if with_colors { "\x1b[1m" } else { "" };
+        let reset = if with_colors { "\x1b[0m" } else { "" };
+
+        println!("{}{}{}", bold, file_path, reset);
+
+        // Group issues by line number
+        let mut line_issues: HashMap<usize, Vec<_>> = HashMap::new();
+        for issue in &result.issues {
+            line_issues.entry(issue.line).or_default().push(issue);
+        }
 
-            let issues = self.lint(&source_code, &file_path.to_string_lossy())?;
+        // Sort by line number and print
+        let mut sorted_lines: Vec<_> = line_issues.keys().collect();
+        sorted_lines.sort();
+
+        for (i, &line_num) in sorted_lines.iter().enumerate() {
+            if let Some(line_issues) = line_issues.get(line_num) {
+                println!("    {}:{}", file_path, line_num);
+                for issue in line_issues {
+                    let (severity_str, severity_color) = match issue.severity {
+                        LintSeverity::Error => ("ERROR", "\x1b[31m"),  // Red
+                        LintSeverity::Warning => ("WARN", "\x1b[33m"), // Yellow
+                        LintSeverity::Info => ("INFO", "\x1b[34m"),    // Blue
+                        LintSeverity::Hint => ("HINT", "\x1b[36m"),    // Cyan
+                    };
+
+                    if with_colors {
+                        println!(
+                            "        {}{}\x1b[0m: `{}`",
+                            severity_color, severity_str, issue.rule
+                        );
+                    } else {
+                        println!("        {}: `{}`", severity_str, issue.rule);
+                    }
+                    println!("        {}", issue.message);
+                }
 
-            for issue in issues {
-                has_issues = true;
-                println!("{}", issue.format(&file_path.to_string_lossy()));
+                // Add newline between line groups (except for the last line group)
+                if i < sorted_lines.len() - 1 {
+                    println!();
+                }
             }
         }
 
-        Ok(has_issues)
+        // Add separator between files (except for the last file)
+        if file_iter.peek().is_some() {
+            println!("\n{}", "-".repeat(60));
+            println!();
+        }
+    }
+}
+
+/// Raises `highest_severity` to the severity of the most severe issue in
+/// `issues`.
+fn update_highest_severity(highest_severity: &mut Option<LintSeverity>, issues: &[LintIssue]) {
+    for issue in issues {
+        let is_more_severe = match highest_severity {
+            Some(severity) => !severity.is_at_least(&issue.severity),
+            None => true,
+        };
+        if is_more_severe {
+            *highest_severity = Some(issue.severity.clone());
+        }
     }
 }
 
@@ -316,17 +771,19 @@ fn visit_each_node(
     node: &Node,
     source_code: &str,
     checkers: &mut [Box<dyn Rule>],
+    checker_severities: &[LintSeverity],
     node_kind_map: &HashMap<GDScriptNodeKind, Vec<usize>>,
     issues: &mut Vec<LintIssue>,
-    ignore_map: &HashMap<usize, HashSet<String>>,
+    suppressions: &mut Suppressions,
 ) {
     if let Some(matching_rules) =
         node_kind_map.get(&GDScriptNodeKind::get_kind_from_ast_node(*node))
     {
         for &rule_idx in matching_rules {
             let rule_issues = checkers[rule_idx].check_node(node, source_code);
-            for issue in rule_issues {
-                if !should_ignore_rule(ignore_map, issue.line, &issue.rule) {
+            for mut issue in rule_issues {
+                if !suppressions.suppresses(issue.line, &issue.rule) {
+                    issue.severity = checker_severities[rule_idx].clone();
                     issues.push(issue);
                 }
             }
@@ -340,9 +797,10 @@ fn visit_each_node(
                 &cursor.node(),
                 source_code,
                 checkers,
+                checker_severities,
                 node_kind_map,
                 issues,
-                ignore_map,
+                suppressions,
             );
             if !cursor.goto_next_sibling() {
                 break;
@@ -355,12 +813,12 @@ pub fn lint_gdscript_with_config(
     source_code: &str,
     file_path: &str,
     config: &LinterConfig,
-) -> Result<Vec<LintIssue>, String> {
+) -> Result<Vec<LintIssue>, Error> {
     let mut linter = GDScriptLinter::new(config.clone())?;
     linter.lint(source_code, file_path)
 }
 
-pub fn lint_gdscript(source_code: &str, file_path: &str) -> Result<Vec<LintIssue>, String> {
+pub fn lint_gdscript(source_code: &str, file_path: &str) -> Result<Vec<LintIssue>, Error> {
     let config = LinterConfig::default();
     lint_gdscript_with_config(source_code, file_path, &config)
 }
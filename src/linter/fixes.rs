@@ -0,0 +1,191 @@
+//! Automatic fixes for lint issues.
+//!
+//! Rules attach fixes to the issues they report as lists of text edits on the
+//! linted source code (see [`LintIssue::with_fix`]). This module applies them
+//! and provides helpers rules use to build common edits, like deleting a line
+//! or renaming a local variable.
+//!
+//! Edits of one fix are applied together or not at all. When fixes overlap, we
+//! apply the first one and skip the others: the linter runs again on the
+//! fixed code and picks up the remaining issues in another pass.
+use crate::linter::lib::get_node_text;
+use crate::linter::{LintIssue, TextEdit};
+use crate::node_kind::GDScriptNodeKind;
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// Source code with fixes applied, and how many fixes were applied.
+pub struct FixedSource {
+    pub source_code: String,
+    pub applied_fix_count: usize,
+}
+
+/// Applies the fixes of `issues` to `source_code`, skipping any fix with an
+/// edit that overlaps or touches an edit from a fix applied before it.
+pub fn apply_fixes(source_code: &str, issues: &[LintIssue]) -> FixedSource {
+    let mut accepted_edits: Vec<&TextEdit> = Vec::new();
+    let mut applied_fix_count = 0;
+
+    for issue in issues {
+        if issue.fix.is_empty() {
+            continue;
+        }
+        let mut conflicts = false;
+        for edit in &issue.fix {
+            for accepted_edit in &accepted_edits {
+                if edit.start_byte <= accepted_edit.end_byte
+                    && accepted_edit.start_byte <= edit.end_byte
+                {
+                    conflicts = true;
+                }
+            }
+        }
+        if conflicts {
+            continue;
+        }
+        for edit in &issue.fix {
+            accepted_edits.push(edit);
+        }
+        applied_fix_count += 1;
+    }
+
+    // We apply edits from the end of the file so that earlier byte offsets
+    // stay valid.
+    accepted_edits.sort_by_key(|edit| std::cmp::Reverse(edit.start_byte));
+    let mut fixed_source = source_code.to_string();
+    for edit in accepted_edits {
+        fixed_source.replace_range(edit.start_byte..edit.end_byte, &edit.new_text);
+    }
+
+    FixedSource {
+        source_code: fixed_source,
+        applied_fix_count,
+    }
+}
+
+/// Returns an edit that deletes the line of `node`, including its line break,
+/// if the node is the only code on that line. Returns None otherwise, for
+/// example when a comment follows the node.
+pub fn make_line_deletion_edit(node: &Node, source_code: &str) -> Option<TextEdit> {
+    let line_start = source_code[..node.start_byte()]
+        .rfind('\n')
+        .map_or(0, |newline_index| newline_index + 1);
+    let line_end = source_code[node.end_byte()..]
+        .find('\n')
+        .map_or(source_code.len(), |newline_index| {
+            node.end_byte() + newline_index + 1
+        });
+
+    let text_before = &source_code[line_start..node.start_byte()];
+    let text_after = &source_code[node.end_byte()..line_end];
+    if !text_before.trim().is_empty() || !text_after.trim().is_empty() {
+        return None;
+    }
+
+    Some(TextEdit {
+        start_byte: line_start,
+        end_byte: line_end,
+        new_text: String::new(),
+    })
+}
+
+/// Returns edits that rename the identifier `declaration_name` and all the
+/// identifiers with the same name inside `scope`. Member accesses like
+/// `object.name` are left untouched.
+///
+/// Returns None if `new_name` is already used in the scope, as renaming would
+/// then merge two different variables.
+pub fn make_local_rename_edits(
+    declaration_name: &Node,
+    scope: &Node,
+    new_name: &str,
+    source_code: &str,
+) -> Option<Vec<TextEdit>> {
+    let old_name = get_node_text(declaration_name, source_code);
+    let mut identifiers = Vec::new();
+    collect_identifiers(scope, &mut identifiers);
+
+    let mut edits = Vec::new();
+    let mut edited_starts = HashSet::new();
+    for identifier in identifiers {
+        let identifier_text = get_node_text(&identifier, source_code);
+        if identifier_text == new_name {
+            return None;
+        }
+        if identifier_text != old_name || is_member_access(&identifier) {
+            continue;
+        }
+        edited_starts.insert(identifier.start_byte());
+        edits.push(TextEdit {
+            start_byte: identifier.start_byte(),
+            end_byte: identifier.end_byte(),
+            new_text: new_name.to_string(),
+        });
+    }
+
+    if !edited_starts.contains(&declaration_name.start_byte()) {
+        edits.push(TextEdit {
+            start_byte: declaration_name.start_byte(),
+            end_byte: declaration_name.end_byte(),
+            new_text: new_name.to_string(),
+        });
+    }
+    Some(edits)
+}
+
+/// Converts a camelCase, PascalCase, or CONSTANT_CASE name to snake_case,
+/// keeping leading underscores. For example, `_maxHealth` becomes
+/// `_max_health` and `HTTPRequest` becomes `http_request`.
+pub fn to_snake_case(name: &str) -> String {
+    let leading_underscore_count = name.len() - name.trim_start_matches('_').len();
+    let mut result = name[..leading_underscore_count].to_string();
+
+    let characters: Vec<char> = name[leading_underscore_count..].chars().collect();
+    for (index, &character) in characters.iter().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            let previous = characters[index - 1];
+            let next_is_lowercase = characters
+                .get(index + 1)
+                .is_some_and(|next| next.is_lowercase());
+            let starts_word = previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lowercase);
+            if starts_word && !result.ends_with('_') {
+                result.push('_');
+            }
+        }
+        for lowercase_character in character.to_lowercase() {
+            result.push(lowercase_character);
+        }
+    }
+    result
+}
+
+fn collect_identifiers<'tree>(node: &Node<'tree>, identifiers: &mut Vec<Node<'tree>>) {
+    if GDScriptNodeKind::get_kind_from_ast_node(*node) == GDScriptNodeKind::Identifier {
+        identifiers.push(*node);
+    }
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            collect_identifiers(&cursor.node(), identifiers);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns true for the member name in `object.member` and
+/// `object.method()`.
+fn is_member_access(identifier: &Node) -> bool {
+    let mut accessed_node = *identifier;
+    if let Some(parent) = identifier.parent()
+        && GDScriptNodeKind::get_kind_from_ast_node(parent) == GDScriptNodeKind::AttributeCall
+    {
+        accessed_node = parent;
+    }
+    accessed_node
+        .prev_sibling()
+        .is_some_and(|previous| previous.kind() == ".")
+}
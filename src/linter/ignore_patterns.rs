@@ -1,174 +1,379 @@
-use std::collections::{HashMap, HashSet};
+//! Parses the comments that suppress lint issues:
+//!
+//! - `# gdlint-ignore` and `# gdlint-ignore-line` suppress issues on the line
+//!   of the comment.
+//! - `# gdlint-ignore-next-line` suppresses issues on the next line.
+//! - `# gdlint-ignore-file` suppresses issues in the whole file.
+//! - `# gdlint-disable` suppresses issues until a matching `# gdlint-enable`
+//!   comment or the end of the file.
+//!
+//! Each directive takes an optional list of rule names separated by commas or
+//! spaces. Without rule names, it applies to all rules.
+use std::collections::HashSet;
+
+/// The kinds of suppression comments, one per directive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuppressionKind {
+    /// `gdlint-ignore` or `gdlint-ignore-line`.
+    CurrentLine,
+    /// `gdlint-ignore-next-line`.
+    NextLine,
+    /// `gdlint-ignore-file`.
+    File,
+    /// `gdlint-disable`, which starts a region.
+    Disable,
+    /// `gdlint-enable`, which ends a region.
+    Enable,
+}
+
+impl SuppressionKind {
+    fn from_directive(directive: &str) -> Option<Self> {
+        match directive {
+            "gdlint-ignore" | "gdlint-ignore-line" => Some(Self::CurrentLine),
+            "gdlint-ignore-next-line" => Some(Self::NextLine),
+            "gdlint-ignore-file" => Some(Self::File),
+            "gdlint-disable" => Some(Self::Disable),
+            "gdlint-enable" => Some(Self::Enable),
+            _ => None,
+        }
+    }
+}
 
-/// Represents an ignore directive for a specific line
+/// A suppression comment found in the source code.
 #[derive(Debug, Clone)]
-pub struct IgnoreDirective {
-    /// The line number this directive applies to
-    pub target_line: usize,
-    /// Set of rule names to ignore on this line
-    pub ignored_rules: HashSet<String>,
+pub struct SuppressionComment {
+    pub kind: SuppressionKind,
+    /// 1-based line of the comment.
+    pub line: usize,
+    /// 1-based byte columns where the directive starts and the comment ends.
+    pub column: usize,
+    pub end_column: usize,
+    /// The rule names listed after the directive. Empty means all rules.
+    pub rules: Vec<String>,
 }
 
-/// Parse ignore comments from source code and return a map of line numbers to ignored rules
-pub fn parse_ignore_patterns(source_code: &str) -> HashMap<usize, HashSet<String>> {
-    let mut ignore_map: HashMap<usize, HashSet<String>> = HashMap::new();
-
-    for (line_idx, line) in source_code.lines().enumerate() {
-        let line_number = line_idx + 1;
-
-        // Look for ignore comments
-        if let Some(comment_start) = line.find('#') {
-            let comment = &line[comment_start..];
-
-            // Check for gdlint-ignore patterns
-            if let Some(rules) = parse_ignore_comment(comment) {
-                // Check if this is a "next-line" directive
-                if comment.contains("gdlint-ignore-next-line") {
-                    // Apply to the next line
-                    let target_line = line_number + 1;
-                    ignore_map.entry(target_line).or_default().extend(rules);
-                } else if comment.contains("gdlint-ignore-line")
-                    || comment.contains("gdlint-ignore")
-                {
-                    // Apply to the current line
-                    ignore_map.entry(line_number).or_default().extend(rules);
-                }
+/// Lines where a `gdlint-disable` comment suppresses issues. If `rule` is
+/// None, the region suppresses all rules except `excluded_rules`.
+#[derive(Debug, Clone)]
+struct DisabledRegion {
+    comment_index: usize,
+    rule: Option<String>,
+    excluded_rules: HashSet<String>,
+    first_line: usize,
+    /// Last line of the region, included. `usize::MAX` while the region is
+    /// open.
+    last_line: usize,
+}
+
+/// All the suppression comments of a file. Records which comments suppress
+/// issues, to report the comments that suppress nothing.
+#[derive(Debug, Clone, Default)]
+pub struct Suppressions {
+    pub comments: Vec<SuppressionComment>,
+    regions: Vec<DisabledRegion>,
+    /// For each comment, whether it suppressed any issue and the rules of the
+    /// issues it suppressed.
+    is_used: Vec<bool>,
+    used_rules: Vec<HashSet<String>>,
+}
+
+impl Suppressions {
+    pub fn parse(source_code: &str) -> Self {
+        let mut suppressions = Self::default();
+        for (line_idx, line) in source_code.lines().enumerate() {
+            if let Some(comment) = parse_suppression_comment(line, line_idx + 1) {
+                suppressions.add_comment(comment);
             }
         }
+        suppressions
     }
 
-    ignore_map
-}
+    fn add_comment(&mut self, comment: SuppressionComment) {
+        let comment_index = self.comments.len();
+        let line = comment.line;
+        match comment.kind {
+            SuppressionKind::Disable if comment.rules.is_empty() => {
+                self.regions.push(DisabledRegion {
+                    comment_index,
+                    rule: None,
+                    excluded_rules: HashSet::new(),
+                    first_line: line,
+                    last_line: usize::MAX,
+                });
+            }
+            SuppressionKind::Disable => {
+                for rule in &comment.rules {
+                    self.regions.push(DisabledRegion {
+                        comment_index,
+                        rule: Some(rule.clone()),
+                        excluded_rules: HashSet::new(),
+                        first_line: line,
+                        last_line: usize::MAX,
+                    });
+                }
+            }
+            SuppressionKind::Enable => self.close_regions(&comment.rules, line),
+            _ => {}
+        }
+        self.comments.push(comment);
+        self.is_used.push(false);
+        self.used_rules.push(HashSet::new());
+    }
 
-/// Parse a single ignore comment and extract the rule names
-fn parse_ignore_comment(comment: &str) -> Option<HashSet<String>> {
-    // Look for gdlint-ignore patterns
-    let patterns = [
-        "gdlint-ignore-next-line",
-        "gdlint-ignore-line",
-        "gdlint-ignore",
-    ];
-
-    for pattern in &patterns {
-        if let Some(start_idx) = comment.find(pattern) {
-            let after_pattern = &comment[start_idx + pattern.len()..];
-
-            // Look for rule names after the pattern
-            // They can be separated by spaces, commas, or both
-            let rules_part = after_pattern.trim();
-
-            if rules_part.is_empty() {
-                // No specific rules mentioned, ignore all rules
-                return Some(HashSet::new()); // Empty set means ignore all
+    /// Ends the open regions for `rules` on `line`, or all open regions if
+    /// `rules` is empty. Regions that disable all rules continue on the next
+    /// line without the re-enabled rules.
+    fn close_regions(&mut self, rules: &[String], line: usize) {
+        let mut continued_regions = Vec::new();
+        for region in self.regions.iter_mut() {
+            if region.last_line != usize::MAX {
+                continue;
             }
+            if let Some(rule) = &region.rule {
+                if rules.is_empty() || rules.contains(rule) {
+                    region.last_line = line;
+                }
+                continue;
+            }
+            region.last_line = line;
+            if !rules.is_empty() {
+                let mut excluded_rules = region.excluded_rules.clone();
+                excluded_rules.extend(rules.iter().cloned());
+                continued_regions.push(DisabledRegion {
+                    comment_index: region.comment_index,
+                    rule: None,
+                    excluded_rules,
+                    first_line: line + 1,
+                    last_line: usize::MAX,
+                });
+            }
+        }
+        self.regions.extend(continued_regions);
+    }
 
-            // Parse comma and/or space separated rule names
-            let rules: HashSet<String> = rules_part
-                .split(|c: char| c == ',' || c.is_whitespace())
-                .map(str::trim)
-                .filter(|s| !s.is_empty())
-                .map(ToString::to_string)
-                .collect();
+    /// Returns true if a comment suppresses issues of `rule_name` on `line`,
+    /// and records that the matching comments are used.
+    pub fn suppresses(&mut self, line: usize, rule_name: &str) -> bool {
+        let mut matching_comments = Vec::new();
+        for (comment_index, comment) in self.comments.iter().enumerate() {
+            let applies_to_line = match comment.kind {
+                SuppressionKind::CurrentLine => comment.line == line,
+                SuppressionKind::NextLine => comment.line + 1 == line,
+                SuppressionKind::File => true,
+                // Regions are checked below.
+                SuppressionKind::Disable | SuppressionKind::Enable => false,
+            };
+            if applies_to_line
+                && (comment.rules.is_empty() || comment.rules.iter().any(|r| r == rule_name))
+            {
+                matching_comments.push(comment_index);
+            }
+        }
+        for region in &self.regions {
+            if line < region.first_line || line > region.last_line {
+                continue;
+            }
+            let applies_to_rule = match &region.rule {
+                Some(rule) => rule == rule_name,
+                None => !region.excluded_rules.contains(rule_name),
+            };
+            if applies_to_rule {
+                matching_comments.push(region.comment_index);
+            }
+        }
 
-            return Some(rules);
+        for &comment_index in &matching_comments {
+            self.is_used[comment_index] = true;
+            self.used_rules[comment_index].insert(rule_name.to_string());
         }
+        !matching_comments.is_empty()
     }
 
-    None
-}
+    /// Returns true if the comment at `comment_index` suppressed an issue.
+    pub fn is_comment_used(&self, comment_index: usize) -> bool {
+        self.is_used[comment_index]
+    }
 
-/// Check if a specific rule should be ignored for a given line
-pub fn should_ignore_rule(
-    ignore_map: &HashMap<usize, HashSet<String>>,
-    line: usize,
-    rule_name: &str,
-) -> bool {
-    if let Some(ignored_rules) = ignore_map.get(&line) {
-        // If the set is empty, it means ignore all rules
-        ignored_rules.is_empty() || ignored_rules.contains(rule_name)
-    } else {
-        false
+    /// Returns true if the comment at `comment_index` suppressed an issue of
+    /// `rule_name`.
+    pub fn is_rule_used(&self, comment_index: usize, rule_name: &str) -> bool {
+        self.used_rules[comment_index].contains(rule_name)
     }
 }
 
+/// Parses a suppression comment on a line of code. The directive must be a
+/// whole word: `gdlint-ignore-file` is not a `gdlint-ignore` comment.
+fn parse_suppression_comment(line: &str, line_number: usize) -> Option<SuppressionComment> {
+    let comment_start = line.find('#')?;
+    let comment = &line[comment_start..];
+    let directive_start = comment.find("gdlint-")?;
+    let after_directive_start = &comment[directive_start..];
+    let directive_end = after_directive_start
+        .find(|c: char| c == ',' || c.is_whitespace())
+        .unwrap_or(after_directive_start.len());
+    let kind = SuppressionKind::from_directive(&after_directive_start[..directive_end])?;
+
+    // Rule names can be separated by spaces, commas, or both.
+    let rules = after_directive_start[directive_end..]
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+        .collect();
+
+    Some(SuppressionComment {
+        kind,
+        line: line_number,
+        column: comment_start + directive_start + 1,
+        end_column: line.trim_end().len() + 1,
+        rules,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used)]
     use super::*;
 
     #[test]
-    fn test_parse_ignore_next_line_single_rule() {
+    fn test_ignore_next_line_single_rule() {
         let source = r#"# gdlint-ignore-next-line private-access
 obj._private_method()"#;
 
-        let ignore_map = parse_ignore_patterns(source);
-        assert_eq!(ignore_map.len(), 1);
-
-        let rules = ignore_map.get(&2).unwrap();
-        assert!(rules.contains("private-access"));
-        assert_eq!(rules.len(), 1);
+        let mut suppressions = Suppressions::parse(source);
+        assert_eq!(suppressions.comments.len(), 1);
+        assert_eq!(suppressions.comments[0].kind, SuppressionKind::NextLine);
+        assert_eq!(suppressions.comments[0].rules, vec!["private-access"]);
+        assert!(suppressions.suppresses(2, "private-access"));
+        assert!(!suppressions.suppresses(2, "constant-name"));
+        assert!(!suppressions.suppresses(1, "private-access"));
+        assert!(!suppressions.suppresses(3, "private-access"));
     }
 
     #[test]
-    fn test_parse_ignore_next_line_multiple_rules() {
+    fn test_ignore_next_line_multiple_rules() {
         let source = r#"# gdlint-ignore-next-line private-access,constant-name
 obj._private_method()"#;
 
-        let ignore_map = parse_ignore_patterns(source);
-        assert_eq!(ignore_map.len(), 1);
-
-        let rules = ignore_map.get(&2).unwrap();
-        assert!(rules.contains("private-access"));
-        assert!(rules.contains("constant-name"));
-        assert_eq!(rules.len(), 2);
+        let mut suppressions = Suppressions::parse(source);
+        assert_eq!(
+            suppressions.comments[0].rules,
+            vec!["private-access", "constant-name"]
+        );
+        assert!(suppressions.suppresses(2, "private-access"));
+        assert!(suppressions.suppresses(2, "constant-name"));
+        assert!(!suppressions.suppresses(2, "other-rule"));
     }
 
     #[test]
-    fn test_parse_ignore_current_line() {
+    fn test_ignore_current_line() {
         let source = r#"obj._private_method() # gdlint-ignore private-access"#;
 
-        let ignore_map = parse_ignore_patterns(source);
-        assert_eq!(ignore_map.len(), 1);
-
-        let rules = ignore_map.get(&1).unwrap();
-        assert!(rules.contains("private-access"));
-        assert_eq!(rules.len(), 1);
+        let mut suppressions = Suppressions::parse(source);
+        assert_eq!(suppressions.comments[0].kind, SuppressionKind::CurrentLine);
+        assert_eq!(suppressions.comments[0].column, 25);
+        assert!(suppressions.suppresses(1, "private-access"));
+        assert!(!suppressions.suppresses(2, "private-access"));
     }
 
     #[test]
-    fn test_parse_ignore_all_rules() {
+    fn test_ignore_all_rules() {
         let source = r#"# gdlint-ignore-next-line
 some_problematic_code()"#;
 
-        let ignore_map = parse_ignore_patterns(source);
-        assert_eq!(ignore_map.len(), 1);
+        let mut suppressions = Suppressions::parse(source);
+        assert!(suppressions.comments[0].rules.is_empty());
+        assert!(suppressions.suppresses(2, "private-access"));
+        assert!(suppressions.suppresses(2, "any-rule"));
+        assert!(!suppressions.suppresses(3, "private-access"));
+    }
+
+    #[test]
+    fn test_ignore_file_is_not_a_line_ignore() {
+        let source = r#"# gdlint-ignore-file private-access
+obj._private_method()"#;
 
-        let rules = ignore_map.get(&2).unwrap();
-        assert!(rules.is_empty()); // Empty means ignore all
+        let mut suppressions = Suppressions::parse(source);
+        assert!(suppressions.suppresses(2, "private-access"));
+        assert!(!suppressions.suppresses(2, "constant-name"));
+        assert!(suppressions.is_rule_used(0, "private-access"));
     }
 
     #[test]
-    fn test_should_ignore_rule() {
-        let mut ignore_map = HashMap::new();
-        let mut rules = HashSet::new();
-        rules.insert("private-access".to_string());
-        rules.insert("constant-name".to_string());
-        ignore_map.insert(5, rules);
+    fn test_disable_and_enable_regions() {
+        let source = r#"# gdlint-disable
+const a = 1
+# gdlint-enable constant-name
+const b = 2
+# gdlint-enable
+const c = 3"#;
+
+        let mut suppressions = Suppressions::parse(source);
+        assert!(suppressions.suppresses(2, "constant-name"));
+        assert!(!suppressions.suppresses(4, "constant-name"));
+        assert!(suppressions.suppresses(4, "private-access"));
+        assert!(!suppressions.suppresses(6, "private-access"));
+        assert!(suppressions.is_comment_used(0));
+        assert!(!suppressions.is_comment_used(1));
+    }
 
-        assert!(should_ignore_rule(&ignore_map, 5, "private-access"));
-        assert!(should_ignore_rule(&ignore_map, 5, "constant-name"));
-        assert!(!should_ignore_rule(&ignore_map, 5, "other-rule"));
-        assert!(!should_ignore_rule(&ignore_map, 6, "private-access"));
+    #[test]
+    fn test_disable_region_without_enable_lasts_until_end_of_file() {
+        let source = r#"const a = 1
+# gdlint-disable constant-name
+const b = 2
+# gdlint-disable
+var c = 3"#;
+
+        let mut suppressions = Suppressions::parse(source);
+        assert!(!suppressions.suppresses(1, "constant-name"));
+        assert!(suppressions.suppresses(3, "constant-name"));
+        assert!(!suppressions.suppresses(3, "private-access"));
+        assert!(suppressions.suppresses(5, "private-access"));
+        // An open region has no last line.
+        assert!(suppressions.suppresses(1000, "constant-name"));
+        assert!(suppressions.suppresses(1000, "private-access"));
     }
 
     #[test]
-    fn test_should_ignore_all_rules() {
-        let mut ignore_map = HashMap::new();
-        ignore_map.insert(5, HashSet::new()); // Empty set means ignore all
+    fn test_enable_one_rule_then_another_inside_all_rules_region() {
+        let source = r#"# gdlint-disable
+const a = 1
+# gdlint-enable constant-name
+const b = 2
+# gdlint-enable private-access
+const c = 3"#;
+
+        let mut suppressions = Suppressions::parse(source);
+        // The enable comment's own line is still in the region.
+        assert!(suppressions.suppresses(3, "constant-name"));
+        assert!(!suppressions.suppresses(4, "constant-name"));
+        assert!(suppressions.suppresses(4, "private-access"));
+        assert!(!suppressions.suppresses(6, "constant-name"));
+        assert!(!suppressions.suppresses(6, "private-access"));
+        assert!(suppressions.suppresses(6, "unused-argument"));
+        assert!(suppressions.is_rule_used(0, "constant-name"));
+        assert!(suppressions.is_rule_used(0, "unused-argument"));
+        assert!(!suppressions.is_comment_used(1));
+        assert!(!suppressions.is_comment_used(2));
+    }
 
-        assert!(should_ignore_rule(&ignore_map, 5, "private-access"));
-        assert!(should_ignore_rule(&ignore_map, 5, "any-rule"));
-        assert!(!should_ignore_rule(&ignore_map, 6, "private-access"));
+    #[test]
+    fn test_ignore_file_combined_with_line_ignore() {
+        let source = r#"# gdlint-ignore-file constant-name
+const a = 1 # gdlint-ignore private-access
+const b = 2 # gdlint-ignore constant-name"#;
+
+        let mut suppressions = Suppressions::parse(source);
+        assert!(suppressions.suppresses(2, "constant-name"));
+        assert!(suppressions.suppresses(2, "private-access"));
+        assert!(!suppressions.suppresses(2, "unused-argument"));
+        assert!(suppressions.suppresses(3, "constant-name"));
+        // Both comments match the issue on line 3, so neither is reported as
+        // unused.
+        assert!(suppressions.is_rule_used(0, "constant-name"));
+        assert!(suppressions.is_rule_used(1, "private-access"));
+        assert!(suppressions.is_rule_used(2, "constant-name"));
     }
 
     #[test]
@@ -176,12 +381,10 @@ some_problematic_code()"#;
         let source = r#"# gdlint-ignore-next-line private-access , constant-name  ,  other-rule
 some_code_with_issues()"#;
 
-        let ignore_map = parse_ignore_patterns(source);
-        let rules = ignore_map.get(&2).unwrap();
-
-        assert!(rules.contains("private-access"));
-        assert!(rules.contains("constant-name"));
-        assert!(rules.contains("other-rule"));
-        assert_eq!(rules.len(), 3);
+        let suppressions = Suppressions::parse(source);
+        assert_eq!(
+            suppressions.comments[0].rules,
+            vec!["private-access", "constant-name", "other-rule"]
+        );
     }
 }
@@ -0,0 +1,354 @@
+//! Machine-readable lint reports for editors and CI services.
+//!
+//! The plain text and pretty formats print issues as the linter finds them
+//! and live in the linter module. The formats in this module describe all
+//! linted files in one document, so the linter collects results first and
+//! passes them to [`write_lint_report`].
+use crate::json::{JsonMember, JsonValue};
+use crate::linter::project_rules::ALL_PROJECT_RULES;
+use crate::linter::rules::ALL_RULES;
+use crate::linter::{LintIssue, LintSeverity};
+
+const TOOL_NAME: &str = "gdscript-formatter";
+const TOOL_INFORMATION_URI: &str = "https://github.com/gdquest/gdscript-formatter";
+
+/// How the linter prints the issues it finds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LintOutputFormat {
+    /// One line per issue: `path:line:rule:severity: message`.
+    Text,
+    /// Human-readable output grouped by file and line.
+    Pretty,
+    /// A JSON array with one object per issue.
+    Json,
+    /// A SARIF 2.1.0 log, used by GitHub code scanning and other tools.
+    Sarif,
+    /// Checkstyle XML, supported by most CI servers.
+    Checkstyle,
+    /// GitHub Actions workflow commands that annotate pull requests.
+    Github,
+    /// A JUnit XML report with one test case per file.
+    Junit,
+}
+
+impl LintOutputFormat {
+    pub fn from_name(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Self::Text),
+            "pretty" => Some(Self::Pretty),
+            "json" => Some(Self::Json),
+            "sarif" => Some(Self::Sarif),
+            "checkstyle" => Some(Self::Checkstyle),
+            "github" => Some(Self::Github),
+            "junit" => Some(Self::Junit),
+            _ => None,
+        }
+    }
+}
+
+/// The issues found in one linted file.
+pub struct FileLintResult {
+    pub file_path: String,
+    pub issues: Vec<LintIssue>,
+}
+
+/// Appends a report of all results in `output_format` to `output`. The text
+/// and pretty formats are printed by the linter directly and produce nothing
+/// here.
+pub fn write_lint_report(
+    output_format: LintOutputFormat,
+    results: &[FileLintResult],
+    output: &mut String,
+) {
+    match output_format {
+        LintOutputFormat::Text | LintOutputFormat::Pretty => {}
+        LintOutputFormat::Json => write_json_report(results, output),
+        LintOutputFormat::Sarif => write_sarif_report(results, output),
+        LintOutputFormat::Checkstyle => write_checkstyle_report(results, output),
+        LintOutputFormat::Github => write_github_report(results, output),
+        LintOutputFormat::Junit => write_junit_report(results, output),
+    }
+}
+
+fn write_json_report(results: &[FileLintResult], output: &mut String) {
+    let mut issue_values = Vec::new();
+    for result in results {
+        for issue in &result.issues {
+            issue_values.push(JsonValue::Object(vec![
+                JsonMember::new("file", JsonValue::string(&result.file_path)),
+                JsonMember::new("line", JsonValue::number(issue.line)),
+                JsonMember::new("column", JsonValue::number(issue.column)),
+                JsonMember::new("end_line", JsonValue::number(issue.end_line)),
+                JsonMember::new("end_column", JsonValue::number(issue.end_column)),
+                JsonMember::new("rule", JsonValue::string(&issue.rule)),
+                JsonMember::new("severity", JsonValue::string(issue.severity.name())),
+                JsonMember::new("message", JsonValue::string(&issue.message)),
+            ]));
+        }
+    }
+    JsonValue::Array(issue_values).write(output);
+    output.push('\n');
+}
+
+fn write_sarif_report(results: &[FileLintResult], output: &mut String) {
+    // The rules table lists file rules, then project rules. Results refer to
+    // rules by their index in this table.
+    let mut rule_names_and_descriptions = Vec::new();
+    for rule in ALL_RULES {
+        rule_names_and_descriptions.push((rule.name, rule.description));
+    }
+    for rule in ALL_PROJECT_RULES {
+        rule_names_and_descriptions.push((rule.name, rule.description));
+    }
+    let mut rule_values = Vec::new();
+    for (name, description) in &rule_names_and_descriptions {
+        rule_values.push(JsonValue::Object(vec![
+            JsonMember::new("id", JsonValue::string(name)),
+            JsonMember::new(
+                "shortDescription",
+                JsonValue::Object(vec![JsonMember::new(
+                    "text",
+                    JsonValue::string(description),
+                )]),
+            ),
+        ]));
+    }
+
+    let mut result_values = Vec::new();
+    for result in results {
+        // SARIF expects URIs, which always use forward slashes.
+        let file_uri = result.file_path.replace('\\', "/");
+        for issue in &result.issues {
+            let mut members = vec![JsonMember::new("ruleId", JsonValue::string(&issue.rule))];
+            if let Some(rule_index) = rule_names_and_descriptions
+                .iter()
+                .position(|(name, _)| *name == issue.rule)
+            {
+                members.push(JsonMember::new("ruleIndex", JsonValue::number(rule_index)));
+            }
+            members.push(JsonMember::new(
+                "level",
+                JsonValue::string(sarif_level(&issue.severity)),
+            ));
+            members.push(JsonMember::new(
+                "message",
+                JsonValue::Object(vec![JsonMember::new(
+                    "text",
+                    JsonValue::string(&issue.message),
+                )]),
+            ));
+            let region = JsonValue::Object(vec![
+                JsonMember::new("startLine", JsonValue::number(issue.line)),
+                JsonMember::new("startColumn", JsonValue::number(issue.column)),
+                JsonMember::new("endLine", JsonValue::number(issue.end_line)),
+                JsonMember::new("endColumn", JsonValue::number(issue.end_column)),
+            ]);
+            let physical_location = JsonValue::Object(vec![
+                JsonMember::new(
+                    "artifactLocation",
+                    JsonValue::Object(vec![JsonMember::new("uri", JsonValue::string(&file_uri))]),
+                ),
+                JsonMember::new("region", region),
+            ]);
+            members.push(JsonMember::new(
+                "locations",
+                JsonValue::Array(vec![JsonValue::Object(vec![JsonMember::new(
+                    "physicalLocation",
+                    physical_location,
+                )])]),
+            ));
+            result_values.push(JsonValue::Object(members));
+        }
+    }
+
+    let driver = JsonValue::Object(vec![
+        JsonMember::new("name", JsonValue::string(TOOL_NAME)),
+        JsonMember::new("version", JsonValue::string(env!("CARGO_PKG_VERSION"))),
+        JsonMember::new("informationUri", JsonValue::string(TOOL_INFORMATION_URI)),
+        JsonMember::new("rules", JsonValue::Array(rule_values)),
+    ]);
+    let run = JsonValue::Object(vec![
+        JsonMember::new(
+            "tool",
+            JsonValue::Object(vec![JsonMember::new("driver", driver)]),
+        ),
+        JsonMember::new("results", JsonValue::Array(result_values)),
+    ]);
+    let log = JsonValue::Object(vec![
+        JsonMember::new(
+            "$schema",
+            JsonValue::string("https://json.schemastore.org/sarif-2.1.0.json"),
+        ),
+        JsonMember::new("version", JsonValue::string("2.1.0")),
+        JsonMember::new("runs", JsonValue::Array(vec![run])),
+    ]);
+    log.write(output);
+    output.push('\n');
+}
+
+fn write_checkstyle_report(results: &[FileLintResult], output: &mut String) {
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<checkstyle version=\"4.3\">\n");
+    for result in results {
+        output.push_str("  <file name=\"");
+        write_xml_escaped(&result.file_path, output);
+        output.push_str("\">\n");
+        for issue in &result.issues {
+            output.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"",
+                issue.line,
+                issue.column,
+                checkstyle_severity(&issue.severity)
+            ));
+            write_xml_escaped(&issue.message, output);
+            output.push_str("\" source=\"");
+            write_xml_escaped(&format!("{}.{}", TOOL_NAME, issue.rule), output);
+            output.push_str("\"/>\n");
+        }
+        output.push_str("  </file>\n");
+    }
+    output.push_str("</checkstyle>\n");
+}
+
+/// Writes GitHub Actions workflow commands. When printed in a workflow step,
+/// GitHub shows each issue as an annotation on the matching line.
+fn write_github_report(results: &[FileLintResult], output: &mut String) {
+    for result in results {
+        for issue in &result.issues {
+            output.push_str("::");
+            output.push_str(github_command(&issue.severity));
+            output.push_str(" file=");
+            write_github_property_escaped(&result.file_path, output);
+            output.push_str(&format!(
+                ",line={},col={},endLine={},endColumn={},title=",
+                issue.line, issue.column, issue.end_line, issue.end_column
+            ));
+            write_github_property_escaped(&issue.rule, output);
+            output.push_str("::");
+            write_github_data_escaped(&issue.message, output);
+            output.push('\n');
+        }
+    }
+}
+
+fn write_junit_report(results: &[FileLintResult], output: &mut String) {
+    let mut failure_count = 0;
+    for result in results {
+        if !result.issues.is_empty() {
+            failure_count += 1;
+        }
+    }
+
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        TOOL_NAME,
+        results.len(),
+        failure_count
+    ));
+    output.push_str(&format!(
+        "  <testsuite name=\"lint\" tests=\"{}\" failures=\"{}\">\n",
+        results.len(),
+        failure_count
+    ));
+    for result in results {
+        output.push_str("    <testcase name=\"");
+        write_xml_escaped(&result.file_path, output);
+        output.push_str("\" classname=\"");
+        write_xml_escaped(&result.file_path, output);
+        if result.issues.is_empty() {
+            output.push_str("\"/>\n");
+            continue;
+        }
+        output.push_str("\">\n");
+        output.push_str(&format!(
+            "      <failure message=\"{} issue(s) found\" type=\"lint\">",
+            result.issues.len()
+        ));
+        for issue in &result.issues {
+            write_xml_escaped(
+                &format!(
+                    "{}:{}:{}: {} {}: {}\n",
+                    result.file_path,
+                    issue.line,
+                    issue.column,
+                    issue.severity.name(),
+                    issue.rule,
+                    issue.message
+                ),
+                output,
+            );
+        }
+        output.push_str("</failure>\n");
+        output.push_str("    </testcase>\n");
+    }
+    output.push_str("  </testsuite>\n");
+    output.push_str("</testsuites>\n");
+}
+
+/// SARIF result levels are error, warning, and note.
+fn sarif_level(severity: &LintSeverity) -> &'static str {
+    match severity {
+        LintSeverity::Error => "error",
+        LintSeverity::Warning => "warning",
+        LintSeverity::Info | LintSeverity::Hint => "note",
+    }
+}
+
+/// Checkstyle severities are error, warning, and info.
+fn checkstyle_severity(severity: &LintSeverity) -> &'static str {
+    match severity {
+        LintSeverity::Error => "error",
+        LintSeverity::Warning => "warning",
+        LintSeverity::Info | LintSeverity::Hint => "info",
+    }
+}
+
+/// GitHub Actions annotations are error, warning, and notice commands.
+fn github_command(severity: &LintSeverity) -> &'static str {
+    match severity {
+        LintSeverity::Error => "error",
+        LintSeverity::Warning => "warning",
+        LintSeverity::Info | LintSeverity::Hint => "notice",
+    }
+}
+
+fn write_xml_escaped(text: &str, output: &mut String) {
+    for character in text.chars() {
+        match character {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            character => output.push(character),
+        }
+    }
+}
+
+/// Escapes the message part of a workflow command, after the final `::`.
+fn write_github_data_escaped(text: &str, output: &mut String) {
+    for character in text.chars() {
+        match character {
+            '%' => output.push_str("%25"),
+            '\r' => output.push_str("%0D"),
+            '\n' => output.push_str("%0A"),
+            character => output.push(character),
+        }
+    }
+}
+
+/// Escapes a `key=value` property of a workflow command. On top of the
+/// message escapes, properties can't contain their separators.
+fn write_github_property_escaped(text: &str, output: &mut String) {
+    for character in text.chars() {
+        match character {
+            '%' => output.push_str("%25"),
+            '\r' => output.push_str("%0D"),
+            '\n' => output.push_str("%0A"),
+            ':' => output.push_str("%3A"),
+            ',' => output.push_str("%2C"),
+            character => output.push(character),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Project rules check all the linted files of a Godot project together, for
+//! issues no single file shows, like two scripts declaring the same
+//! `class_name`.
+//!
+//! They run in a second phase, after the linter checked each file on its own.
+//! The linter groups files by Godot project, the closest directory containing
+//! `project.godot`, and passes each project to the rules with the parsed tree
+//! of every file. The project root lets rules resolve `res://` paths.
+pub mod cyclic_preload;
+pub mod duplicate_class_name;
+pub mod missing_resource;
+pub mod unused_class_name;
+
+use crate::linter::lib::get_node_text;
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use crate::node_kind::GDScriptNodeKind;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Range, Tree};
+
+use cyclic_preload::CyclicPreloadRule;
+use duplicate_class_name::DuplicateClassNameRule;
+use missing_resource::MissingResourceRule;
+use unused_class_name::UnusedClassNameRule;
+
+/// One linted file of a project.
+pub struct ProjectFile {
+    pub path: PathBuf,
+    /// The canonical path of the file, used to find the file a `res://` path
+    /// points to.
+    pub canonical_path: PathBuf,
+    pub source_code: String,
+    pub tree: Tree,
+}
+
+/// The linted files of one Godot project.
+pub struct Project {
+    /// The directory containing `project.godot`. None if the files are not in
+    /// a Godot project, in which case `res://` paths can't be resolved.
+    pub root: Option<PathBuf>,
+    pub files: Vec<ProjectFile>,
+}
+
+impl Project {
+    /// Returns the path to show for a file in messages: its `res://` path if
+    /// the file is in the project, or the path it was linted with.
+    pub fn display_path(&self, file_index: usize) -> String {
+        let file = &self.files[file_index];
+        if let Some(root) = &self.root
+            && let Ok(canonical_root) = root.canonicalize()
+            && let Ok(relative_path) = file.canonical_path.strip_prefix(canonical_root)
+        {
+            let mut resource_path = String::from("res://");
+            for (index, component) in relative_path.components().enumerate() {
+                if index > 0 {
+                    resource_path.push('/');
+                }
+                resource_path.push_str(&component.as_os_str().to_string_lossy());
+            }
+            return resource_path;
+        }
+        file.path.to_string_lossy().to_string()
+    }
+
+    /// Resolves a path passed to `load()` or `preload()` in a file to a path
+    /// on the disk. Returns None for paths we can't resolve, like `uid://`
+    /// and `user://` paths, or `res://` paths outside a Godot project.
+    pub fn resolve_load_path(&self, file_index: usize, load_path: &str) -> Option<PathBuf> {
+        if let Some(project_path) = load_path.strip_prefix("res://") {
+            return self.root.as_ref().map(|root| root.join(project_path));
+        }
+        if load_path.contains("://") {
+            return None;
+        }
+        // Godot resolves other paths relative to the script's directory.
+        let directory = self.files[file_index].path.parent()?;
+        Some(directory.join(load_path))
+    }
+
+    /// Returns the index of the linted file at `path`, if any.
+    pub fn find_file(&self, path: &Path) -> Option<usize> {
+        let canonical_path = path.canonicalize().ok()?;
+        self.files
+            .iter()
+            .position(|file| file.canonical_path == canonical_path)
+    }
+}
+
+/// An issue found by a project rule, with the index of the file it belongs
+/// to in [`Project::files`].
+pub struct ProjectIssue {
+    pub file_index: usize,
+    pub issue: LintIssue,
+}
+
+pub trait ProjectRule {
+    /// Checks all the files of a project together and returns the issues
+    /// found in any of them.
+    fn check_project(&mut self, project: &Project) -> Vec<ProjectIssue>;
+}
+
+pub struct ProjectRuleDefinition {
+    pub name: &'static str,
+    /// One-line summary of what the rule checks, shown in machine-readable
+    /// reports like SARIF.
+    pub description: &'static str,
+    /// Severity of the issues this rule reports, unless the linter
+    /// configuration overrides it for this rule.
+    pub default_severity: LintSeverity,
+    pub create: fn(&LinterConfig) -> Box<dyn ProjectRule>,
+}
+
+/// List of all the rules that check a whole project.
+pub const ALL_PROJECT_RULES: &[ProjectRuleDefinition] = &[
+    ProjectRuleDefinition {
+        name: "duplicate-class-name",
+        description: "Detects scripts that declare the same `class_name`",
+        default_severity: LintSeverity::Error,
+        create: |_config| Box::new(DuplicateClassNameRule),
+    },
+    ProjectRuleDefinition {
+        name: "missing-resource",
+        description: "Detects load() and preload() calls with paths to files that don't exist",
+        default_severity: LintSeverity::Error,
+        create: |_config| Box::new(MissingResourceRule),
+    },
+    ProjectRuleDefinition {
+        name: "unused-class-name",
+        description: "Detects `class_name` declarations that no other linted script uses",
+        default_severity: LintSeverity::Info,
+        create: |_config| Box::new(UnusedClassNameRule),
+    },
+    ProjectRuleDefinition {
+        name: "cyclic-preload",
+        description: "Detects scripts that preload each other in a cycle",
+        default_severity: LintSeverity::Warning,
+        create: |_config| Box::new(CyclicPreloadRule),
+    },
+];
+
+/// A call to `load()` or `preload()` with a string literal path.
+pub struct LoadCall {
+    pub is_preload: bool,
+    /// The path without its quotes.
+    pub path: String,
+    /// The range of the string literal.
+    pub range: Range,
+}
+
+/// Collects the `load()` and `preload()` calls under `node`.
+pub fn find_load_calls(node: &Node, source_code: &str, load_calls: &mut Vec<LoadCall>) {
+    if GDScriptNodeKind::get_kind_from_ast_node(*node) == GDScriptNodeKind::Call
+        && let Some(function_node) = node.child(0)
+        && let Some(arguments_node) = node.child_by_field_name("arguments")
+    {
+        let function_name = get_node_text(&function_node, source_code);
+        if (function_name == "load" || function_name == "preload")
+            && let Some(path_node) = arguments_node.named_child(0)
+            && GDScriptNodeKind::get_kind_from_ast_node(path_node) == GDScriptNodeKind::String
+            && let Some(path) = get_string_value(get_node_text(&path_node, source_code))
+        {
+            load_calls.push(LoadCall {
+                is_preload: function_name == "preload",
+                path: path.to_string(),
+                range: path_node.range(),
+            });
+        }
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            find_load_calls(&cursor.node(), source_code, load_calls);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the name node of each `class_name` statement under `node`.
+pub fn find_class_name_nodes<'tree>(node: &Node<'tree>, name_nodes: &mut Vec<Node<'tree>>) {
+    if GDScriptNodeKind::get_kind_from_ast_node(*node) == GDScriptNodeKind::ClassName
+        && let Some(name_node) = node.child_by_field_name("name")
+    {
+        name_nodes.push(name_node);
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            find_class_name_nodes(&cursor.node(), name_nodes);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the content of a plain string literal. Returns None for strings
+/// with escape sequences, as load paths don't need them.
+fn get_string_value(literal: &str) -> Option<&str> {
+    let quote = literal.chars().next()?;
+    if quote != '"' && quote != '\'' {
+        return None;
+    }
+    let content = literal.strip_prefix(quote)?.strip_suffix(quote)?;
+    if content.contains('\\') || content.contains(quote) {
+        return None;
+    }
+    Some(content)
+}
@@ -0,0 +1,103 @@
+use crate::linter::LintIssue;
+use crate::linter::project_rules::{Project, ProjectIssue, ProjectRule, find_load_calls};
+use std::collections::VecDeque;
+use tree_sitter::Range;
+
+/// Reports preload cycles between linted scripts, like `a.gd` preloading
+/// `b.gd` which preloads `a.gd`. Godot can fail to load scripts in a preload
+/// cycle, so one of the preloads should become a `load()` call.
+pub struct CyclicPreloadRule;
+
+/// A preload of the linted file at `target_index`.
+struct PreloadEdge {
+    target_index: usize,
+    range: Range,
+}
+
+impl ProjectRule for CyclicPreloadRule {
+    fn check_project(&mut self, project: &Project) -> Vec<ProjectIssue> {
+        let mut edges: Vec<Vec<PreloadEdge>> = Vec::new();
+        for (file_index, file) in project.files.iter().enumerate() {
+            let mut load_calls = Vec::new();
+            find_load_calls(&file.tree.root_node(), &file.source_code, &mut load_calls);
+            let mut file_edges = Vec::new();
+            for load_call in load_calls {
+                if !load_call.is_preload {
+                    continue;
+                }
+                if let Some(resolved_path) = project.resolve_load_path(file_index, &load_call.path)
+                    && let Some(target_index) = project.find_file(&resolved_path)
+                {
+                    file_edges.push(PreloadEdge {
+                        target_index,
+                        range: load_call.range,
+                    });
+                }
+            }
+            edges.push(file_edges);
+        }
+
+        // We report each cycle once, on a preload in the cycle's file with the
+        // lowest index. To do that, we only look for paths back to a file
+        // through files with a higher index.
+        let mut issues = Vec::new();
+        for start_index in 0..edges.len() {
+            for edge in &edges[start_index] {
+                if edge.target_index < start_index {
+                    continue;
+                }
+                let Some(cycle) = find_path(&edges, edge.target_index, start_index) else {
+                    continue;
+                };
+                let mut cycle_paths = vec![project.display_path(start_index)];
+                for file_index in cycle {
+                    cycle_paths.push(project.display_path(file_index));
+                }
+                issues.push(ProjectIssue {
+                    file_index: start_index,
+                    issue: LintIssue::from_range(
+                        &edge.range,
+                        "cyclic-preload".to_string(),
+                        format!(
+                            "Cyclic preload: {}. Consider using load() for one of these scripts",
+                            cycle_paths.join(" -> ")
+                        ),
+                    ),
+                });
+            }
+        }
+        issues
+    }
+}
+
+/// Returns the files on the shortest preload path from `from_index` to
+/// `to_index`, both included, only going through files with an index of at
+/// least `to_index`.
+fn find_path(edges: &[Vec<PreloadEdge>], from_index: usize, to_index: usize) -> Option<Vec<usize>> {
+    let mut previous_indices: Vec<Option<usize>> = vec![None; edges.len()];
+    let mut visited = vec![false; edges.len()];
+    let mut queue = VecDeque::new();
+    visited[from_index] = true;
+    queue.push_back(from_index);
+
+    while let Some(current_index) = queue.pop_front() {
+        if current_index == to_index {
+            let mut path = vec![current_index];
+            let mut path_index = current_index;
+            while let Some(previous_index) = previous_indices[path_index] {
+                path.push(previous_index);
+                path_index = previous_index;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for edge in &edges[current_index] {
+            if edge.target_index >= to_index && !visited[edge.target_index] {
+                visited[edge.target_index] = true;
+                previous_indices[edge.target_index] = Some(current_index);
+                queue.push_back(edge.target_index);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,59 @@
+use crate::linter::LintIssue;
+use crate::linter::lib::get_node_text;
+use crate::linter::project_rules::{Project, ProjectIssue, ProjectRule, find_class_name_nodes};
+use std::collections::HashMap;
+use tree_sitter::Range;
+
+pub struct DuplicateClassNameRule;
+
+impl ProjectRule for DuplicateClassNameRule {
+    fn check_project(&mut self, project: &Project) -> Vec<ProjectIssue> {
+        // We keep class names in the order we find them so issues come out in
+        // a stable order.
+        let mut class_names: Vec<String> = Vec::new();
+        let mut declarations: HashMap<String, Vec<(usize, Range)>> = HashMap::new();
+        for (file_index, file) in project.files.iter().enumerate() {
+            let mut name_nodes = Vec::new();
+            find_class_name_nodes(&file.tree.root_node(), &mut name_nodes);
+            for name_node in name_nodes {
+                let name = get_node_text(&name_node, &file.source_code).to_string();
+                if !declarations.contains_key(&name) {
+                    class_names.push(name.clone());
+                }
+                declarations
+                    .entry(name)
+                    .or_default()
+                    .push((file_index, name_node.range()));
+            }
+        }
+
+        let mut issues = Vec::new();
+        for name in &class_names {
+            let locations = &declarations[name];
+            if locations.len() < 2 {
+                continue;
+            }
+            for (file_index, range) in locations {
+                let mut other_paths = Vec::new();
+                for (other_file_index, _) in locations {
+                    if other_file_index != file_index {
+                        other_paths.push(project.display_path(*other_file_index));
+                    }
+                }
+                issues.push(ProjectIssue {
+                    file_index: *file_index,
+                    issue: LintIssue::from_range(
+                        range,
+                        "duplicate-class-name".to_string(),
+                        format!(
+                            "Class name '{}' is also declared in {}",
+                            name,
+                            other_paths.join(", ")
+                        ),
+                    ),
+                });
+            }
+        }
+        issues
+    }
+}
@@ -0,0 +1,32 @@
+use crate::linter::LintIssue;
+use crate::linter::project_rules::{Project, ProjectIssue, ProjectRule, find_load_calls};
+
+pub struct MissingResourceRule;
+
+impl ProjectRule for MissingResourceRule {
+    fn check_project(&mut self, project: &Project) -> Vec<ProjectIssue> {
+        let mut issues = Vec::new();
+        for (file_index, file) in project.files.iter().enumerate() {
+            let mut load_calls = Vec::new();
+            find_load_calls(&file.tree.root_node(), &file.source_code, &mut load_calls);
+            for load_call in load_calls {
+                let Some(resolved_path) = project.resolve_load_path(file_index, &load_call.path)
+                else {
+                    continue;
+                };
+                if resolved_path.exists() {
+                    continue;
+                }
+                issues.push(ProjectIssue {
+                    file_index,
+                    issue: LintIssue::from_range(
+                        &load_call.range,
+                        "missing-resource".to_string(),
+                        format!("Resource '{}' does not exist", load_call.path),
+                    ),
+                });
+            }
+        }
+        issues
+    }
+}
@@ -0,0 +1,83 @@
+use crate::linter::LintIssue;
+use crate::linter::lib::get_node_text;
+use crate::linter::project_rules::{Project, ProjectIssue, ProjectRule, find_class_name_nodes};
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// Reports classes whose name appears nowhere in the linted files besides
+/// their declaration. The rule only sees the linted files, so it only runs on
+/// files inside a Godot project and assumes the whole project gets linted.
+pub struct UnusedClassNameRule;
+
+impl ProjectRule for UnusedClassNameRule {
+    fn check_project(&mut self, project: &Project) -> Vec<ProjectIssue> {
+        if project.root.is_none() {
+            return Vec::new();
+        }
+
+        let mut declarations = Vec::new();
+        for (file_index, file) in project.files.iter().enumerate() {
+            let mut name_nodes = Vec::new();
+            find_class_name_nodes(&file.tree.root_node(), &mut name_nodes);
+            for name_node in name_nodes {
+                declarations.push((file_index, name_node));
+            }
+        }
+        if declarations.is_empty() {
+            return Vec::new();
+        }
+
+        // Counts the tokens matching each class name in all files. A class is
+        // used if its name appears as a token outside of its declaration.
+        let mut token_counts: HashMap<&str, usize> = HashMap::new();
+        for (file_index, name_node) in &declarations {
+            let name = get_node_text(name_node, &project.files[*file_index].source_code);
+            token_counts.insert(name, 0);
+        }
+        for file in &project.files {
+            count_tokens(&file.tree.root_node(), &file.source_code, &mut token_counts);
+        }
+
+        let mut issues = Vec::new();
+        for (file_index, name_node) in declarations {
+            let name = get_node_text(&name_node, &project.files[file_index].source_code);
+            // Duplicated class names count each other as uses, which is fine:
+            // the duplicate-class-name rule reports them.
+            if token_counts.get(name).copied().unwrap_or(0) > 1 {
+                continue;
+            }
+            issues.push(ProjectIssue {
+                file_index,
+                issue: LintIssue::from_node(
+                    &name_node,
+                    "unused-class-name".to_string(),
+                    format!(
+                        "Class name '{}' is not used in any linted script. Consider removing it",
+                        name
+                    ),
+                ),
+            });
+        }
+        issues
+    }
+}
+
+fn count_tokens(node: &Node, source_code: &str, token_counts: &mut HashMap<&str, usize>) {
+    if node.child_count() == 0 {
+        let text = get_node_text(node, source_code);
+        if let Some(count) = token_counts.get_mut(text) {
+            *count += 1;
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            count_tokens(&cursor.node(), source_code, token_counts);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
@@ -1,5 +1,63 @@
+use crate::linter::LintSeverity;
+use crate::linter::project_rules::ALL_PROJECT_RULES;
 use crate::linter::rules::ALL_RULES;
-use std::collections::HashSet;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// Rules that check names against a naming convention. Each of them accepts
+/// a custom [`NamingConvention`] in the linter config.
+pub const NAMING_RULE_NAMES: &[&str] = &[
+    "class-name",
+    "constant-name",
+    "enum-member-name",
+    "enum-name",
+    "function-argument-name",
+    "function-name",
+    "loop-variable-name",
+    "signal-name",
+    "variable-name",
+];
+
+/// Rules that only run when the linter config gives them a severity, like
+/// `--severity class-definitions-order=warning`. They enforce choices not
+/// every project makes, or only give accurate results when linting the whole
+/// project.
+pub const RULES_DISABLED_BY_DEFAULT: &[&str] = &["class-definitions-order", "unused-class-name"];
+
+/// Customizes the names a naming rule accepts. For example, a project can
+/// accept the `_on_Button_pressed` callbacks Godot's editor generates.
+#[derive(Debug, Clone, Default)]
+pub struct NamingConvention {
+    /// If set, replaces the rule's built-in pattern: names are valid if they
+    /// match this regex. Use `^` and `$` to match the whole name.
+    pub pattern: Option<Regex>,
+    /// Names that are always valid, whatever the pattern.
+    pub allowed_names: HashSet<String>,
+}
+
+impl NamingConvention {
+    /// Returns true if `name` follows this convention. Without a custom
+    /// pattern, calls `is_valid_by_default` with the name.
+    pub fn is_valid(&self, name: &str, is_valid_by_default: fn(&str) -> bool) -> bool {
+        if self.allowed_names.contains(name) {
+            return true;
+        }
+        match &self.pattern {
+            Some(pattern) => pattern.is_match(name),
+            None => is_valid_by_default(name),
+        }
+    }
+
+    /// Describes what names should look like, to complete a message like
+    /// "Signal name 'X' should ...". Returns `default_expectation` without a
+    /// custom pattern.
+    pub fn describe_expectation(&self, default_expectation: &str) -> String {
+        match &self.pattern {
+            Some(pattern) => format!("match the pattern `{}`", pattern.as_str()),
+            None => default_expectation.to_string(),
+        }
+    }
+}
 
 /// Parse disabled rules from command line arguments or configuration
 pub fn parse_disabled_rules(rules_string: &str) -> HashSet<String> {
@@ -10,9 +68,33 @@ pub fn parse_disabled_rules(rules_string: &str) -> HashSet<String> {
         .collect()
 }
 
-/// Get all available rule names
+/// Parse per-rule severities from a comma-separated list of `rule=severity`
+/// pairs, like `unused-argument=error,private-access=warning`. Returns the
+/// invalid pair as the error.
+pub fn parse_rule_severities(
+    severities_string: &str,
+) -> Result<HashMap<String, LintSeverity>, String> {
+    let mut rule_severities = HashMap::new();
+    for pair in severities_string.split(',').map(str::trim) {
+        if pair.is_empty() {
+            continue;
+        }
+        let Some((rule_name, severity_name)) = pair.split_once('=') else {
+            return Err(pair.to_string());
+        };
+        let Some(severity) = LintSeverity::from_name(severity_name.trim()) else {
+            return Err(pair.to_string());
+        };
+        rule_severities.insert(rule_name.trim().to_string(), severity);
+    }
+    Ok(rule_severities)
+}
+
+/// Get all available rule names, including project rules
 pub fn get_all_rule_names() -> Vec<&'static str> {
-    ALL_RULES.iter().map(|rule| rule.name).collect()
+    let mut rule_names: Vec<&'static str> = ALL_RULES.iter().map(|rule| rule.name).collect();
+    rule_names.extend(ALL_PROJECT_RULES.iter().map(|rule| rule.name));
+    rule_names
 }
 
 /// Validate that all provided rule names are valid
@@ -1,3 +1,4 @@
+pub mod class_definitions_order;
 pub mod class_name;
 pub mod comparison_with_itself;
 pub mod constant_name;
@@ -12,12 +13,16 @@ pub mod no_else_return;
 pub mod private_access;
 pub mod signal_name;
 pub mod standalone_expression;
+pub mod syntax_error;
 pub mod unnecessary_pass;
 pub mod unused_argument;
+pub mod unused_suppression;
 pub mod variable_name;
 
-use crate::linter::{LintIssue, LinterConfig};
+use crate::linter::ignore_patterns::Suppressions;
+use crate::linter::{LintIssue, LintSeverity, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
+use std::collections::HashSet;
 use tree_sitter::Node;
 
 pub trait Rule {
@@ -34,9 +39,20 @@ pub trait Rule {
         Vec::new()
     }
 
+    /// This is called once with the root of the AST, before visiting its
+    /// nodes, for rules that need the whole tree at once, like reporting
+    /// syntax errors.
+    fn check_tree(&mut self, _root: &Node, _source_code: &str) -> Vec<LintIssue> {
+        Vec::new()
+    }
+
     /// The linter calls this function for each node matching the nodes in
     /// get_target_ast_nodes when traversing the AST. This is the main method
     /// rules should use if possible to check for issue.
+    ///
+    /// Rules that can repair the code attach edits to the issues they return
+    /// with `LintIssue::with_fix()`. The helpers in `linter::fixes` build
+    /// common edits.
     fn check_node(&mut self, _node: &Node, _source_code: &str) -> Vec<LintIssue> {
         Vec::new()
     }
@@ -48,8 +64,21 @@ pub trait Rule {
     fn finalize(&mut self, _source_code: &str) -> Vec<LintIssue> {
         Vec::new()
     }
+
+    /// This is called last, after the linter filtered the issues of all rules
+    /// with the file's suppression comments. `checked_rules` lists the rules
+    /// that ran on the file. It lets rules report on the suppression comments
+    /// themselves, like comments that suppress nothing.
+    fn check_suppressions(
+        &mut self,
+        _suppressions: &Suppressions,
+        _checked_rules: &HashSet<&str>,
+    ) -> Vec<LintIssue> {
+        Vec::new()
+    }
 }
 
+use class_definitions_order::ClassDefinitionsOrderRule;
 use class_name::ClassNameRule;
 use comparison_with_itself::ComparisonWithItselfRule;
 use constant_name::ConstantNameRule;
@@ -64,20 +93,36 @@ use no_else_return::NoElseReturnRule;
 use private_access::PrivateAccessRule;
 use signal_name::SignalNameRule;
 use standalone_expression::StandaloneExpressionRule;
+use syntax_error::SyntaxErrorRule;
 use unnecessary_pass::UnnecessaryPassRule;
 use unused_argument::UnusedArgumentRule;
+use unused_suppression::UnusedSuppressionRule;
 use variable_name::VariableNameRule;
 
 pub struct RuleDefinition {
     pub name: &'static str,
+    /// One-line summary of what the rule checks, shown in machine-readable
+    /// reports like SARIF.
+    pub description: &'static str,
+    /// Severity of the issues this rule reports, unless the linter
+    /// configuration overrides it for this rule.
+    pub default_severity: LintSeverity,
     pub create: fn(&LinterConfig) -> Box<dyn Rule>,
 }
 
 /// List of all the rules available in the linter. The linter will only run
 /// these plus the ones that have not been disabled in the config.
 pub const ALL_RULES: &[RuleDefinition] = &[
+    RuleDefinition {
+        name: "syntax-error",
+        description: "Reports code the parser can't read, with its location",
+        default_severity: LintSeverity::Error,
+        create: |_config| Box::new(SyntaxErrorRule),
+    },
     RuleDefinition {
         name: "duplicated-load",
+        description: "Detects copy-pasted load() calls for the same path",
+        default_severity: LintSeverity::Warning,
         create: |_config| {
             Box::new(DuplicatedLoadRule {
                 load_paths: std::collections::HashMap::new(),
@@ -86,66 +131,110 @@ pub const ALL_RULES: &[RuleDefinition] = &[
     },
     RuleDefinition {
         name: "standalone-expression",
+        description: "Detects standalone expressions that aren't used",
+        default_severity: LintSeverity::Warning,
         create: |_config| Box::new(StandaloneExpressionRule),
     },
     RuleDefinition {
         name: "unnecessary-pass",
+        description: "Detects pass statements when other statements are present",
+        default_severity: LintSeverity::Warning,
         create: |_config| Box::new(UnnecessaryPassRule),
     },
     RuleDefinition {
         name: "unused-argument",
+        description: "Detects unused function arguments",
+        default_severity: LintSeverity::Warning,
         create: |_config| Box::new(UnusedArgumentRule),
     },
     RuleDefinition {
         name: "comparison-with-itself",
+        description: "Detects redundant comparisons like `x == x`",
+        default_severity: LintSeverity::Warning,
         create: |_config| Box::new(ComparisonWithItselfRule),
     },
     RuleDefinition {
         name: "private-access",
+        description: "Detects calls to private methods or variable references (prefixed with `_`)",
+        default_severity: LintSeverity::Error,
         create: |_config| Box::new(PrivateAccessRule),
     },
     RuleDefinition {
         name: "max-line-length",
+        description: "Validates maximum line length",
+        default_severity: LintSeverity::Warning,
         create: |config| Box::new(MaxLineLengthRule::new(config)),
     },
     RuleDefinition {
         name: "no-else-return",
+        description: "Detects unnecessary else after `if`/`elif` blocks that end with `return`",
+        default_severity: LintSeverity::Warning,
         create: |_config| Box::new(NoElseReturnRule),
     },
     RuleDefinition {
         name: "function-name",
-        create: |_config| Box::new(FunctionNameRule),
+        description: "Validates function names (`snake_case`, `_private_snake_case`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(FunctionNameRule::new(config)),
     },
     RuleDefinition {
         name: "class-name",
-        create: |_config| Box::new(ClassNameRule),
+        description: "Validates class names (`PascalCase`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(ClassNameRule::new(config)),
     },
     RuleDefinition {
         name: "signal-name",
-        create: |_config| Box::new(SignalNameRule),
+        description: "Validates signal names (`snake_case`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(SignalNameRule::new(config)),
     },
     RuleDefinition {
         name: "variable-name",
-        create: |_config| Box::new(VariableNameRule),
+        description: "Validates class variable names (`snake_case` or `_private_snake_case`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(VariableNameRule::new(config)),
     },
     RuleDefinition {
         name: "function-argument-name",
-        create: |_config| Box::new(FunctionArgumentNameRule),
+        description: "Validates function argument names (`snake_case` or `_private_snake_case`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(FunctionArgumentNameRule::new(config)),
     },
     RuleDefinition {
         name: "loop-variable-name",
-        create: |_config| Box::new(LoopVariableNameRule),
+        description: "Validates loop variable names (`snake_case` or `_private_snake_case`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(LoopVariableNameRule::new(config)),
     },
     RuleDefinition {
         name: "enum-name",
-        create: |_config| Box::new(EnumNameRule),
+        description: "Validates enum names (`PascalCase`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(EnumNameRule::new(config)),
     },
     RuleDefinition {
         name: "enum-member-name",
-        create: |_config| Box::new(EnumMemberNameRule),
+        description: "Validates enum element names (`CONSTANT_CASE`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(EnumMemberNameRule::new(config)),
     },
     RuleDefinition {
         name: "constant-name",
-        create: |_config| Box::new(ConstantNameRule),
+        description: "Validates constant names (`CONSTANT_CASE`)",
+        default_severity: LintSeverity::Error,
+        create: |config| Box::new(ConstantNameRule::new(config)),
+    },
+    RuleDefinition {
+        name: "class-definitions-order",
+        description: "Detects declarations out of the order `--reorder-code` sorts them in",
+        default_severity: LintSeverity::Warning,
+        create: |config| Box::new(ClassDefinitionsOrderRule::new(config)),
+    },
+    RuleDefinition {
+        name: "unused-suppression",
+        description: "Detects suppression comments that suppress nothing or name unknown rules",
+        default_severity: LintSeverity::Warning,
+        create: |_config| Box::new(UnusedSuppressionRule),
     },
 ];
@@ -0,0 +1,143 @@
+use crate::linter::rules::Rule;
+use crate::linter::{LintIssue, LinterConfig};
+use crate::node_kind::GDScriptNodeKind;
+use crate::parser::{RegionWithDisabledFormatting, find_disabled_regions};
+use crate::reorder::{self, DeclarationKind, ReorderConfiguration, ReorderItem};
+use tree_sitter::Node;
+
+/// Reports declarations that are not in the order the formatter's
+/// `--reorder-code` option would put them in. It builds the same reorder plan
+/// as the formatter, so the two never disagree, and reports the first
+/// out-of-order declaration of each class.
+pub struct ClassDefinitionsOrderRule {
+    reorder_config: ReorderConfiguration,
+}
+
+impl ClassDefinitionsOrderRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            reorder_config: config.reorder.clone(),
+        }
+    }
+
+    /// Checks the declarations of `parent`, the source node or an inner class
+    /// body, then the bodies of its inner classes.
+    fn check_class(
+        &self,
+        parent: Node,
+        source_code: &str,
+        disabled_regions: &[RegionWithDisabledFormatting],
+        issues: &mut Vec<LintIssue>,
+    ) {
+        let plan = reorder::build_reorder_plan(
+            parent,
+            source_code,
+            disabled_regions,
+            &self.reorder_config,
+        );
+        let mut source_order: Vec<&ReorderItem> = plan.items.iter().collect();
+        source_order.sort_by_key(|item| (item.child_index, item.sub_child));
+        let first_mismatch = plan
+            .items
+            .iter()
+            .zip(source_order)
+            .find(|(planned, current)| {
+                (planned.child_index, planned.sub_child) != (current.child_index, current.sub_child)
+            });
+        if let Some((planned, current)) = first_mismatch {
+            issues.push(create_issue(parent, planned, current));
+        }
+
+        let mut cursor = parent.walk();
+        for child in parent.children(&mut cursor) {
+            let kind = GDScriptNodeKind::get_kind_from_ast_node(child);
+            if kind != GDScriptNodeKind::ClassDefinition && kind != GDScriptNodeKind::InnerClass {
+                continue;
+            }
+            let mut class_cursor = child.walk();
+            for class_child in child.children(&mut class_cursor) {
+                if GDScriptNodeKind::get_kind_from_ast_node(class_child)
+                    == GDScriptNodeKind::ClassBody
+                {
+                    self.check_class(class_child, source_code, disabled_regions, issues);
+                }
+            }
+        }
+    }
+}
+
+impl Rule for ClassDefinitionsOrderRule {
+    fn check_tree(&mut self, root: &Node, source_code: &str) -> Vec<LintIssue> {
+        // The formatter doesn't reorder code with syntax errors.
+        if root.has_error() {
+            return Vec::new();
+        }
+        let disabled_regions = find_disabled_regions(source_code);
+        let mut issues = Vec::new();
+        self.check_class(*root, source_code, &disabled_regions, &mut issues);
+        issues
+    }
+}
+
+/// Creates the issue for `planned`, the declaration that should come before
+/// `current`, the declaration at its position in the source code.
+fn create_issue(parent: Node, planned: &ReorderItem, current: &ReorderItem) -> LintIssue {
+    let message = format!(
+        "{} should come before {}",
+        describe_item(planned),
+        describe_item(current)
+    );
+    let mut declaration = parent.child(planned.child_index as u32);
+    if let Some(sub_child) = planned.sub_child {
+        declaration = declaration.and_then(|node| node.child(sub_child as u32));
+    }
+    let Some(declaration) = declaration else {
+        return LintIssue::new(
+            parent.start_position().row + 1,
+            parent.start_position().column + 1,
+            "class-definitions-order".to_string(),
+            message,
+        );
+    };
+    match declaration.child_by_field_name("name") {
+        Some(name_node) if !planned.is_pinned => {
+            LintIssue::from_node(&name_node, "class-definitions-order".to_string(), message)
+        }
+        _ => LintIssue::new(
+            declaration.start_position().row + 1,
+            declaration.start_position().column + 1,
+            "class-definitions-order".to_string(),
+            message,
+        ),
+    }
+}
+
+/// Describes a reorder item for lint messages, like "signal `died`".
+fn describe_item(item: &ReorderItem) -> String {
+    if item.is_pinned {
+        return "the `# fmt: off` region".to_string();
+    }
+    let kind_name = match item.classification {
+        DeclarationKind::ClassAnnotation => "annotation",
+        DeclarationKind::ClassName => "class_name",
+        DeclarationKind::Extends => return "the `extends` statement".to_string(),
+        DeclarationKind::Docstring => return "the class docstring".to_string(),
+        DeclarationKind::Signal => "signal",
+        DeclarationKind::Enum => "enum",
+        DeclarationKind::Constant => "constant",
+        DeclarationKind::StaticVariable => "static variable",
+        DeclarationKind::ExportVariable if item.name.is_empty() => {
+            return "the export grouping annotation".to_string();
+        }
+        DeclarationKind::ExportVariable => "export variable",
+        DeclarationKind::RegularVariable => "variable",
+        DeclarationKind::OnReadyVariable => "onready variable",
+        DeclarationKind::Method => "method",
+        DeclarationKind::InnerClass => "class",
+        DeclarationKind::Unknown => {
+            let first_line = item.name.lines().next().unwrap_or_default().trim();
+            return format!("`{}`", first_line);
+        }
+    };
+    format!("{} `{}`", kind_name, item.name)
+}
@@ -1,6 +1,6 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::LintIssue;
+use crate::linter::lib::get_node_text;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
@@ -25,12 +25,9 @@ impl Rule for ComparisonWithItselfRule {
                 let right_text = get_node_text(&right_node, source_code);
 
                 if left_text == right_text {
-                    let (line, column) = get_line_column(node);
-                    issues.push(LintIssue::new(
-                        line,
-                        column,
+                    issues.push(LintIssue::from_node(
+                        node,
                         "comparison-with-itself".to_string(),
-                        LintSeverity::Warning,
                         format!(
                             "Redundant comparison '{}' - comparing expression with itself",
                             get_node_text(node, source_code)
@@ -1,13 +1,22 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::{CONSTANT_CASE, PASCAL_CASE, PRIVATE_CONSTANT_CASE};
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
-pub struct ConstantNameRule;
+pub struct ConstantNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl ConstantNameRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("constant-name"),
+        }
+    }
+
     fn is_valid_constant_name(name: &str) -> bool {
         CONSTANT_CASE.is_match(name) || PRIVATE_CONSTANT_CASE.is_match(name)
     }
@@ -51,29 +60,36 @@ impl Rule for ConstantNameRule {
 
             if is_preload_const {
                 // For all load/preload constants, check load naming rules
-                if !Self::is_valid_load_constant_name(name) {
-                    let (line, column) = get_line_column(&name_node);
-                    issues.push(LintIssue::new(
-                        line,
-                        column,
+                if !self
+                    .naming_convention
+                    .is_valid(name, Self::is_valid_load_constant_name)
+                {
+                    issues.push(LintIssue::from_node(
+                        &name_node,
                         "constant-name".to_string(),
-                        LintSeverity::Error,
                         format!(
-                            "Preload constant name '{}' should be in PascalCase or CONSTANT_CASE format",
-                            name
+                            "Preload constant name '{}' should {}",
+                            name,
+                            self.naming_convention
+                                .describe_expectation("be in PascalCase or CONSTANT_CASE format")
                         ),
                     ));
                 }
             } else {
                 // For regular constants, just check regular rules
-                if !Self::is_valid_constant_name(name) {
-                    let (line, column) = get_line_column(&name_node);
-                    issues.push(LintIssue::new(
-                        line,
-                        column,
+                if !self
+                    .naming_convention
+                    .is_valid(name, Self::is_valid_constant_name)
+                {
+                    issues.push(LintIssue::from_node(
+                        &name_node,
                         "constant-name".to_string(),
-                        LintSeverity::Error,
-                        format!("Constant name '{}' should be in CONSTANT_CASE format", name),
+                        format!(
+                            "Constant name '{}' should {}",
+                            name,
+                            self.naming_convention
+                                .describe_expectation("be in CONSTANT_CASE format")
+                        ),
                     ));
                 }
             }
@@ -1,12 +1,12 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::LintIssue;
+use crate::linter::lib::get_node_text;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
 use crate::node_kind::GDScriptNodeKind;
 use std::collections::HashMap;
-use tree_sitter::Node;
+use tree_sitter::{Node, Range};
 
 pub struct DuplicatedLoadRule {
-    pub load_paths: HashMap<String, Vec<(usize, usize)>>,
+    pub load_paths: HashMap<String, Vec<Range>>,
 }
 
 impl Rule for DuplicatedLoadRule {
@@ -28,11 +28,10 @@ impl Rule for DuplicatedLoadRule {
                             == GDScriptNodeKind::String
                         {
                             let path = get_node_text(&arg_node, source_code);
-                            let (line, column) = get_line_column(&arg_node);
                             self.load_paths
                                 .entry(path.to_string())
                                 .or_default()
-                                .push((line, column));
+                                .push(arg_node.range());
                         }
                         if !args_cursor.goto_next_sibling() {
                             break;
@@ -49,12 +48,10 @@ impl Rule for DuplicatedLoadRule {
 
         for (path, locations) in &self.load_paths {
             if locations.len() > 1 {
-                for (line, column) in locations {
-                    issues.push(LintIssue::new(
-                        *line,
-                        *column,
+                for range in locations {
+                    issues.push(LintIssue::from_range(
+                        range,
                         "duplicated-load".to_string(),
-                        LintSeverity::Warning,
                         format!(
                             "Duplicated load of '{}'. Consider extracting to a constant.",
                             path
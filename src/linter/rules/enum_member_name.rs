@@ -1,12 +1,21 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::CONSTANT_CASE;
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
-pub struct EnumMemberNameRule;
+pub struct EnumMemberNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl EnumMemberNameRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("enum-member-name"),
+        }
+    }
+
     fn is_valid_enum_member_name(name: &str) -> bool {
         CONSTANT_CASE.is_match(name)
     }
@@ -33,17 +42,18 @@ impl Rule for EnumMemberNameRule {
                         let element_name = get_node_text(&element_name_node, source_code);
                         // Skip empty enum member names (happens with empty enums)
                         if !element_name.is_empty()
-                            && !Self::is_valid_enum_member_name(element_name)
+                            && !self
+                                .naming_convention
+                                .is_valid(element_name, Self::is_valid_enum_member_name)
                         {
-                            let (line, column) = get_line_column(&element_name_node);
-                            issues.push(LintIssue::new(
-                                line,
-                                column,
+                            issues.push(LintIssue::from_node(
+                                &element_name_node,
                                 "enum-member-name".to_string(),
-                                LintSeverity::Error,
                                 format!(
-                                    "Enum element name '{}' should be in CONSTANT_CASE format",
-                                    element_name
+                                    "Enum element name '{}' should {}",
+                                    element_name,
+                                    self.naming_convention
+                                        .describe_expectation("be in CONSTANT_CASE format")
                                 ),
                             ));
                         }
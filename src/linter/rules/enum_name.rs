@@ -1,12 +1,21 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::PASCAL_CASE;
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
-pub struct EnumNameRule;
+pub struct EnumNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl EnumNameRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("enum-name"),
+        }
+    }
+
     fn is_valid_enum_name(name: &str) -> bool {
         PASCAL_CASE.is_match(name)
     }
@@ -23,14 +32,19 @@ impl Rule for EnumNameRule {
         // Check enum name
         if let Some(name_node) = node.child_by_field_name("name") {
             let name = get_node_text(&name_node, source_code);
-            if !Self::is_valid_enum_name(name) {
-                let (line, column) = get_line_column(&name_node);
-                issues.push(LintIssue::new(
-                    line,
-                    column,
+            if !self
+                .naming_convention
+                .is_valid(name, Self::is_valid_enum_name)
+            {
+                issues.push(LintIssue::from_node(
+                    &name_node,
                     "enum-name".to_string(),
-                    LintSeverity::Error,
-                    format!("Enum name '{}' should be in PascalCase format", name),
+                    format!(
+                        "Enum name '{}' should {}",
+                        name,
+                        self.naming_convention
+                            .describe_expectation("be in PascalCase format")
+                    ),
                 ));
             }
         }
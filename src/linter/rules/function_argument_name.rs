@@ -1,15 +1,27 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::fixes::{make_local_rename_edits, to_snake_case};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::{PRIVATE_SNAKE_CASE, SNAKE_CASE};
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
-pub struct FunctionArgumentNameRule;
+pub struct FunctionArgumentNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl FunctionArgumentNameRule {
-    fn is_valid_argument_name(name: &str) -> bool {
-        SNAKE_CASE.is_match(name) || PRIVATE_SNAKE_CASE.is_match(name)
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("function-argument-name"),
+        }
+    }
+
+    fn is_valid_argument_name(&self, name: &str) -> bool {
+        self.naming_convention.is_valid(name, |name| {
+            SNAKE_CASE.is_match(name) || PRIVATE_SNAKE_CASE.is_match(name)
+        })
     }
 }
 
@@ -32,26 +44,41 @@ impl Rule for FunctionArgumentNameRule {
                         param_kind,
                         GDScriptNodeKind::Identifier | GDScriptNodeKind::Parameter
                     ) {
-                        let param_name = if param_kind == GDScriptNodeKind::Identifier {
-                            get_node_text(&param_node, source_code)
-                        } else if let Some(name_child) = param_node.child(0) {
-                            get_node_text(&name_child, source_code)
+                        let name_node = if param_kind == GDScriptNodeKind::Identifier {
+                            Some(param_node)
                         } else {
-                            ""
+                            param_node.child(0)
+                        };
+                        let param_name = match name_node {
+                            Some(name_node) => get_node_text(&name_node, source_code),
+                            None => "",
                         };
 
-                        if !param_name.is_empty() && !Self::is_valid_argument_name(param_name) {
-                            let (line, column) = get_line_column(&param_node);
-                            issues.push(LintIssue::new(
-                                line,
-                                column,
+                        if !param_name.is_empty() && !self.is_valid_argument_name(param_name) {
+                            let mut issue = LintIssue::from_node(
+                                &param_node,
                                 "function-argument-name".to_string(),
-                                LintSeverity::Error,
                                 format!(
-                                    "Function argument '{}' should be in snake_case or _private_snake_case format",
-                                    param_name
+                                    "Function argument '{}' should {}",
+                                    param_name,
+                                    self.naming_convention.describe_expectation(
+                                        "be in snake_case or _private_snake_case format"
+                                    )
                                 ),
-                            ));
+                            );
+                            let new_name = to_snake_case(param_name);
+                            if let Some(name_node) = name_node
+                                && self.is_valid_argument_name(&new_name)
+                                && let Some(edits) = make_local_rename_edits(
+                                    &name_node,
+                                    node,
+                                    &new_name,
+                                    source_code,
+                                )
+                            {
+                                issue = issue.with_fix(edits);
+                            }
+                            issues.push(issue);
                         }
                     }
                     if !params_cursor.goto_next_sibling() {
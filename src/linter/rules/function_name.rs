@@ -1,12 +1,21 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::{PRIVATE_SNAKE_CASE, SNAKE_CASE};
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
-pub struct FunctionNameRule;
+pub struct FunctionNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl FunctionNameRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("function-name"),
+        }
+    }
+
     fn is_valid_function_name(name: &str) -> bool {
         SNAKE_CASE.is_match(name) || PRIVATE_SNAKE_CASE.is_match(name)
     }
@@ -22,16 +31,18 @@ impl Rule for FunctionNameRule {
 
         if let Some(name_node) = node.child_by_field_name("name") {
             let name = get_node_text(&name_node, source_code);
-            if !Self::is_valid_function_name(name) {
-                let (line, column) = get_line_column(&name_node);
-                issues.push(LintIssue::new(
-                    line,
-                    column,
+            if !self
+                .naming_convention
+                .is_valid(name, Self::is_valid_function_name)
+            {
+                issues.push(LintIssue::from_node(
+                    &name_node,
                     "function-name".to_string(),
-                    LintSeverity::Error,
                     format!(
-                        "Function name '{}' should be in snake_case, _private_snake_case format",
-                        name
+                        "Function name '{}' should {}",
+                        name,
+                        self.naming_convention
+                            .describe_expectation("be in snake_case, _private_snake_case format")
                     ),
                 ));
             }
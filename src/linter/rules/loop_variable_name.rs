@@ -1,15 +1,27 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::fixes::{make_local_rename_edits, to_snake_case};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::{PRIVATE_SNAKE_CASE, SNAKE_CASE};
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
-pub struct LoopVariableNameRule;
+pub struct LoopVariableNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl LoopVariableNameRule {
-    fn is_valid_loop_variable_name(name: &str) -> bool {
-        SNAKE_CASE.is_match(name) || PRIVATE_SNAKE_CASE.is_match(name)
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("loop-variable-name"),
+        }
+    }
+
+    fn is_valid_loop_variable_name(&self, name: &str) -> bool {
+        self.naming_convention.is_valid(name, |name| {
+            SNAKE_CASE.is_match(name) || PRIVATE_SNAKE_CASE.is_match(name)
+        })
     }
 }
 
@@ -25,35 +37,46 @@ impl Rule for LoopVariableNameRule {
         // In GDScript, for loops have the pattern: for <variable> in <iterable>:
         // The variable could be an identifier or a typed parameter
         if let Some(left_node) = node.child_by_field_name("left") {
-            let variable_name = if GDScriptNodeKind::get_kind_from_ast_node(left_node)
+            let name_node = if GDScriptNodeKind::get_kind_from_ast_node(left_node)
                 == GDScriptNodeKind::Identifier
             {
-                get_node_text(&left_node, source_code)
+                Some(left_node)
             } else if GDScriptNodeKind::get_kind_from_ast_node(left_node)
                 == GDScriptNodeKind::Parameter
             {
                 // For typed loop variables like "for i: int in range(10):"
-                if let Some(name_child) = left_node.child(0) {
-                    get_node_text(&name_child, source_code)
-                } else {
-                    ""
-                }
+                left_node.child(0)
             } else {
-                ""
+                None
+            };
+            let variable_name = match name_node {
+                Some(name_node) => get_node_text(&name_node, source_code),
+                None => "",
             };
 
-            if !variable_name.is_empty() && !Self::is_valid_loop_variable_name(variable_name) {
-                let (line, column) = get_line_column(&left_node);
-                issues.push(LintIssue::new(
-                    line,
-                    column,
+            if !variable_name.is_empty() && !self.is_valid_loop_variable_name(variable_name) {
+                let mut issue = LintIssue::from_node(
+                    &left_node,
                     "loop-variable-name".to_string(),
-                    LintSeverity::Error,
                     format!(
-                        "Loop variable '{}' should be in snake_case_or _private_snake_case format",
-                        variable_name
+                        "Loop variable '{}' should {}",
+                        variable_name,
+                        self.naming_convention
+                            .describe_expectation("be in snake_case_or _private_snake_case format")
                     ),
-                ));
+                );
+                // The fix renames the variable inside the loop body only, so
+                // the iterable expression keeps referring to outer variables.
+                let new_name = to_snake_case(variable_name);
+                if let Some(name_node) = name_node
+                    && let Some(body_node) = node.child_by_field_name("body")
+                    && self.is_valid_loop_variable_name(&new_name)
+                    && let Some(edits) =
+                        make_local_rename_edits(&name_node, &body_node, &new_name, source_code)
+                {
+                    issue = issue.with_fix(edits);
+                }
+                issues.push(issue);
             }
         }
 
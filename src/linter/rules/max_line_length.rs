@@ -1,5 +1,5 @@
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity, LinterConfig};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 
 pub struct MaxLineLengthRule {
@@ -24,16 +24,17 @@ impl Rule for MaxLineLengthRule {
                 .fold(0, |acc, ch| if ch == '\t' { acc + 4 } else { acc + 1 });
 
             if display_width > self.config.max_line_length {
-                issues.push(LintIssue::new(
+                let mut issue = LintIssue::new(
                     line_number + 1,
                     self.config.max_line_length + 1,
                     "max-line-length".to_string(),
-                    LintSeverity::Warning,
                     format!(
                         "Line is too long. Found {} characters, maximum allowed is {}",
                         display_width, self.config.max_line_length
                     ),
-                ));
+                );
+                issue.end_column = display_width + 1;
+                issues.push(issue);
             }
         }
 
@@ -1,6 +1,5 @@
-use crate::linter::lib::get_line_column;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, TextEdit};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
@@ -33,6 +32,111 @@ impl NoElseReturnRule {
 
         false
     }
+
+    /// Returns the edit that turns `elif` into `if`. As the branches above
+    /// end with `return`, the `elif` branch can start a new if statement.
+    fn make_elif_fix(elif_node: &Node, source_code: &str) -> Option<Vec<TextEdit>> {
+        let keyword_end = elif_node.start_byte() + "elif".len();
+        if source_code.get(elif_node.start_byte()..keyword_end) != Some("elif") {
+            return None;
+        }
+        Some(vec![TextEdit {
+            start_byte: elif_node.start_byte(),
+            end_byte: keyword_end,
+            new_text: "if".to_string(),
+        }])
+    }
+
+    /// Returns the edit that replaces the else clause with its body, dedented
+    /// to the level of the if statement. Returns None when the edit could lose
+    /// a comment or change the contents of a multiline string.
+    fn make_else_fix(else_node: &Node, source_code: &str) -> Option<Vec<TextEdit>> {
+        let body_node = else_node.child_by_field_name("body")?;
+        if Self::contains_multiline_string(&body_node) {
+            return None;
+        }
+
+        let else_start = else_node.start_byte();
+        let else_line_start = find_line_start(source_code, else_start);
+        let indentation = &source_code[else_line_start..else_start];
+        if !indentation.trim().is_empty() {
+            return None;
+        }
+
+        // For an else clause on one line, like `else: return value`, we only
+        // keep the body.
+        let body_start = body_node
+            .named_child(0)
+            .map_or(body_node.start_byte(), |statement| statement.start_byte());
+        let body_end = body_start
+            + source_code[body_start..body_node.end_byte()]
+                .trim_end()
+                .len();
+        let body_line_start = find_line_start(source_code, body_start);
+        if body_line_start <= else_start {
+            if source_code[else_start..body_start].trim() != "else:" {
+                return None;
+            }
+            return Some(vec![TextEdit {
+                start_byte: else_start,
+                end_byte: body_end,
+                new_text: source_code[body_start..body_end].to_string(),
+            }]);
+        }
+
+        if source_code[else_start..body_line_start].trim() != "else:" {
+            return None;
+        }
+        let body_indentation = &source_code[body_line_start..body_start];
+        let mut new_text = String::new();
+        let body_lines = source_code[body_line_start..body_end].split('\n');
+        for (line_index, line) in body_lines.enumerate() {
+            if line_index > 0 {
+                new_text.push('\n');
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let code = line.strip_prefix(body_indentation)?;
+            // The first line replaces the else keyword, which already comes
+            // after the indentation.
+            if line_index > 0 {
+                new_text.push_str(indentation);
+            }
+            new_text.push_str(code);
+        }
+        Some(vec![TextEdit {
+            start_byte: else_start,
+            end_byte: body_end,
+            new_text,
+        }])
+    }
+
+    fn contains_multiline_string(node: &Node) -> bool {
+        if GDScriptNodeKind::get_kind_from_ast_node(*node) == GDScriptNodeKind::String
+            && node.start_position().row != node.end_position().row
+        {
+            return true;
+        }
+        let mut cursor = node.walk();
+        if cursor.goto_first_child() {
+            loop {
+                if Self::contains_multiline_string(&cursor.node()) {
+                    return true;
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+        false
+    }
+}
+
+fn find_line_start(source_code: &str, byte_offset: usize) -> usize {
+    source_code[..byte_offset]
+        .rfind('\n')
+        .map_or(0, |newline_index| newline_index + 1)
 }
 
 impl Rule for NoElseReturnRule {
@@ -43,13 +147,14 @@ impl Rule for NoElseReturnRule {
     fn check_node(&mut self, node: &Node, source_code: &str) -> Vec<LintIssue> {
         let mut issues = Vec::new();
 
-        let mut if_body_ends_with_return = false;
+        // True while every branch before the current one ends with `return`.
+        // Only then can an `elif` start a new if statement without running
+        // after one of the branches above it.
+        let mut all_branches_return = false;
         if let Some(body_node) = node.child_by_field_name("body") {
-            if_body_ends_with_return = Self::body_ends_with_return(&body_node, source_code);
+            all_branches_return = Self::body_ends_with_return(&body_node, source_code);
         }
 
-        let mut all_branches_return = if_body_ends_with_return;
-
         let mut stmt_cursor = node.walk();
         if stmt_cursor.goto_first_child() {
             loop {
@@ -57,15 +162,16 @@ impl Rule for NoElseReturnRule {
                 if GDScriptNodeKind::get_kind_from_ast_node(child_node)
                     == GDScriptNodeKind::ElifStatement
                 {
-                    if if_body_ends_with_return {
-                        let (line, column) = get_line_column(&child_node);
-                        issues.push(LintIssue::new(
-                            line,
-                            column,
+                    if all_branches_return {
+                        let mut issue = LintIssue::from_node(
+                            &child_node,
                             "no-else-return".to_string(),
-                            LintSeverity::Warning,
                             "Unnecessary 'elif' after 'if' block that ends with 'return'. Use 'if' instead".to_string(),
-                        ));
+                        );
+                        if let Some(edits) = Self::make_elif_fix(&child_node, source_code) {
+                            issue = issue.with_fix(edits);
+                        }
+                        issues.push(issue);
                     }
 
                     if let Some(elif_body) = child_node.child_by_field_name("body")
@@ -75,18 +181,18 @@ impl Rule for NoElseReturnRule {
                     }
                 } else if GDScriptNodeKind::get_kind_from_ast_node(child_node)
                     == GDScriptNodeKind::ElseStatement
+                    && all_branches_return
                 {
-                    let (line, column) = get_line_column(&child_node);
-                    if all_branches_return {
-                        issues.push(LintIssue::new(
-                            line,
-                            column,
-                            "no-else-return".to_string(),
-                            LintSeverity::Warning,
-                            "Unnecessary 'else' after 'if'/'elif' blocks that end with 'return'"
-                                .to_string(),
-                        ));
+                    let mut issue = LintIssue::from_node(
+                        &child_node,
+                        "no-else-return".to_string(),
+                        "Unnecessary 'else' after 'if'/'elif' blocks that end with 'return'"
+                            .to_string(),
+                    );
+                    if let Some(edits) = Self::make_else_fix(&child_node, source_code) {
+                        issue = issue.with_fix(edits);
                     }
+                    issues.push(issue);
                 }
                 if !stmt_cursor.goto_next_sibling() {
                     break;
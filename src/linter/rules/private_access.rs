@@ -1,6 +1,6 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::LintIssue;
+use crate::linter::lib::get_node_text;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 pub struct PrivateAccessRule;
@@ -29,12 +29,9 @@ impl Rule for PrivateAccessRule {
                             && object_name != "super"
                             && object_name != "self"
                         {
-                            let (line, column) = get_line_column(&method_name_node);
-                            issues.push(LintIssue::new(
-                                line,
-                                column,
+                            issues.push(LintIssue::from_node(
+                                &method_name_node,
                                 "private-access".to_string(),
-                                LintSeverity::Error,
                                 format!("Private method '{}' should not be called from outside its class", method_name),
                             ));
                         }
@@ -47,12 +44,9 @@ impl Rule for PrivateAccessRule {
                         && object_name != "super"
                         && object_name != "self"
                     {
-                        let (line, column) = get_line_column(&method_node);
-                        issues.push(LintIssue::new(
-                            line,
-                            column,
+                        issues.push(LintIssue::from_node(
+                            &method_node,
                             "private-access".to_string(),
-                            LintSeverity::Error,
                             format!("Private variable '{}' should not be accessed from outside its class", method_name),
                         ));
                     }
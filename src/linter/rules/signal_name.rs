@@ -1,12 +1,21 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::SNAKE_CASE;
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
-pub struct SignalNameRule;
+pub struct SignalNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl SignalNameRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("signal-name"),
+        }
+    }
+
     fn is_valid_signal_name(name: &str) -> bool {
         SNAKE_CASE.is_match(name)
     }
@@ -22,14 +31,19 @@ impl Rule for SignalNameRule {
 
         if let Some(name_node) = node.child_by_field_name("name") {
             let name = get_node_text(&name_node, source_code);
-            if !Self::is_valid_signal_name(name) {
-                let (line, column) = get_line_column(&name_node);
-                issues.push(LintIssue::new(
-                    line,
-                    column,
+            if !self
+                .naming_convention
+                .is_valid(name, Self::is_valid_signal_name)
+            {
+                issues.push(LintIssue::from_node(
+                    &name_node,
                     "signal-name".to_string(),
-                    LintSeverity::Error,
-                    format!("Signal name '{}' should be in snake_case format", name),
+                    format!(
+                        "Signal name '{}' should {}",
+                        name,
+                        self.naming_convention
+                            .describe_expectation("be in snake_case format")
+                    ),
                 ));
             }
         }
@@ -1,6 +1,6 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::LintIssue;
+use crate::linter::lib::get_node_text;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
@@ -29,13 +29,10 @@ impl Rule for StandaloneExpressionRule {
                     | GDScriptNodeKind::Literal
                     | GDScriptNodeKind::String
             ) {
-                let (line, column) = get_line_column(&expr_child);
                 let expr_text = get_node_text(&expr_child, source_code);
-                issues.push(LintIssue::new(
-                        line,
-                        column,
+                issues.push(LintIssue::from_node(
+                        &expr_child,
                         "standalone-expression".to_string(),
-                        LintSeverity::Warning,
                         format!(
                             "Standalone expression '{}' is not assigned or used, the line may have no effect",
                             expr_text
@@ -0,0 +1,27 @@
+use crate::linter::LintIssue;
+use crate::linter::rules::Rule;
+use crate::parser::find_syntax_errors;
+use tree_sitter::Node;
+
+/// Reports the code the parser couldn't make sense of. The other rules still
+/// run on the rest of the tree, but the formatter refuses to format the file
+/// in safe mode until these errors are fixed.
+pub struct SyntaxErrorRule;
+
+impl Rule for SyntaxErrorRule {
+    fn check_tree(&mut self, root: &Node, source_code: &str) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        for syntax_error in find_syntax_errors(*root, source_code) {
+            let mut issue = LintIssue::new(
+                syntax_error.line,
+                syntax_error.column,
+                "syntax-error".to_string(),
+                format!("Syntax error: {}", syntax_error.message),
+            );
+            issue.end_line = syntax_error.end_line;
+            issue.end_column = syntax_error.end_column;
+            issues.push(issue);
+        }
+        issues
+    }
+}
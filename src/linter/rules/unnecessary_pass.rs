@@ -1,6 +1,6 @@
-use crate::linter::lib::get_line_column;
+use crate::linter::LintIssue;
+use crate::linter::fixes::make_line_deletion_edit;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 pub struct UnnecessaryPassRule;
@@ -10,7 +10,7 @@ impl Rule for UnnecessaryPassRule {
         &[GDScriptNodeKind::Body, GDScriptNodeKind::ClassBody]
     }
 
-    fn check_node(&mut self, node: &Node, _source_code: &str) -> Vec<LintIssue> {
+    fn check_node(&mut self, node: &Node, source_code: &str) -> Vec<LintIssue> {
         let mut issues = Vec::new();
         let mut has_other_statements = false;
         let mut pass_nodes = Vec::new();
@@ -39,14 +39,15 @@ impl Rule for UnnecessaryPassRule {
 
         if has_other_statements {
             for pass_node in pass_nodes {
-                let (line, column) = get_line_column(&pass_node);
-                issues.push(LintIssue::new(
-                    line,
-                    column,
+                let mut issue = LintIssue::from_node(
+                    &pass_node,
                     "unnecessary-pass".to_string(),
-                    LintSeverity::Warning,
                     "Unnecessary 'pass' statement when other statements are present".to_string(),
-                ));
+                );
+                if let Some(edit) = make_line_deletion_edit(&pass_node, source_code) {
+                    issue = issue.with_fix(vec![edit]);
+                }
+                issues.push(issue);
             }
         }
 
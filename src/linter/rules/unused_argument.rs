@@ -1,6 +1,6 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, TextEdit};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
 
@@ -87,14 +87,16 @@ impl Rule for UnusedArgumentRule {
         if let Some(body_node) = node.child_by_field_name("body") {
             for (param_name, param_node) in parameters {
                 if !Self::is_identifier_used_in_node(&body_node, &param_name, source_code) {
-                    let (line, column) = get_line_column(&param_node);
-                    issues.push(LintIssue::new(
-                        line,
-                        column,
+                    let prefix_edit = TextEdit {
+                        start_byte: param_node.start_byte(),
+                        end_byte: param_node.start_byte(),
+                        new_text: "_".to_string(),
+                    };
+                    issues.push(LintIssue::from_node(
+                        &param_node,
                         "unused-argument".to_string(),
-                        LintSeverity::Warning,
                         format!("Function argument '{}' is unused. Consider removing it or prefixing with '_'", param_name),
-                    ));
+                    ).with_fix(vec![prefix_edit]));
                 }
             }
         }
@@ -0,0 +1,60 @@
+use crate::linter::LintIssue;
+use crate::linter::ignore_patterns::{SuppressionKind, Suppressions};
+use crate::linter::rule_config::get_all_rule_names;
+use crate::linter::rules::Rule;
+use std::collections::HashSet;
+
+/// Reports suppression comments that suppress no issue or name unknown rules,
+/// so stale comments get cleaned up.
+pub struct UnusedSuppressionRule;
+
+impl Rule for UnusedSuppressionRule {
+    fn check_suppressions(
+        &mut self,
+        suppressions: &Suppressions,
+        checked_rules: &HashSet<&str>,
+    ) -> Vec<LintIssue> {
+        let mut issues = Vec::new();
+        let all_rule_names = get_all_rule_names();
+
+        for (comment_index, comment) in suppressions.comments.iter().enumerate() {
+            let mut messages = Vec::new();
+            for rule_name in &comment.rules {
+                if !all_rule_names.contains(&rule_name.as_str()) {
+                    messages.push(format!(
+                        "Unknown rule '{}' in suppression comment",
+                        rule_name
+                    ));
+                } else if comment.kind != SuppressionKind::Enable
+                    && checked_rules.contains(rule_name.as_str())
+                    && !suppressions.is_rule_used(comment_index, rule_name)
+                {
+                    messages.push(format!(
+                        "Suppression of '{}' is unused: there is no issue to suppress",
+                        rule_name
+                    ));
+                }
+            }
+            if comment.rules.is_empty()
+                && comment.kind != SuppressionKind::Enable
+                && !suppressions.is_comment_used(comment_index)
+            {
+                messages.push(
+                    "Suppression comment is unused: there is no issue to suppress".to_string(),
+                );
+            }
+
+            for message in messages {
+                let mut issue = LintIssue::new(
+                    comment.line,
+                    comment.column,
+                    "unused-suppression".to_string(),
+                    message,
+                );
+                issue.end_column = comment.end_column;
+                issues.push(issue);
+            }
+        }
+        issues
+    }
+}
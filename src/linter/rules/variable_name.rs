@@ -1,12 +1,21 @@
-use crate::linter::lib::{get_line_column, get_node_text};
+use crate::linter::lib::get_node_text;
 use crate::linter::regex_patterns::{PASCAL_CASE, PRIVATE_SNAKE_CASE, SNAKE_CASE};
+use crate::linter::rule_config::NamingConvention;
 use crate::linter::rules::Rule;
-use crate::linter::{LintIssue, LintSeverity};
+use crate::linter::{LintIssue, LinterConfig};
 use crate::node_kind::GDScriptNodeKind;
 use tree_sitter::Node;
-pub struct VariableNameRule;
+pub struct VariableNameRule {
+    naming_convention: NamingConvention,
+}
 
 impl VariableNameRule {
+    pub fn new(config: &LinterConfig) -> Self {
+        Self {
+            naming_convention: config.naming_convention("variable-name"),
+        }
+    }
+
     fn is_valid_variable_name(name: &str) -> bool {
         SNAKE_CASE.is_match(name) || PRIVATE_SNAKE_CASE.is_match(name)
     }
@@ -50,31 +59,37 @@ impl Rule for VariableNameRule {
 
             if is_load_var {
                 // For load() variables, only check load rules if they fail normal load validation
-                if !Self::is_valid_load_variable_name(name) {
-                    let (line, column) = get_line_column(&name_node);
-                    issues.push(LintIssue::new(
-                        line,
-                        column,
+                if !self
+                    .naming_convention
+                    .is_valid(name, Self::is_valid_load_variable_name)
+                {
+                    issues.push(LintIssue::from_node(
+                        &name_node,
                         "load-variable-name".to_string(),
-                        LintSeverity::Error,
                         format!(
-                            "Variable name '{}' should be in PascalCase, snake_case or _private_snake_case format",
-                            name
+                            "Variable name '{}' should {}",
+                            name,
+                            self.naming_convention.describe_expectation(
+                                "be in PascalCase, snake_case or _private_snake_case format"
+                            )
                         ),
                     ));
                 }
             } else {
                 // For regular variables, just check regular rules
-                if !Self::is_valid_variable_name(name) {
-                    let (line, column) = get_line_column(&name_node);
-                    issues.push(LintIssue::new(
-                        line,
-                        column,
+                if !self
+                    .naming_convention
+                    .is_valid(name, Self::is_valid_variable_name)
+                {
+                    issues.push(LintIssue::from_node(
+                        &name_node,
                         "variable-name".to_string(),
-                        LintSeverity::Error,
                         format!(
-                            "Variable name '{}' should be in snake_case or _private_snake_case format",
-                            name
+                            "Variable name '{}' should {}",
+                            name,
+                            self.naming_convention.describe_expectation(
+                                "be in snake_case or _private_snake_case format"
+                            )
                         ),
                     ));
                 }
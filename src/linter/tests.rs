@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod linter_tests {
     #![allow(clippy::unwrap_used)]
-    use crate::linter::{LintIssue, LintSeverity, LinterConfig, lint_gdscript_with_config};
+    use crate::linter::fixes::{apply_fixes, to_snake_case};
+    use crate::linter::output_formats::{FileLintResult, LintOutputFormat, write_lint_report};
+    use crate::linter::rule_config::{NamingConvention, parse_rule_severities};
+    use crate::linter::{
+        GDScriptLinter, LintIssue, LintSeverity, LinterConfig, TextEdit, lint_gdscript_with_config,
+    };
+    use crate::parser::SyntaxError;
 
     #[test]
     fn test_lint_basic_functionality() {
@@ -58,19 +64,276 @@ signal BadSignal
     }
 
     #[test]
-    fn test_lint_issue_format() {
-        let issue = LintIssue::new(
-            10,
-            5,
-            "test-rule".to_string(),
-            LintSeverity::Error,
-            "Test message".to_string(),
+    fn test_class_definitions_order_is_off_by_default() {
+        let test_code = "extends Node\n\nconst SPEED = 10\nsignal died\n";
+
+        let config = LinterConfig::default();
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        assert!(issues.iter().all(|i| i.rule != "class-definitions-order"));
+
+        let mut config = LinterConfig::default();
+        config
+            .rule_severities
+            .insert("class-definitions-order".to_string(), LintSeverity::Error);
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        let order_issue = issues
+            .iter()
+            .find(|i| i.rule == "class-definitions-order")
+            .unwrap();
+        assert_eq!(order_issue.line, 4);
+        assert_eq!(order_issue.severity, LintSeverity::Error);
+        assert_eq!(
+            order_issue.message,
+            "signal `died` should come before constant `SPEED`"
+        );
+    }
+
+    #[test]
+    fn test_class_definitions_order_checks_inner_classes() {
+        let test_code =
+            "extends Node\n\n\nclass Inner:\n\tfunc jump():\n\t\tpass\n\n\tvar speed = 1\n";
+
+        let mut config = LinterConfig::default();
+        config
+            .rule_severities
+            .insert("class-definitions-order".to_string(), LintSeverity::Warning);
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+        let order_issues: Vec<&LintIssue> = issues
+            .iter()
+            .filter(|i| i.rule == "class-definitions-order")
+            .collect();
+        assert_eq!(order_issues.len(), 1);
+        assert_eq!(order_issues[0].line, 8);
+        assert_eq!(
+            order_issues[0].message,
+            "variable `speed` should come before method `jump`"
+        );
+    }
+
+    #[test]
+    fn test_lint_rule_severity_overrides() {
+        let test_code = "var badVariable = 30\nfunc test(unused):\n\tpass\n";
+
+        let mut config = LinterConfig::default();
+        config
+            .rule_severities
+            .insert("variable-name".to_string(), LintSeverity::Hint);
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+
+        let variable_issue = issues.iter().find(|i| i.rule == "variable-name").unwrap();
+        assert_eq!(variable_issue.severity, LintSeverity::Hint);
+        let argument_issue = issues.iter().find(|i| i.rule == "unused-argument").unwrap();
+        assert_eq!(argument_issue.severity, LintSeverity::Warning);
+    }
+
+    #[test]
+    fn test_lint_custom_naming_conventions() {
+        let test_code = "func _on_Button_pressed():\n\tpass\n\nfunc doThing():\n\tpass\n\nconst maxSpeed = 10\n";
+
+        let mut config = LinterConfig::default();
+        let mut function_naming = NamingConvention::default();
+        function_naming
+            .allowed_names
+            .insert("_on_Button_pressed".to_string());
+        config
+            .naming_conventions
+            .insert("function-name".to_string(), function_naming);
+        let constant_naming = NamingConvention {
+            pattern: Some(regex::Regex::new("^[A-Z][A-Z0-9_]*$").unwrap()),
+            ..Default::default()
+        };
+        config
+            .naming_conventions
+            .insert("constant-name".to_string(), constant_naming);
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+
+        let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Function name 'doThing' should be in snake_case, _private_snake_case format",
+                "Constant name 'maxSpeed' should match the pattern `^[A-Z][A-Z0-9_]*$`",
+            ]
         );
+    }
+
+    #[test]
+    fn test_parse_rule_severities() {
+        let severities =
+            parse_rule_severities("unused-argument=error, private-access = info").unwrap();
+        assert_eq!(
+            severities.get("unused-argument"),
+            Some(&LintSeverity::Error)
+        );
+        assert_eq!(severities.get("private-access"), Some(&LintSeverity::Info));
+        assert_eq!(
+            parse_rule_severities("unused-argument=fatal"),
+            Err("unused-argument=fatal".to_string())
+        );
+        assert!(LintSeverity::Warning.is_at_least(&LintSeverity::Info));
+        assert!(!LintSeverity::Warning.is_at_least(&LintSeverity::Error));
+    }
+
+    #[test]
+    fn test_lint_issue_format() {
+        let mut issue = LintIssue::new(10, 5, "test-rule".to_string(), "Test message".to_string());
+        issue.severity = LintSeverity::Error;
 
         let formatted = issue.format("test.gd");
         assert_eq!(formatted, "test.gd:10:test-rule:error: Test message");
     }
 
+    #[test]
+    fn test_lint_issue_spans_flagged_code() {
+        let config = LinterConfig::default();
+        let issues =
+            lint_gdscript_with_config("var badVariable = 30\n", "test.gd", &config).unwrap();
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "variable-name");
+        assert_eq!((issues[0].line, issues[0].column), (1, 5));
+        assert_eq!((issues[0].end_line, issues[0].end_column), (1, 16));
+    }
+
+    #[test]
+    fn test_lint_syntax_errors() {
+        let config = LinterConfig::default();
+        let test_code = "var speed = 10\nfunc broken(:\n\tpass\n";
+        let issues = lint_gdscript_with_config(test_code, "test.gd", &config).unwrap();
+
+        let syntax_issue = issues.iter().find(|i| i.rule == "syntax-error").unwrap();
+        assert_eq!(syntax_issue.line, 2);
+        assert_eq!(syntax_issue.severity, LintSeverity::Error);
+        assert!(syntax_issue.message.starts_with("Syntax error: "));
+
+        let issues = lint_gdscript_with_config("var speed = 10\n", "test.gd", &config).unwrap();
+        assert!(issues.iter().all(|i| i.rule != "syntax-error"));
+    }
+
+    #[test]
+    fn test_syntax_error_format() {
+        let syntax_error = SyntaxError {
+            line: 3,
+            column: 13,
+            end_line: 3,
+            end_column: 14,
+            message: "missing `)`".to_string(),
+            snippet: "\tfunc broken(:".to_string(),
+        };
+
+        assert_eq!(
+            syntax_error.format("test.gd"),
+            "test.gd:3:13: syntax error: missing `)`\n    \tfunc broken(:\n    \t           ^"
+        );
+    }
+
+    #[test]
+    fn test_lint_report_formats() {
+        let mut issue = LintIssue::new(
+            2,
+            5,
+            "variable-name".to_string(),
+            "Bad name: <badVariable>".to_string(),
+        );
+        issue.end_column = 16;
+        let results = vec![FileLintResult {
+            file_path: "scripts/player.gd".to_string(),
+            issues: vec![issue],
+        }];
+
+        let mut json_report = String::new();
+        write_lint_report(LintOutputFormat::Json, &results, &mut json_report);
+        assert_eq!(
+            json_report,
+            "[{\"file\":\"scripts/player.gd\",\"line\":2,\"column\":5,\"end_line\":2,\"end_column\":16,\"rule\":\"variable-name\",\"severity\":\"warning\",\"message\":\"Bad name: <badVariable>\"}]\n"
+        );
+
+        let mut github_report = String::new();
+        write_lint_report(LintOutputFormat::Github, &results, &mut github_report);
+        assert_eq!(
+            github_report,
+            "::warning file=scripts/player.gd,line=2,col=5,endLine=2,endColumn=16,title=variable-name::Bad name: <badVariable>\n"
+        );
+
+        let mut checkstyle_report = String::new();
+        write_lint_report(
+            LintOutputFormat::Checkstyle,
+            &results,
+            &mut checkstyle_report,
+        );
+        assert!(checkstyle_report.contains(
+            "<error line=\"2\" column=\"5\" severity=\"warning\" message=\"Bad name: &lt;badVariable&gt;\" source=\"gdscript-formatter.variable-name\"/>"
+        ));
+
+        let mut sarif_report = String::new();
+        write_lint_report(LintOutputFormat::Sarif, &results, &mut sarif_report);
+        let sarif_log = crate::json::JsonValue::parse(&sarif_report).unwrap();
+        let run = &sarif_log.get("runs").unwrap().as_array().unwrap()[0];
+        let rules = run
+            .get("tool")
+            .and_then(|tool| tool.get("driver"))
+            .and_then(|driver| driver.get("rules"))
+            .and_then(crate::json::JsonValue::as_array)
+            .unwrap();
+        assert_eq!(
+            rules.len(),
+            crate::linter::rules::ALL_RULES.len()
+                + crate::linter::project_rules::ALL_PROJECT_RULES.len()
+        );
+        let result = &run.get("results").unwrap().as_array().unwrap()[0];
+        assert_eq!(
+            result
+                .get("ruleId")
+                .and_then(crate::json::JsonValue::as_str),
+            Some("variable-name")
+        );
+    }
+
+    #[test]
+    fn test_lint_fix() {
+        let test_code = "func _ready(myArg):\n\tfor myItem in [1, 2]:\n\t\tprint(myItem)\n\tpass\n\tif true:\n\t\treturn\n\telse:\n\t\tprint(myArg)\n";
+
+        let mut linter = GDScriptLinter::new(LinterConfig::default()).unwrap();
+        let fixed_code = linter
+            .fix(test_code, &crate::FormatterConfiguration::default())
+            .unwrap();
+
+        assert_eq!(
+            fixed_code,
+            "func _ready(my_arg):\n\tfor my_item in [1, 2]:\n\t\tprint(my_item)\n\tif true:\n\t\treturn\n\tprint(my_arg)\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_fixes_skips_overlapping_edits() {
+        let source_code = "var a = 1\n";
+        let replace_name =
+            LintIssue::new(1, 5, "first".to_string(), String::new()).with_fix(vec![TextEdit {
+                start_byte: 4,
+                end_byte: 5,
+                new_text: "b".to_string(),
+            }]);
+        let prefix_name =
+            LintIssue::new(1, 5, "second".to_string(), String::new()).with_fix(vec![TextEdit {
+                start_byte: 4,
+                end_byte: 4,
+                new_text: "_".to_string(),
+            }]);
+
+        let fixed_source = apply_fixes(source_code, &[replace_name, prefix_name]);
+        assert_eq!(fixed_source.source_code, "var b = 1\n");
+        assert_eq!(fixed_source.applied_fix_count, 1);
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("myVariable"), "my_variable");
+        assert_eq!(to_snake_case("MyVariable"), "my_variable");
+        assert_eq!(to_snake_case("_maxHealth"), "_max_health");
+        assert_eq!(to_snake_case("HTTPRequest"), "http_request");
+        assert_eq!(to_snake_case("MAX_SPEED"), "max_speed");
+    }
+
     #[test]
     fn test_parse_disabled_rules() {
         let rules = crate::linter::rule_config::parse_disabled_rules(
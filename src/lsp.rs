@@ -0,0 +1,535 @@
+//! Language server mode, started with `gdscript-formatter lsp`.
+//!
+//! The server speaks JSON-RPC over stdin and stdout following the Language
+//! Server Protocol. It supports formatting whole documents and ranges, and
+//! publishes linter issues as diagnostics whenever a document is opened or
+//! changed.
+//!
+//! Each document gets the same per-file configuration as on the command line:
+//! the formatter and linter resolve the project configuration file and
+//! `.editorconfig` settings from the document's path. The server keeps open
+//! documents in memory and uses full document synchronization: clients send
+//! the complete text on every change.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use gdscript_formatter::editorconfig::apply_editorconfig_to_linter_config;
+use gdscript_formatter::json::{JsonMember, JsonValue};
+use gdscript_formatter::linter::{GDScriptLinter, LintIssue, LintSeverity, LinterConfig};
+use gdscript_formatter::project_config::{
+    ProjectConfigCache, apply_project_config_to_linter_config,
+};
+use gdscript_formatter::{
+    FormatterConfiguration, RangeSourceBytes, format_gdscript, format_gdscript_range,
+    line_range_to_byte_range,
+};
+
+use crate::{FormatterConfigOverrides, config_apply_file_settings_then_cli_overrides};
+
+/// JSON-RPC error codes from the JSON-RPC and LSP specifications.
+const ERROR_CODE_PARSE_ERROR: i32 = -32700;
+const ERROR_CODE_INVALID_PARAMS: i32 = -32602;
+const ERROR_CODE_METHOD_NOT_FOUND: i32 = -32601;
+const ERROR_CODE_REQUEST_FAILED: i32 = -32803;
+
+/// LSP diagnostic severities.
+const DIAGNOSTIC_SEVERITY_ERROR: usize = 1;
+const DIAGNOSTIC_SEVERITY_WARNING: usize = 2;
+const DIAGNOSTIC_SEVERITY_INFORMATION: usize = 3;
+const DIAGNOSTIC_SEVERITY_HINT: usize = 4;
+
+/// `TextDocumentSyncKind.Full`: clients send the whole document on change.
+const TEXT_DOCUMENT_SYNC_FULL: usize = 1;
+
+struct LanguageServer {
+    /// Text of the open documents, by document URI.
+    documents: HashMap<String, String>,
+    is_shutdown_requested: bool,
+}
+
+/// Runs the language server until the client sends the `exit` notification or
+/// closes stdin. Returns the process exit code: 0 if the client asked the
+/// server to shut down first, 1 otherwise, as the protocol requires.
+pub fn run_language_server() -> Result<i32, Box<dyn std::error::Error>> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    let mut server = LanguageServer {
+        documents: HashMap::new(),
+        is_shutdown_requested: false,
+    };
+
+    while let Some(message_text) = read_message(&mut reader)? {
+        let message = match JsonValue::parse(&message_text) {
+            Ok(message) => message,
+            Err(error) => {
+                let response = make_error_response(JsonValue::Null, ERROR_CODE_PARSE_ERROR, &error);
+                write_message(&mut writer, &response)?;
+                continue;
+            }
+        };
+        // Messages without a method are responses from the client, and
+        // JSON-RPC doesn't allow answering them.
+        let Some(method) = message.get("method").and_then(JsonValue::as_str) else {
+            continue;
+        };
+        if method == "exit" {
+            break;
+        }
+        let outgoing_messages = server.handle_message(method, &message);
+        for outgoing_message in outgoing_messages {
+            write_message(&mut writer, &outgoing_message)?;
+        }
+    }
+
+    if server.is_shutdown_requested {
+        Ok(0)
+    } else {
+        Ok(1)
+    }
+}
+
+impl LanguageServer {
+    /// Handles one request or notification and returns the messages to send
+    /// back: the response for requests, and any diagnostics to publish.
+    fn handle_message(&mut self, method: &str, message: &JsonValue) -> Vec<JsonValue> {
+        let null = JsonValue::Null;
+        let params = message.get("params").unwrap_or(&null);
+        let request_id = message.get("id").cloned();
+
+        let mut outgoing_messages = Vec::new();
+        match method {
+            "initialize" => {
+                let result = JsonValue::Object(vec![
+                    JsonMember::new(
+                        "capabilities",
+                        JsonValue::Object(vec![
+                            JsonMember::new(
+                                "textDocumentSync",
+                                JsonValue::number(TEXT_DOCUMENT_SYNC_FULL),
+                            ),
+                            JsonMember::new("documentFormattingProvider", JsonValue::Bool(true)),
+                            JsonMember::new(
+                                "documentRangeFormattingProvider",
+                                JsonValue::Bool(true),
+                            ),
+                        ]),
+                    ),
+                    JsonMember::new(
+                        "serverInfo",
+                        JsonValue::Object(vec![
+                            JsonMember::new("name", JsonValue::string("gdscript-formatter")),
+                            JsonMember::new(
+                                "version",
+                                JsonValue::string(env!("CARGO_PKG_VERSION")),
+                            ),
+                        ]),
+                    ),
+                ]);
+                outgoing_messages.push(make_response(request_id, Ok(result)));
+            }
+            "shutdown" => {
+                self.is_shutdown_requested = true;
+                outgoing_messages.push(make_response(request_id, Ok(JsonValue::Null)));
+            }
+            "textDocument/didOpen" => {
+                let text_document = params.get("textDocument").unwrap_or(&null);
+                if let (Some(uri), Some(text)) = (
+                    text_document.get("uri").and_then(JsonValue::as_str),
+                    text_document.get("text").and_then(JsonValue::as_str),
+                ) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                    outgoing_messages.push(self.make_diagnostics_notification(uri));
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = get_text_document_uri(params);
+                // With full synchronization, the last change holds the complete
+                // document text.
+                let text = params
+                    .get("contentChanges")
+                    .and_then(JsonValue::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(JsonValue::as_str);
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                    outgoing_messages.push(self.make_diagnostics_notification(uri));
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = get_text_document_uri(params) {
+                    self.documents.remove(uri);
+                    outgoing_messages.push(make_diagnostics_notification(uri, Vec::new()));
+                }
+            }
+            "textDocument/formatting" | "textDocument/rangeFormatting" => {
+                let result = self.format_document(method, params);
+                outgoing_messages.push(make_response(request_id, result));
+            }
+            _ => {
+                // Requests expect a response. Notifications we don't support,
+                // like `initialized` or `$/` protocol notifications, are
+                // ignored.
+                if request_id.is_some() {
+                    outgoing_messages.push(make_response(
+                        request_id,
+                        Err(RequestError {
+                            code: ERROR_CODE_METHOD_NOT_FOUND,
+                            message: format!("Unsupported method: {}", method),
+                        }),
+                    ));
+                }
+            }
+        }
+        outgoing_messages
+    }
+
+    /// Formats the document or the requested line range and returns a single
+    /// text edit replacing the whole document, or no edit if the document is
+    /// already formatted.
+    fn format_document(&self, method: &str, params: &JsonValue) -> Result<JsonValue, RequestError> {
+        let uri = get_text_document_uri(params).ok_or_else(|| RequestError {
+            code: ERROR_CODE_INVALID_PARAMS,
+            message: "Missing textDocument.uri".to_string(),
+        })?;
+        let text = self.documents.get(uri).ok_or_else(|| RequestError {
+            code: ERROR_CODE_INVALID_PARAMS,
+            message: format!("Document is not open: {}", uri),
+        })?;
+
+        let mut config = FormatterConfiguration::default();
+        config_apply_file_settings_then_cli_overrides(
+            &mut config,
+            &uri_to_path(uri),
+            &mut ProjectConfigCache::default(),
+            FormatterConfigOverrides::default(),
+        )
+        .map_err(|error| RequestError {
+            code: ERROR_CODE_REQUEST_FAILED,
+            message: error,
+        })?;
+
+        let formatted = if method == "textDocument/rangeFormatting" {
+            let range = params.get("range").ok_or_else(|| RequestError {
+                code: ERROR_CODE_INVALID_PARAMS,
+                message: "Missing range".to_string(),
+            })?;
+            let byte_range = RangeSourceBytes {
+                start_byte: position_to_byte_offset(
+                    text,
+                    get_position_field(range, "start", "line"),
+                    get_position_field(range, "start", "character"),
+                ),
+                end_byte: position_to_byte_offset(
+                    text,
+                    get_position_field(range, "end", "line"),
+                    get_position_field(range, "end", "character"),
+                ),
+            };
+            format_gdscript_range(text, &byte_range, &config)
+        } else {
+            format_gdscript(text, &config)
+        };
+        let formatted = formatted.map_err(|error| RequestError {
+            code: ERROR_CODE_REQUEST_FAILED,
+            message: error.to_string(),
+        })?;
+
+        if formatted == *text {
+            return Ok(JsonValue::Array(Vec::new()));
+        }
+        let document_end = get_document_end_position(text);
+        let text_edit = JsonValue::Object(vec![
+            JsonMember::new(
+                "range",
+                make_range(
+                    Position {
+                        line: 0,
+                        character: 0,
+                    },
+                    document_end,
+                ),
+            ),
+            JsonMember::new("newText", JsonValue::String(formatted)),
+        ]);
+        Ok(JsonValue::Array(vec![text_edit]))
+    }
+
+    /// Lints an open document and returns the `publishDiagnostics`
+    /// notification for it. Linter errors are reported as a single diagnostic
+    /// on the first line so they remain visible in the editor.
+    fn make_diagnostics_notification(&self, uri: &str) -> JsonValue {
+        let Some(text) = self.documents.get(uri) else {
+            return make_diagnostics_notification(uri, Vec::new());
+        };
+        let path = uri_to_path(uri);
+        let mut config = LinterConfig::default();
+        let lint_result =
+            match apply_project_config_to_linter_config(&mut config, &path).and_then(|()| {
+                apply_editorconfig_to_linter_config(&mut config, &path);
+                GDScriptLinter::new(config)
+            }) {
+                Ok(mut linter) => linter.lint(text, &path.to_string_lossy()),
+                Err(error) => Err(error),
+            };
+        let mut diagnostics = Vec::new();
+        match lint_result {
+            Ok(issues) => {
+                let lines: Vec<&str> = text.split('\n').collect();
+                for issue in &issues {
+                    diagnostics.push(make_diagnostic(issue, &lines));
+                }
+            }
+            Err(error) => {
+                let position = Position {
+                    line: 0,
+                    character: 0,
+                };
+                diagnostics.push(JsonValue::Object(vec![
+                    JsonMember::new("range", make_range(position, position)),
+                    JsonMember::new("severity", JsonValue::number(DIAGNOSTIC_SEVERITY_ERROR)),
+                    JsonMember::new("source", JsonValue::string("gdscript-formatter")),
+                    JsonMember::new("message", JsonValue::String(error.to_string())),
+                ]));
+            }
+        }
+        make_diagnostics_notification(uri, diagnostics)
+    }
+}
+
+struct RequestError {
+    code: i32,
+    message: String,
+}
+
+/// A position in a document as the protocol defines it: 0-based line and
+/// character offsets, where characters count UTF-16 code units.
+#[derive(Clone, Copy)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+fn make_response(
+    request_id: Option<JsonValue>,
+    result: Result<JsonValue, RequestError>,
+) -> JsonValue {
+    let request_id = request_id.unwrap_or(JsonValue::Null);
+    match result {
+        Ok(result) => JsonValue::Object(vec![
+            JsonMember::new("jsonrpc", JsonValue::string("2.0")),
+            JsonMember::new("id", request_id),
+            JsonMember::new("result", result),
+        ]),
+        Err(error) => make_error_response(request_id, error.code, &error.message),
+    }
+}
+
+fn make_error_response(request_id: JsonValue, code: i32, message: &str) -> JsonValue {
+    JsonValue::Object(vec![
+        JsonMember::new("jsonrpc", JsonValue::string("2.0")),
+        JsonMember::new("id", request_id),
+        JsonMember::new(
+            "error",
+            JsonValue::Object(vec![
+                JsonMember::new("code", JsonValue::Number(f64::from(code))),
+                JsonMember::new("message", JsonValue::string(message)),
+            ]),
+        ),
+    ])
+}
+
+fn make_diagnostics_notification(uri: &str, diagnostics: Vec<JsonValue>) -> JsonValue {
+    JsonValue::Object(vec![
+        JsonMember::new("jsonrpc", JsonValue::string("2.0")),
+        JsonMember::new(
+            "method",
+            JsonValue::string("textDocument/publishDiagnostics"),
+        ),
+        JsonMember::new(
+            "params",
+            JsonValue::Object(vec![
+                JsonMember::new("uri", JsonValue::string(uri)),
+                JsonMember::new("diagnostics", JsonValue::Array(diagnostics)),
+            ]),
+        ),
+    ])
+}
+
+/// Converts a linter issue to an LSP diagnostic. Linter columns are 1-based
+/// byte offsets, so we convert them to UTF-16 offsets using the line's text.
+fn make_diagnostic(issue: &LintIssue, lines: &[&str]) -> JsonValue {
+    let start = make_issue_position(issue.line, issue.column, lines);
+    let end = make_issue_position(issue.end_line, issue.end_column, lines);
+    let severity = match issue.severity {
+        LintSeverity::Error => DIAGNOSTIC_SEVERITY_ERROR,
+        LintSeverity::Warning => DIAGNOSTIC_SEVERITY_WARNING,
+        LintSeverity::Info => DIAGNOSTIC_SEVERITY_INFORMATION,
+        LintSeverity::Hint => DIAGNOSTIC_SEVERITY_HINT,
+    };
+    JsonValue::Object(vec![
+        JsonMember::new("range", make_range(start, end)),
+        JsonMember::new("severity", JsonValue::number(severity)),
+        JsonMember::new("code", JsonValue::string(&issue.rule)),
+        JsonMember::new("source", JsonValue::string("gdscript-formatter")),
+        JsonMember::new("message", JsonValue::string(&issue.message)),
+    ])
+}
+
+/// Converts a 1-based line and byte column from the linter to a position.
+fn make_issue_position(line_number: usize, column: usize, lines: &[&str]) -> Position {
+    let line = line_number.saturating_sub(1);
+    let mut character = 0;
+    if let Some(line_text) = lines.get(line) {
+        character = count_utf16_code_units_before(line_text, column.saturating_sub(1));
+    }
+    Position { line, character }
+}
+
+fn make_range(start: Position, end: Position) -> JsonValue {
+    JsonValue::Object(vec![
+        JsonMember::new("start", make_position(start)),
+        JsonMember::new("end", make_position(end)),
+    ])
+}
+
+fn make_position(position: Position) -> JsonValue {
+    JsonValue::Object(vec![
+        JsonMember::new("line", JsonValue::number(position.line)),
+        JsonMember::new("character", JsonValue::number(position.character)),
+    ])
+}
+
+fn get_text_document_uri(params: &JsonValue) -> Option<&str> {
+    params.get("textDocument")?.get("uri")?.as_str()
+}
+
+/// Reads `range.<position_name>.<field_name>` from request parameters,
+/// defaulting to 0 when missing.
+fn get_position_field(range: &JsonValue, position_name: &str, field_name: &str) -> usize {
+    let Some(position) = range.get(position_name) else {
+        return 0;
+    };
+    position
+        .get(field_name)
+        .and_then(JsonValue::as_usize)
+        .unwrap_or(0)
+}
+
+/// Returns the position right after the last character of the text.
+fn get_document_end_position(text: &str) -> Position {
+    let mut line = 0;
+    let mut last_line_start = 0;
+    for (current_index, byte) in text.bytes().enumerate() {
+        if byte == b'\n' {
+            line += 1;
+            last_line_start = current_index + 1;
+        }
+    }
+    let last_line = &text[last_line_start..];
+    Position {
+        line,
+        character: count_utf16_code_units_before(last_line, last_line.len()),
+    }
+}
+
+/// Converts an LSP position to a byte offset in `text`. LSP lines start at 0
+/// and characters count UTF-16 code units. Positions past the end of a line
+/// clamp to the end of the line.
+fn position_to_byte_offset(text: &str, line: usize, character: usize) -> usize {
+    let line_range = line_range_to_byte_range(text, line + 1, line + 1);
+    let line_text = &text[line_range.start_byte..line_range.end_byte];
+    let mut code_unit_count = 0;
+    for (character_start, current_character) in line_text.char_indices() {
+        if code_unit_count >= character {
+            return line_range.start_byte + character_start;
+        }
+        code_unit_count += current_character.len_utf16();
+    }
+    line_range.end_byte
+}
+
+/// Counts the UTF-16 code units in the first `byte_offset` bytes of `text`.
+fn count_utf16_code_units_before(text: &str, byte_offset: usize) -> usize {
+    let mut code_unit_count = 0;
+    for (character_start, character) in text.char_indices() {
+        if character_start >= byte_offset {
+            break;
+        }
+        code_unit_count += character.len_utf16();
+    }
+    code_unit_count
+}
+
+/// Converts a `file://` URI to a file path, decoding percent-escaped bytes.
+/// Editors send URIs like `file:///home/user/player.gd` or
+/// `file:///c%3A/project/player.gd` on Windows.
+fn uri_to_path(uri: &str) -> PathBuf {
+    let encoded_path = uri.strip_prefix("file://").unwrap_or(uri);
+    let encoded_bytes = encoded_path.as_bytes();
+    let mut decoded_bytes = Vec::with_capacity(encoded_bytes.len());
+    let mut current_index = 0;
+    while current_index < encoded_bytes.len() {
+        let byte = encoded_bytes[current_index];
+        if byte == b'%' && current_index + 2 < encoded_bytes.len() {
+            let hex_digits = &encoded_bytes[current_index + 1..current_index + 3];
+            if let Ok(hex_digits) = std::str::from_utf8(hex_digits)
+                && let Ok(decoded_byte) = u8::from_str_radix(hex_digits, 16)
+            {
+                decoded_bytes.push(decoded_byte);
+                current_index += 3;
+                continue;
+            }
+        }
+        decoded_bytes.push(byte);
+        current_index += 1;
+    }
+    let mut path = String::from_utf8_lossy(&decoded_bytes).into_owned();
+    // Windows paths come as /c:/project/file.gd.
+    let path_bytes = path.as_bytes();
+    if path_bytes.len() > 2 && path_bytes[0] == b'/' && path_bytes[2] == b':' {
+        path.remove(0);
+    }
+    PathBuf::from(path)
+}
+
+/// Reads one message framed with a `Content-Length` header. Returns `None`
+/// when the client closed stdin.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut content_length: Option<usize> = None;
+    let mut header_line = String::new();
+    loop {
+        header_line.clear();
+        if reader.read_line(&mut header_line)? == 0 {
+            return Ok(None);
+        }
+        let header = header_line.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut content = vec![0; content_length.unwrap_or(0)];
+    reader.read_exact(&mut content)?;
+    Ok(Some(String::from_utf8_lossy(&content).into_owned()))
+}
+
+fn write_message(writer: &mut impl Write, message: &JsonValue) -> io::Result<()> {
+    let content = message.to_json_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
@@ -1,12 +1,13 @@
 //! Command-line entry point for the GDScript formatter and linter.
 //!
-//! Formatting settings come from three places. The formatter starts with its
-//! built-in defaults, applies the `.editorconfig` properties that match each
-//! input file, and then applies any CLI option flags explicitly provided on the
-//! command line. Command line flags override editorconfig settings which
-//! override built-in defaults.
+//! Formatting settings come from four places. The formatter starts with its
+//! built-in defaults, applies the project configuration file found for each
+//! input file (`gdscript-formatter.toml` or the `[gdscript_formatter]` section
+//! of `project.godot`), then the `.editorconfig` properties that match the
+//! file, and finally any CLI option flags explicitly provided on the command
+//! line. Each source overrides the previous ones.
 //!
-//! Stdin input uses the current directory to find `.editorconfig`. It uses a
+//! Stdin input uses the current directory to find these files. It uses a
 //! synthetic `stdin.gd` path so filename sections such as `[*.gd]` also match.
 //!
 //! The formatter keeps both batch implementations below. The sequential path
@@ -15,6 +16,7 @@
 //! the same per-file configuration logic.
 
 mod cli;
+mod lsp;
 
 use std::{
     env, fs,
@@ -23,16 +25,21 @@ use std::{
     thread,
 };
 
+use gdscript_formatter::linter::LintSeverity;
+use gdscript_formatter::linter::output_formats::LintOutputFormat;
 use gdscript_formatter::linter::rule_config::{
-    get_all_rule_names, parse_disabled_rules, validate_rule_names,
+    RULES_DISABLED_BY_DEFAULT, get_all_rule_names, parse_disabled_rules, parse_rule_severities,
+    validate_rule_names,
 };
+use gdscript_formatter::project_config::ProjectConfigCache;
 use gdscript_formatter::{
-    FormatterConfiguration, QuoteStyle, RenderElement, format_gdscript,
-    format_gdscript_with_buffers, linter::LinterConfig,
+    Error, FormatterConfiguration, LineEnding, QuoteStyle, RenderElement, format_gdscript,
+    format_gdscript_range, format_gdscript_with_buffers, line_range_to_byte_range,
+    linter::LinterConfig, make_unified_diff,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use cli::{Command, parse_args};
+use cli::{Command, LineRange, parse_args};
 
 const ERROR_CODE_NOT_FORMATTED: i32 = 1;
 
@@ -40,11 +47,12 @@ const ERROR_CODE_NOT_FORMATTED: i32 = 1;
 struct FormatterOutput {
     index: usize,
     file_path: PathBuf,
+    original_content: String,
     formatted_content: String,
     is_formatted: bool,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 struct FormatterConfigOverrides {
     /// Explicitly requested tab or space indentation.
     use_spaces: Option<bool>,
@@ -58,22 +66,42 @@ struct FormatterConfigOverrides {
     continuation_indent_level: Option<u16>,
     /// Explicitly requested string quote style.
     quote_style: Option<QuoteStyle>,
+    /// Explicitly requested line ending.
+    line_ending: Option<LineEnding>,
+    /// Set when safe mode was requested on the command line.
+    safe: Option<bool>,
+    /// Set when the idempotency check was requested on the command line.
+    verify_idempotent: Option<bool>,
+    /// Set when code reordering was requested on the command line.
+    reorder_code: Option<bool>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let parsed_cli_args = parse_args();
 
+    if let Command::LanguageServer = parsed_cli_args.command {
+        let exit_code = lsp::run_language_server()?;
+        std::process::exit(exit_code);
+    }
+
     if let Command::Lint {
         disabled_linter_rules,
         max_line_length,
         do_list_rules,
-        do_pretty_print,
+        output_format,
+        do_fix,
+        rule_severities,
+        error_on_severity,
     } = parsed_cli_args.command
     {
         if do_list_rules {
             println!("Available linting rules:");
             for rule in get_all_rule_names() {
-                println!("  {}", rule);
+                if RULES_DISABLED_BY_DEFAULT.contains(&rule) {
+                    println!("  {} (off by default)", rule);
+                } else {
+                    println!("  {}", rule);
+                }
             }
             return Ok(());
         }
@@ -90,9 +118,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             HashSet::new()
         };
 
+        let rule_severities = if let Some(severities_str) = rule_severities {
+            let severities = match parse_rule_severities(&severities_str) {
+                Ok(severities) => severities,
+                Err(invalid_pair) => {
+                    eprintln!(
+                        "Error: Invalid rule severity '{}', expected rule=severity with a severity of error, warning, info, or hint",
+                        invalid_pair
+                    );
+                    std::process::exit(1);
+                }
+            };
+            let rule_names = severities.keys().cloned().collect();
+            if let Err(invalid_rules) = validate_rule_names(&rule_names) {
+                eprintln!("Error: Invalid rule names: {}", invalid_rules.join(", "));
+                eprintln!("Use --list-rules to see all available rules");
+                std::process::exit(1);
+            }
+            severities
+        } else {
+            HashMap::new()
+        };
+
         let linter_config = LinterConfig {
             disabled_rules,
             max_line_length: max_line_length.unwrap_or(100),
+            ..Default::default()
         };
 
         let input_gdscript_files = find_gdscript_files(
@@ -103,7 +154,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             &input_gdscript_files,
             linter_config,
             max_line_length,
-            do_pretty_print,
+            &rule_severities,
+            output_format,
+            do_fix,
+            &error_on_severity,
         );
     }
 
@@ -111,14 +165,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         do_print_to_stdout,
         use_verbose_output,
         do_check_formatted_only,
+        do_print_diff,
         use_spaces,
         indent_size,
         use_verify_structure,
+        use_verify_idempotent,
         do_reorder_code,
         max_line_length,
         blank_lines_around_definitions,
         continuation_indent_level,
         quote_style,
+        line_ending,
+        line_range,
     } = parsed_cli_args.command
     else {
         unreachable!();
@@ -126,6 +184,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut config = FormatterConfiguration {
         safe: use_verify_structure,
+        verify_idempotent: use_verify_idempotent,
         reorder_code: do_reorder_code,
         ..Default::default()
     };
@@ -133,6 +192,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let Some(quote_style) = quote_style {
         config.quote_style = quote_style;
     }
+    if let Some(line_ending) = line_ending {
+        config.printer.line_ending = line_ending;
+    }
 
     let config_overrides = FormatterConfigOverrides {
         use_spaces,
@@ -141,6 +203,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         blank_lines_around_definitions,
         continuation_indent_level,
         quote_style,
+        line_ending,
+        safe: use_verify_structure.then_some(true),
+        verify_idempotent: use_verify_idempotent.then_some(true),
+        reorder_code: do_reorder_code.then_some(true),
     };
 
     if parsed_cli_args.input_file_paths.is_empty() && !io::stdin().is_terminal() {
@@ -155,13 +221,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // settings. For the most part, you'd use a section like `[*.gd]`
         // matching GDScript files. we fake running the formatter on a `.gd`
         // file in the current directory to get user settings to apply.
-        config_apply_editorconfig_then_cli_overrides(
+        config_apply_file_settings_then_cli_overrides(
             &mut stdin_config,
             &current_directory.join("stdin.gd"),
+            &mut ProjectConfigCache::default(),
             config_overrides,
-        );
-        let formatted_content = format_gdscript(&input_content, &stdin_config)?;
+        )?;
+        let formatted_content = match line_range {
+            Some(line_range) => {
+                let byte_range = line_range_to_byte_range(
+                    &input_content,
+                    line_range.first_line,
+                    line_range.last_line,
+                );
+                format_gdscript_range(&input_content, &byte_range, &stdin_config)?
+            }
+            None => format_gdscript(&input_content, &stdin_config)?,
+        };
 
+        if do_print_diff {
+            print!(
+                "{}",
+                make_unified_diff(&input_content, &formatted_content, "stdin", "stdin")
+            );
+        }
         if do_check_formatted_only {
             if input_content != formatted_content {
                 eprintln!("The input passed via stdin is not formatted");
@@ -169,7 +252,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             } else {
                 eprintln!("The input passed via stdin is already formatted");
             }
-        } else {
+        } else if !do_print_diff {
             print!("{}", formatted_content);
         }
 
@@ -197,8 +280,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let _ = io::stdout().flush();
     }
 
+    // We apply the settings of each file before formatting files in parallel,
+    // so each project configuration file gets read once per run. Files in
+    // nested directories can match different configuration and EditorConfig
+    // files, so each file gets its own copy of the config.
+    let mut project_configs = ProjectConfigCache::default();
+    let mut file_configs = Vec::with_capacity(input_gdscript_files.len());
+    for file_path in &input_gdscript_files {
+        let mut file_config = config.clone();
+        let result = config_apply_file_settings_then_cli_overrides(
+            &mut file_config,
+            file_path,
+            &mut project_configs,
+            config_overrides,
+        );
+        file_configs.push(result.map(|()| file_config));
+    }
+
     let mut sorted_outputs: Vec<Result<FormatterOutput, String>> =
-        format_files_parallel(&input_gdscript_files, &config, config_overrides);
+        format_files_parallel(&input_gdscript_files, &file_configs, line_range);
 
     sorted_outputs.sort_by(compare_output_index);
 
@@ -208,6 +308,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     for output in sorted_outputs {
         match output {
             Ok(output) => {
+                if do_print_diff && !output.is_formatted {
+                    terminal_clear_line();
+                    eprint!("\r");
+                    let path = output.file_path.to_string_lossy();
+                    print!(
+                        "{}",
+                        make_unified_diff(
+                            &output.original_content,
+                            &output.formatted_content,
+                            &path,
+                            &path,
+                        )
+                    );
+                }
                 if do_check_formatted_only {
                     if use_verbose_output {
                         eprintln!(
@@ -224,6 +338,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         all_formatted = false;
                         unformatted_files.push(output.file_path);
                     }
+                } else if do_print_diff {
+                    if !output.is_formatted {
+                        unformatted_files.push(output.file_path);
+                    }
                 } else if do_print_to_stdout {
                     terminal_clear_line();
                     eprint!("\r");
@@ -284,6 +402,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
             std::process::exit(ERROR_CODE_NOT_FORMATTED);
         }
+    } else if do_print_diff {
+        if !use_verbose_output {
+            terminal_clear_line();
+        }
+        let summary_prefix = if use_verbose_output { "" } else { "\r" };
+        eprintln!(
+            "{}{} of {} file(s) would be formatted",
+            summary_prefix,
+            unformatted_files.len(),
+            total_files
+        );
     } else if !do_print_to_stdout {
         if !use_verbose_output {
             terminal_clear_line();
@@ -328,66 +457,92 @@ fn run_linter(
     input_files: &[PathBuf],
     config: LinterConfig,
     max_line_length_override: Option<usize>,
-    do_pretty_print: bool,
+    rule_severity_overrides: &HashMap<String, LintSeverity>,
+    output_format: LintOutputFormat,
+    do_fix: bool,
+    error_on_severity: &LintSeverity,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut linter = gdscript_formatter::linter::GDScriptLinter::new(config)?;
-    let has_issues = linter.lint_files_with_editorconfig(
+    let highest_severity = linter.lint_files_with_editorconfig(
         input_files,
-        do_pretty_print,
+        output_format,
         max_line_length_override,
+        rule_severity_overrides,
+        do_fix,
     )?;
 
-    if has_issues {
+    if let Some(severity) = highest_severity
+        && severity.is_at_least(error_on_severity)
+    {
         std::process::exit(1);
     }
 
     Ok(())
 }
 
-/// Formats one file after applying its matching editorconfig settings.
-///
-/// Different files can fall under different editorconfig settings so we clone
-/// the base configuration and apply file-specific settings individually.
-/// CLI options override editorconfig are applied last by [`apply_file_config`].
+/// Formats one file with its configuration, built by
+/// [`config_apply_file_settings_then_cli_overrides`].
 fn format_one_file(
     index: usize,
     file_path: &PathBuf,
-    config: &FormatterConfiguration,
-    config_overrides: FormatterConfigOverrides,
+    file_config: &FormatterConfiguration,
+    line_range: Option<LineRange>,
     render_elements: &mut Vec<RenderElement>,
     output: &mut String,
 ) -> Result<FormatterOutput, String> {
     let input_content = fs::read_to_string(file_path)
         .map_err(|error| format!("Failed to read file {}: {}", file_path.display(), error))?;
 
-    // We need to clone that config because files in nested directories can
-    // match different EditorConfig files and rules.
-    let mut file_config = config.clone();
-    config_apply_editorconfig_then_cli_overrides(&mut file_config, file_path, config_overrides);
-    format_gdscript_with_buffers(&input_content, &file_config, render_elements, output)
-        .map_err(|error| format!("Failed to format file {}: {}", file_path.display(), error))?;
+    match line_range {
+        Some(line_range) => {
+            let byte_range = line_range_to_byte_range(
+                &input_content,
+                line_range.first_line,
+                line_range.last_line,
+            );
+            *output = format_gdscript_range(&input_content, &byte_range, file_config)
+                .map_err(|error| describe_format_error(file_path, &error))?;
+        }
+        None => {
+            format_gdscript_with_buffers(&input_content, file_config, render_elements, output)
+                .map_err(|error| describe_format_error(file_path, &error))?;
+        }
+    }
 
     let is_formatted = input_content == *output;
 
     Ok(FormatterOutput {
         index,
         file_path: file_path.clone(),
+        original_content: input_content,
         formatted_content: output.clone(),
         is_formatted,
     })
 }
 
-/// Applies project editorconfig settings first and CLI settings second.
+/// Describes why formatting a file failed. Syntax errors get the file path in
+/// front of their location so terminals and editors can link to them.
+fn describe_format_error(file_path: &Path, error: &Error) -> String {
+    let path = file_path.display().to_string();
+    format!("Failed to format file {}: {}", path, error.format(&path))
+}
+
+/// Applies the project configuration file first, editorconfig settings second,
+/// and CLI settings last.
 ///
 /// The override fields are `Option`s because `None` means that the user did
 /// not pass that flag. Without this distinction, a CLI default such as an
 /// indentation size of four would look like an explicit request and would
 /// incorrectly override `.editorconfig`.
-fn config_apply_editorconfig_then_cli_overrides(
+fn config_apply_file_settings_then_cli_overrides(
     config: &mut FormatterConfiguration,
     config_path: &Path,
+    project_configs: &mut ProjectConfigCache,
     config_overrides: FormatterConfigOverrides,
-) {
+) -> Result<(), String> {
+    project_configs
+        .apply_to_formatter_config(config, config_path)
+        .map_err(|error| error.to_string())?;
     gdscript_formatter::editorconfig::apply_editorconfig_to_formatter_config(config, config_path);
     if let Some(use_spaces) = config_overrides.use_spaces {
         config.printer.use_spaces = use_spaces;
@@ -407,14 +562,28 @@ fn config_apply_editorconfig_then_cli_overrides(
     if let Some(quote_style) = config_overrides.quote_style {
         config.quote_style = quote_style;
     }
+    if let Some(line_ending) = config_overrides.line_ending {
+        config.printer.line_ending = line_ending;
+    }
+    if let Some(safe) = config_overrides.safe {
+        config.safe = safe;
+    }
+    if let Some(verify_idempotent) = config_overrides.verify_idempotent {
+        config.verify_idempotent = verify_idempotent;
+    }
+    if let Some(reorder_code) = config_overrides.reorder_code {
+        config.reorder_code = reorder_code;
+    }
+    Ok(())
 }
 
 /// Formats files concurrently but preserves their original order when
-/// outputting the results.
+/// outputting the results. `file_configs` holds the configuration of each
+/// file, or the error we got reading it.
 fn format_files_parallel(
     files: &[PathBuf],
-    config: &FormatterConfiguration,
-    config_overrides: FormatterConfigOverrides,
+    file_configs: &[Result<FormatterConfiguration, String>],
+    line_range: Option<LineRange>,
 ) -> Vec<Result<FormatterOutput, String>> {
     if files.is_empty() {
         return Vec::new();
@@ -429,9 +598,12 @@ fn format_files_parallel(
 
     thread::scope(|scope| {
         let mut handles = Vec::with_capacity(thread_count);
-        for (chunk_index, chunk) in files.chunks(chunk_size).enumerate() {
+        let config_chunks = file_configs.chunks(chunk_size);
+        for (chunk_index, (chunk, chunk_configs)) in
+            files.chunks(chunk_size).zip(config_chunks).enumerate()
+        {
             let handle = scope.spawn(move || {
-                format_chunk(chunk, chunk_index, chunk_size, config, config_overrides)
+                format_chunk(chunk, chunk_configs, chunk_index, chunk_size, line_range)
             });
             handles.push(handle);
         }
@@ -446,24 +618,28 @@ fn format_files_parallel(
 
 fn format_chunk(
     chunk: &[PathBuf],
+    chunk_configs: &[Result<FormatterConfiguration, String>],
     chunk_index: usize,
     chunk_size: usize,
-    config: &FormatterConfiguration,
-    config_overrides: FormatterConfigOverrides,
+    line_range: Option<LineRange>,
 ) -> Vec<Result<FormatterOutput, String>> {
     let mut results = Vec::with_capacity(chunk.len());
     let mut render_elements: Vec<RenderElement> = Vec::new();
     let mut output = String::new();
-    for (local_index, file_path) in chunk.iter().enumerate() {
+    for (local_index, (file_path, file_config)) in chunk.iter().zip(chunk_configs).enumerate() {
         let global_index = chunk_index * chunk_size + local_index;
-        results.push(format_one_file(
-            global_index,
-            file_path,
-            config,
-            config_overrides,
-            &mut render_elements,
-            &mut output,
-        ));
+        let result = match file_config {
+            Ok(file_config) => format_one_file(
+                global_index,
+                file_path,
+                file_config,
+                line_range,
+                &mut render_elements,
+                &mut output,
+            ),
+            Err(error) => Err(error.clone()),
+        };
+        results.push(result);
     }
     results
 }
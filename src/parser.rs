@@ -5,6 +5,9 @@
 
 use crate::QuoteStyle;
 use crate::node_kind::GDScriptNodeKind;
+use crate::renderer::LineEnding;
+use crate::reorder::ReorderConfiguration;
+use std::borrow::Cow;
 use tree_sitter;
 
 pub struct ParseInput<'src> {
@@ -13,6 +16,7 @@ pub struct ParseInput<'src> {
     pub kind_lookup: &'static [GDScriptNodeKind; 256],
     pub has_parse_errors: bool,
     pub reorder_code: bool,
+    pub reorder: ReorderConfiguration,
     pub blank_lines_around_definitions: u16,
     /// Extra indent level for continuation lines (default 2).
     pub continuation_indent_level: u16,
@@ -42,7 +46,7 @@ pub struct RegionWithDisabledFormatting {
 /// Scans the source code character by character to find pairs of "# fmt: off"
 /// and "# fmt: on" comments. Returns the byte ranges where formatting
 /// should be disabled.
-fn find_disabled_regions(source: &str) -> Vec<RegionWithDisabledFormatting> {
+pub fn find_disabled_regions(source: &str) -> Vec<RegionWithDisabledFormatting> {
     // Nathan: I tried first to iterate over the tree sitter node tree. I don't
     // know if I was doing something wrong, but it made the entire formatter
     // ~20% slower. There is also the option of querying nodes with tree sitter,
@@ -227,6 +231,183 @@ fn find_disabled_regions(source: &str) -> Vec<RegionWithDisabledFormatting> {
     regions
 }
 
+/// The byte order mark some editors write at the start of UTF-8 files.
+pub const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+/// The line ending style and byte order mark of a source file. The formatter
+/// works on source code with LF line endings and no byte order mark, and uses
+/// this to write the output the way the input was saved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceEncoding {
+    /// The line ending of the first line break in the source, or LF if the
+    /// source has a single line.
+    pub line_ending: LineEnding,
+    pub has_byte_order_mark: bool,
+}
+
+impl SourceEncoding {
+    pub fn detect(source: &str) -> Self {
+        let line_ending = match source.find('\n') {
+            Some(newline_position) if source[..newline_position].ends_with('\r') => {
+                LineEnding::CrLf
+            }
+            _ => LineEnding::Lf,
+        };
+        Self {
+            line_ending,
+            has_byte_order_mark: source.starts_with(BYTE_ORDER_MARK),
+        }
+    }
+}
+
+/// Removes the byte order mark and converts CRLF line endings to LF. Returns
+/// the source as is if it has neither, which is the common case.
+///
+/// This converts every CRLF, including the ones inside multiline strings and
+/// `# fmt: off` regions, as we don't parse the code yet. In a file with mixed
+/// line endings, these line breaks come out with the line ending of the
+/// output. Safe mode uses [find_crlf_line_breaks] to report strings whose
+/// value this changes.
+pub fn normalize_source(source: &str) -> Cow<'_, str> {
+    let source = source.strip_prefix(BYTE_ORDER_MARK).unwrap_or(source);
+    if source.contains("\r\n") {
+        Cow::Owned(source.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(source)
+    }
+}
+
+/// Converts a byte offset in a source file to the matching offset in the
+/// source returned by [normalize_source].
+pub fn normalize_source_offset(source: &str, byte_offset: usize) -> usize {
+    let byte_offset = byte_offset.min(source.len());
+    let byte_order_mark_length = if source.starts_with(BYTE_ORDER_MARK) {
+        BYTE_ORDER_MARK.len_utf8()
+    } else {
+        0
+    };
+    let removed_carriage_returns = source[..byte_offset].matches("\r\n").count()
+        + usize::from(
+            source[byte_offset..].starts_with('\n') && source[..byte_offset].ends_with('\r'),
+        );
+    byte_offset.saturating_sub(byte_order_mark_length + removed_carriage_returns)
+}
+
+/// Returns the offsets of the line breaks that are CRLF in a source file, in
+/// the source returned by [normalize_source]. Each offset points at the line
+/// break's LF. The offsets are sorted.
+pub fn find_crlf_line_breaks(source: &str) -> Vec<usize> {
+    let source = source.strip_prefix(BYTE_ORDER_MARK).unwrap_or(source);
+    source
+        .match_indices("\r\n")
+        .enumerate()
+        .map(|(removed_carriage_returns, (offset, _))| offset - removed_carriage_returns)
+        .collect()
+}
+
+/// A syntax error in the parsed source code. Lines and columns are 1-based,
+/// and columns count bytes, like lint issues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String,
+    /// The source line where the error starts, without its line break.
+    pub snippet: String,
+}
+
+impl SyntaxError {
+    /// Formats the error like a compiler would: the file path, location, and
+    /// message, followed by the source line and a caret under the error.
+    pub fn format(&self, file_path: &str) -> String {
+        format!("{}:{}", file_path, self)
+    }
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let column_prefix = self
+            .snippet
+            .get(..self.column.saturating_sub(1))
+            .unwrap_or(&self.snippet);
+        // We keep tabs so the caret lines up with the code above it.
+        let caret_indent: String = column_prefix
+            .chars()
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            formatter,
+            "{}:{}: syntax error: {}\n    {}\n    {}^",
+            self.line, self.column, self.message, self.snippet, caret_indent
+        )
+    }
+}
+
+/// Maximum number of characters of unexpected code we show in syntax error
+/// messages.
+const MAX_SYNTAX_ERROR_EXCERPT_LENGTH: usize = 30;
+
+/// Walks the tree to find the ERROR and MISSING nodes tree-sitter inserts
+/// when it recovers from syntax errors. Returns one error per node, in source
+/// order. We don't look inside ERROR nodes, as their content is part of the
+/// same error.
+pub fn find_syntax_errors(root: tree_sitter::Node, source: &str) -> Vec<SyntaxError> {
+    let mut errors = Vec::new();
+    if root.has_error() {
+        collect_syntax_errors(root, source, &mut errors);
+    }
+    errors
+}
+
+fn collect_syntax_errors(node: tree_sitter::Node, source: &str, errors: &mut Vec<SyntaxError>) {
+    if node.is_error() || node.is_missing() {
+        let message = if node.is_missing() {
+            format!("missing `{}`", node.kind())
+        } else {
+            let node_text = &source[node.start_byte()..node.end_byte()];
+            let first_line = node_text.lines().next().unwrap_or("").trim();
+            let mut excerpt: String = first_line
+                .chars()
+                .take(MAX_SYNTAX_ERROR_EXCERPT_LENGTH)
+                .collect();
+            if excerpt.len() < first_line.len() {
+                excerpt.push_str("...");
+            }
+            format!("unexpected `{}`", excerpt)
+        };
+        let start = node.start_position();
+        let end = node.end_position();
+        let line_start = source[..node.start_byte()]
+            .rfind('\n')
+            .map_or(0, |newline_index| newline_index + 1);
+        let line_end = source[node.start_byte()..]
+            .find('\n')
+            .map_or(source.len(), |newline_index| {
+                node.start_byte() + newline_index
+            });
+        errors.push(SyntaxError {
+            line: start.row + 1,
+            column: start.column + 1,
+            end_line: end.row + 1,
+            end_column: end.column + 1,
+            message,
+            snippet: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        });
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() {
+            collect_syntax_errors(child, source, errors);
+        }
+    }
+}
+
 impl<'src> ParseInput<'src> {
     pub fn new(source: &'src str, config: &crate::FormatterConfiguration) -> Option<Self> {
         let mut parser = tree_sitter::Parser::new();
@@ -244,6 +425,7 @@ impl<'src> ParseInput<'src> {
             kind_lookup,
             has_parse_errors,
             reorder_code: config.reorder_code,
+            reorder: config.reorder.clone(),
             blank_lines_around_definitions: config.blank_lines_around_definitions,
             continuation_indent_level: config.printer.continuation_indent_level,
             quote_style: config.quote_style,
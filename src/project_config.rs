@@ -0,0 +1,983 @@
+//! Reads the project configuration file and applies its values to the
+//! formatter and linter configs.
+//!
+//! Teams can share settings in a `gdscript-formatter.toml` file or in a
+//! `[gdscript_formatter]` section of Godot's `project.godot` file. For each
+//! input file, we look for a configuration in the file's directory, then in
+//! each parent directory. We stop at the first directory that contains
+//! `project.godot`, the root of the Godot project. The closest configuration
+//! file wins, and `gdscript-formatter.toml` wins over `project.godot` in the
+//! same directory.
+//!
+//! Settings apply in this order, each source overriding the previous ones:
+//!
+//! 1. Built-in defaults.
+//! 2. The project configuration file.
+//! 3. Matching `.editorconfig` properties (see [crate::editorconfig]).
+//! 4. Command line options.
+//!
+//! Both files support the same keys. In TOML, tables group the keys:
+//!
+//! ```toml
+//! [formatter]
+//! indent_size = 4
+//!
+//! [formatter.reorder]
+//! sort_mode = "alphabetical"
+//!
+//! [linter]
+//! disabled_rules = ["private-access"]
+//!
+//! [linter.rules]
+//! unused-argument = "error"
+//!
+//! [linter.naming.function-name]
+//! pattern = '^_?[a-z][a-z0-9_]*$'
+//! allowed_names = ["_on_Button_pressed"]
+//! ```
+//!
+//! In `project.godot`, keys include their section, like Godot's own settings:
+//! `formatter/indent_size=4` or `linter/rules/unused-argument="error"`.
+//!
+//! We only support the subset of TOML these settings need: tables, strings,
+//! integers, booleans, and arrays of strings. Unknown keys and values of the
+//! wrong type are errors, so typos don't go unnoticed.
+
+use crate::linter::rule_config::{NAMING_RULE_NAMES, get_all_rule_names};
+use crate::linter::{LintSeverity, LinterConfig};
+use crate::{
+    Error, FormatterConfiguration, LineEnding, QuoteStyle, ReorderConfiguration, ReorderRegionMode,
+    ReorderSortMode,
+};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PROJECT_CONFIG_FILE_NAME: &str = "gdscript-formatter.toml";
+const PROJECT_GODOT_FILE_NAME: &str = "project.godot";
+const PROJECT_GODOT_SECTION: &str = "[gdscript_formatter]";
+
+/// A value read from a project configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    StringArray(Vec<String>),
+}
+
+/// One setting from a project configuration file. The key includes the
+/// section, like `formatter/indent_size`.
+#[derive(Debug, Clone)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: ConfigValue,
+    /// 1-based line of the setting in the file, used in error messages.
+    pub line: usize,
+}
+
+/// The settings of a project configuration file.
+#[derive(Debug, Clone)]
+pub struct ProjectConfig {
+    pub path: PathBuf,
+    pub entries: Vec<ConfigEntry>,
+}
+
+/// Finds the project configuration for `file_path` and applies its formatter
+/// settings to `config`. Does nothing if there is no configuration file.
+/// Returns [Error::Config] if the file can't be read or has invalid values.
+pub fn apply_project_config_to_formatter_config(
+    config: &mut FormatterConfiguration,
+    file_path: &Path,
+) -> Result<(), Error> {
+    let Some(project_config) = find_project_config(file_path).map_err(Error::Config)? else {
+        return Ok(());
+    };
+    project_config
+        .apply_to_formatter_config(config)
+        .map_err(Error::Config)
+}
+
+/// Finds the project configuration for `file_path` and applies its linter
+/// settings to `config`. Does nothing if there is no configuration file.
+/// Returns [Error::Config] if the file can't be read or has invalid values.
+pub fn apply_project_config_to_linter_config(
+    config: &mut LinterConfig,
+    file_path: &Path,
+) -> Result<(), Error> {
+    let Some(project_config) = find_project_config(file_path).map_err(Error::Config)? else {
+        return Ok(());
+    };
+    project_config
+        .apply_to_linter_config(config)
+        .map_err(Error::Config)
+}
+
+/// Searches the directory of `file_path` and its parents for a configuration
+/// file, stopping at the Godot project root.
+pub fn find_project_config(file_path: &Path) -> Result<Option<ProjectConfig>, String> {
+    match find_project_config_path(file_path)? {
+        Some(config_path) => read_project_config(&config_path),
+        None => Ok(None),
+    }
+}
+
+/// Returns the path of the configuration file that applies to `file_path`:
+/// the closest `gdscript-formatter.toml` or `project.godot` file.
+fn find_project_config_path(file_path: &Path) -> Result<Option<PathBuf>, String> {
+    let absolute_path = make_absolute(file_path)?;
+
+    let mut directory = absolute_path.parent();
+    while let Some(current_directory) = directory {
+        let config_path = current_directory.join(PROJECT_CONFIG_FILE_NAME);
+        if config_path.is_file() {
+            return Ok(Some(config_path));
+        }
+        let project_godot_path = current_directory.join(PROJECT_GODOT_FILE_NAME);
+        if project_godot_path.is_file() {
+            return Ok(Some(project_godot_path));
+        }
+        directory = current_directory.parent();
+    }
+    Ok(None)
+}
+
+/// Reads and parses a configuration file. Returns None for a `project.godot`
+/// file without a formatter section.
+fn read_project_config(config_path: &Path) -> Result<Option<ProjectConfig>, String> {
+    let text = read_config_file(config_path)?;
+    if config_path.file_name() == Some(PROJECT_GODOT_FILE_NAME.as_ref()) {
+        return ProjectConfig::parse_project_godot(&text, config_path);
+    }
+    ProjectConfig::parse_toml(&text, config_path).map(Some)
+}
+
+/// Remembers the configuration files it read, so a run that formats or lints
+/// many files reads and parses each configuration file once.
+#[derive(Debug, Default)]
+pub struct ProjectConfigCache {
+    /// The configuration of each file we read, by path.
+    configs: HashMap<PathBuf, Option<ProjectConfig>>,
+    /// The Godot project root of each directory we looked up.
+    project_roots: HashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl ProjectConfigCache {
+    /// Like [find_project_config], but only reads each configuration file the
+    /// first time a file needs it.
+    pub fn find_project_config(
+        &mut self,
+        file_path: &Path,
+    ) -> Result<Option<&ProjectConfig>, String> {
+        let Some(config_path) = find_project_config_path(file_path)? else {
+            return Ok(None);
+        };
+        if !self.configs.contains_key(&config_path) {
+            let project_config = read_project_config(&config_path)?;
+            self.configs.insert(config_path.clone(), project_config);
+        }
+        Ok(self.configs[&config_path].as_ref())
+    }
+
+    /// Like [find_project_root], but only searches each directory's project
+    /// root once.
+    pub fn find_project_root(&mut self, file_path: &Path) -> Result<Option<PathBuf>, String> {
+        let absolute_path = make_absolute(file_path)?;
+        let Some(directory) = absolute_path.parent() else {
+            return Ok(None);
+        };
+        if let Some(project_root) = self.project_roots.get(directory) {
+            return Ok(project_root.clone());
+        }
+        let project_root = find_project_root(&absolute_path)?;
+        self.project_roots
+            .insert(directory.to_path_buf(), project_root.clone());
+        Ok(project_root)
+    }
+
+    /// Like [apply_project_config_to_formatter_config], with the cached
+    /// configuration files.
+    pub fn apply_to_formatter_config(
+        &mut self,
+        config: &mut FormatterConfiguration,
+        file_path: &Path,
+    ) -> Result<(), Error> {
+        let Some(project_config) = self.find_project_config(file_path).map_err(Error::Config)?
+        else {
+            return Ok(());
+        };
+        project_config
+            .apply_to_formatter_config(config)
+            .map_err(Error::Config)
+    }
+
+    /// Like [apply_project_config_to_linter_config], with the cached
+    /// configuration files.
+    pub fn apply_to_linter_config(
+        &mut self,
+        config: &mut LinterConfig,
+        file_path: &Path,
+    ) -> Result<(), Error> {
+        let Some(project_config) = self.find_project_config(file_path).map_err(Error::Config)?
+        else {
+            return Ok(());
+        };
+        project_config
+            .apply_to_linter_config(config)
+            .map_err(Error::Config)
+    }
+}
+
+/// Returns the Godot project root of `file_path`: the closest directory
+/// containing `project.godot`, which `res://` paths are relative to.
+pub fn find_project_root(file_path: &Path) -> Result<Option<PathBuf>, String> {
+    let absolute_path = make_absolute(file_path)?;
+    let mut directory = absolute_path.parent();
+    while let Some(current_directory) = directory {
+        if current_directory.join(PROJECT_GODOT_FILE_NAME).is_file() {
+            return Ok(Some(current_directory.to_path_buf()));
+        }
+        directory = current_directory.parent();
+    }
+    Ok(None)
+}
+
+fn make_absolute(file_path: &Path) -> Result<PathBuf, String> {
+    if file_path.is_absolute() {
+        return Ok(file_path.to_path_buf());
+    }
+    Ok(std::env::current_dir()
+        .map_err(|error| format!("Failed to get current directory: {}", error))?
+        .join(file_path))
+}
+
+fn read_config_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read file {}: {}", path.display(), error))
+}
+
+impl ProjectConfig {
+    /// Parses a `gdscript-formatter.toml` file and checks all its keys and
+    /// values.
+    pub fn parse_toml(text: &str, path: &Path) -> Result<Self, String> {
+        let mut project_config = Self {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+        };
+
+        let lines: Vec<&str> = text.lines().collect();
+        let mut section = String::new();
+        let mut line_index = 0;
+        while line_index < lines.len() {
+            let line_number = line_index + 1;
+            let content = strip_comment(lines[line_index], '#').trim();
+            line_index += 1;
+            if content.is_empty() {
+                continue;
+            }
+
+            if content.starts_with('[') {
+                if content.starts_with("[[") || !content.ends_with(']') {
+                    return Err(project_config.error_at(line_number, "invalid table header"));
+                }
+                section = content[1..content.len() - 1].trim().replace('.', "/");
+                continue;
+            }
+
+            let Some((key, value_text)) = content.split_once('=') else {
+                return Err(project_config.error_at(line_number, "expected `key = value`"));
+            };
+            // Arrays can span multiple lines, one item per line.
+            let mut value_text = value_text.trim().to_string();
+            if value_text.starts_with('[') {
+                while !value_text.ends_with(']') && line_index < lines.len() {
+                    value_text.push(' ');
+                    value_text.push_str(strip_comment(lines[line_index], '#').trim());
+                    line_index += 1;
+                }
+            }
+
+            let key = key.trim().trim_matches('"');
+            let full_key = if section.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}/{}", section, key)
+            };
+            project_config.push_entry(full_key, &value_text, line_number)?;
+        }
+
+        project_config.validate()?;
+        Ok(project_config)
+    }
+
+    /// Parses the `[gdscript_formatter]` section of a `project.godot` file.
+    /// Returns None if the file has no such section.
+    pub fn parse_project_godot(text: &str, path: &Path) -> Result<Option<Self>, String> {
+        let mut project_config = Self {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+        };
+
+        let mut has_section = false;
+        let mut is_in_section = false;
+        for (line_index, line) in text.lines().enumerate() {
+            let content = strip_comment(line, ';').trim();
+            if content.is_empty() {
+                continue;
+            }
+            if content.starts_with('[') {
+                is_in_section = content == PROJECT_GODOT_SECTION;
+                has_section = has_section || is_in_section;
+                continue;
+            }
+            if !is_in_section {
+                continue;
+            }
+
+            let Some((key, value_text)) = content.split_once('=') else {
+                return Err(project_config.error_at(line_index + 1, "expected `key=value`"));
+            };
+            project_config.push_entry(key.trim().to_string(), value_text.trim(), line_index + 1)?;
+        }
+
+        if !has_section {
+            return Ok(None);
+        }
+        project_config.validate()?;
+        Ok(Some(project_config))
+    }
+
+    pub fn apply_to_formatter_config(
+        &self,
+        config: &mut FormatterConfiguration,
+    ) -> Result<(), String> {
+        for entry in &self.entries {
+            self.apply_formatter_entry(config, entry)?;
+        }
+        Ok(())
+    }
+
+    pub fn apply_to_linter_config(&self, config: &mut LinterConfig) -> Result<(), String> {
+        // The class-definitions-order rule checks the declaration order the
+        // formatter would produce, so it reads the formatter's reorder keys.
+        let mut formatter_config = FormatterConfiguration {
+            reorder: config.reorder.clone(),
+            ..Default::default()
+        };
+        for entry in &self.entries {
+            if entry.key.starts_with("formatter/reorder/") {
+                self.apply_formatter_entry(&mut formatter_config, entry)?;
+            }
+            self.apply_linter_entry(config, entry)?;
+        }
+        config.reorder = formatter_config.reorder;
+        Ok(())
+    }
+
+    fn push_entry(&mut self, key: String, value_text: &str, line: usize) -> Result<(), String> {
+        let value = parse_value(value_text).map_err(|message| self.error_at(line, &message))?;
+        self.entries.push(ConfigEntry { key, value, line });
+        Ok(())
+    }
+
+    /// Applies every entry to default configs to report unknown keys and
+    /// invalid values when reading the file.
+    fn validate(&self) -> Result<(), String> {
+        let mut formatter_config = FormatterConfiguration::default();
+        let mut linter_config = LinterConfig::default();
+        for entry in &self.entries {
+            let is_formatter_key = self.apply_formatter_entry(&mut formatter_config, entry)?;
+            let is_linter_key = self.apply_linter_entry(&mut linter_config, entry)?;
+            if !is_formatter_key && !is_linter_key {
+                return Err(self.error_at(entry.line, &format!("unknown key '{}'", entry.key)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies one entry to the formatter config. Returns false if the key is
+    /// not a formatter setting.
+    fn apply_formatter_entry(
+        &self,
+        config: &mut FormatterConfiguration,
+        entry: &ConfigEntry,
+    ) -> Result<bool, String> {
+        match entry.key.as_str() {
+            "formatter/use_spaces" => config.printer.use_spaces = self.expect_bool(entry)?,
+            "formatter/indent_size" => {
+                config.printer.indent_size = self.expect_positive_usize(entry)?;
+            }
+            "formatter/max_line_length" => {
+                config.printer.max_line_length = self.expect_positive_usize(entry)?;
+            }
+            "formatter/maximum_blank_lines" => {
+                config.printer.maximum_blank_lines = self.expect_u16(entry)?;
+            }
+            "formatter/continuation_indent_level" => {
+                config.printer.continuation_indent_level = self.expect_u16(entry)?;
+            }
+            "formatter/insert_final_newline" => {
+                config.printer.insert_final_newline = self.expect_bool(entry)?;
+            }
+            "formatter/trim_trailing_whitespace" => {
+                config.printer.trim_trailing_whitespace = self.expect_bool(entry)?;
+            }
+            "formatter/indent_blank_lines" => {
+                config.printer.indent_blank_lines = self.expect_bool(entry)?;
+            }
+            "formatter/safe" => config.safe = self.expect_bool(entry)?,
+            "formatter/verify_idempotent" => {
+                config.verify_idempotent = self.expect_bool(entry)?;
+            }
+            "formatter/reorder_code" => config.reorder_code = self.expect_bool(entry)?,
+            "formatter/blank_lines_around_definitions" => {
+                config.blank_lines_around_definitions = self.expect_u16(entry)?;
+            }
+            "formatter/quote_style" => {
+                let name = self.expect_string(entry)?;
+                config.quote_style = QuoteStyle::from_name(name).ok_or_else(|| {
+                    self.error_at(
+                        entry.line,
+                        &format!(
+                            "quote_style must be preserve, single, double, or minimize-escapes, got '{}'",
+                            name
+                        ),
+                    )
+                })?;
+            }
+            "formatter/reorder/category_order" => {
+                let names = self.expect_string_array(entry)?;
+                config.reorder.category_order =
+                    ReorderConfiguration::parse_category_order(names)
+                        .map_err(|message| self.error_at(entry.line, &message))?;
+            }
+            "formatter/reorder/sort_mode" => {
+                let name = self.expect_string(entry)?;
+                config.reorder.sort_mode = ReorderSortMode::from_name(name).ok_or_else(|| {
+                    self.error_at(
+                        entry.line,
+                        &format!(
+                            "sort_mode must be source, alphabetical, or public-first, got '{}'",
+                            name
+                        ),
+                    )
+                })?;
+            }
+            "formatter/reorder/region_mode" => {
+                let name = self.expect_string(entry)?;
+                config.reorder.region_mode =
+                    ReorderRegionMode::from_name(name).ok_or_else(|| {
+                        self.error_at(
+                            entry.line,
+                            &format!(
+                                "region_mode must be attach, in-place, or first-member, got '{}'",
+                                name
+                            ),
+                        )
+                    })?;
+            }
+            "formatter/reorder/virtual_method_order" => {
+                config.reorder.virtual_method_order = self.expect_string_array(entry)?.to_vec();
+            }
+            "formatter/line_ending" => {
+                let name = self.expect_string(entry)?;
+                config.printer.line_ending = LineEnding::from_name(name).ok_or_else(|| {
+                    self.error_at(
+                        entry.line,
+                        &format!("line_ending must be auto, lf, or crlf, got '{}'", name),
+                    )
+                })?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Applies one entry to the linter config. Returns false if the key is not
+    /// a linter setting.
+    fn apply_linter_entry(
+        &self,
+        config: &mut LinterConfig,
+        entry: &ConfigEntry,
+    ) -> Result<bool, String> {
+        if let Some(naming_key) = entry.key.strip_prefix("linter/naming/") {
+            let Some((rule_name, setting)) = naming_key.split_once('/') else {
+                return Ok(false);
+            };
+            if !NAMING_RULE_NAMES.contains(&rule_name) {
+                return Err(
+                    self.error_at(entry.line, &format!("'{}' is not a naming rule", rule_name))
+                );
+            }
+            let naming_convention = config
+                .naming_conventions
+                .entry(rule_name.to_string())
+                .or_default();
+            match setting {
+                "pattern" => {
+                    let pattern = self.expect_string(entry)?;
+                    let regex = Regex::new(pattern).map_err(|error| {
+                        self.error_at(
+                            entry.line,
+                            &format!("invalid pattern for '{}': {}", rule_name, error),
+                        )
+                    })?;
+                    naming_convention.pattern = Some(regex);
+                }
+                "allowed_names" => {
+                    let ConfigValue::StringArray(names) = &entry.value else {
+                        return Err(self.error_at(
+                            entry.line,
+                            &format!("expected an array of names for '{}'", entry.key),
+                        ));
+                    };
+                    naming_convention
+                        .allowed_names
+                        .extend(names.iter().cloned());
+                }
+                _ => return Ok(false),
+            }
+            return Ok(true);
+        }
+
+        if let Some(rule_name) = entry.key.strip_prefix("linter/rules/") {
+            self.expect_rule_name(entry, rule_name)?;
+            let value = self.expect_string(entry)?;
+            if value == "off" {
+                config.disabled_rules.insert(rule_name.to_string());
+            } else if let Some(severity) = LintSeverity::from_name(value) {
+                config.disabled_rules.remove(rule_name);
+                config
+                    .rule_severities
+                    .insert(rule_name.to_string(), severity);
+            } else {
+                return Err(self.error_at(
+                    entry.line,
+                    &format!(
+                        "rule setting must be off, error, warning, info, or hint, got '{}'",
+                        value
+                    ),
+                ));
+            }
+            return Ok(true);
+        }
+
+        match entry.key.as_str() {
+            "linter/max_line_length" => {
+                config.max_line_length = self.expect_positive_usize(entry)?;
+            }
+            "linter/disabled_rules" => {
+                let ConfigValue::StringArray(rule_names) = &entry.value else {
+                    return Err(self.error_at(
+                        entry.line,
+                        &format!("expected an array of rule names for '{}'", entry.key),
+                    ));
+                };
+                for rule_name in rule_names {
+                    self.expect_rule_name(entry, rule_name)?;
+                    config.disabled_rules.insert(rule_name.clone());
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn expect_bool(&self, entry: &ConfigEntry) -> Result<bool, String> {
+        match entry.value {
+            ConfigValue::Bool(value) => Ok(value),
+            _ => Err(self.error_at(
+                entry.line,
+                &format!("expected true or false for '{}'", entry.key),
+            )),
+        }
+    }
+
+    fn expect_usize(&self, entry: &ConfigEntry) -> Result<usize, String> {
+        match entry.value {
+            ConfigValue::Integer(value) if value >= 0 => Ok(value as usize),
+            _ => Err(self.error_at(
+                entry.line,
+                &format!("expected a non-negative integer for '{}'", entry.key),
+            )),
+        }
+    }
+
+    /// Like `expect_usize`, for sizes where 0 makes no sense.
+    fn expect_positive_usize(&self, entry: &ConfigEntry) -> Result<usize, String> {
+        match entry.value {
+            ConfigValue::Integer(value) if value > 0 => Ok(value as usize),
+            _ => Err(self.error_at(
+                entry.line,
+                &format!("expected a positive integer for '{}'", entry.key),
+            )),
+        }
+    }
+
+    fn expect_u16(&self, entry: &ConfigEntry) -> Result<u16, String> {
+        let value = self.expect_usize(entry)?;
+        u16::try_from(value).map_err(|_| {
+            self.error_at(
+                entry.line,
+                &format!("value for '{}' is too large", entry.key),
+            )
+        })
+    }
+
+    fn expect_string<'a>(&self, entry: &'a ConfigEntry) -> Result<&'a str, String> {
+        match &entry.value {
+            ConfigValue::String(value) => Ok(value),
+            _ => Err(self.error_at(
+                entry.line,
+                &format!("expected a string for '{}'", entry.key),
+            )),
+        }
+    }
+
+    fn expect_string_array<'a>(&self, entry: &'a ConfigEntry) -> Result<&'a [String], String> {
+        match &entry.value {
+            ConfigValue::StringArray(values) => Ok(values),
+            _ => Err(self.error_at(
+                entry.line,
+                &format!("expected an array of strings for '{}'", entry.key),
+            )),
+        }
+    }
+
+    fn expect_rule_name(&self, entry: &ConfigEntry, rule_name: &str) -> Result<(), String> {
+        if get_all_rule_names().contains(&rule_name) {
+            return Ok(());
+        }
+        Err(self.error_at(entry.line, &format!("unknown linter rule '{}'", rule_name)))
+    }
+
+    fn error_at(&self, line: usize, message: &str) -> String {
+        format!("{}:{}: {}", self.path.display(), line, message)
+    }
+}
+
+/// Removes a trailing comment from a line, ignoring comment characters inside
+/// strings.
+fn strip_comment(line: &str, comment_character: char) -> &str {
+    let mut open_quote: Option<char> = None;
+    let mut is_escaped = false;
+    for (character_index, character) in line.char_indices() {
+        if let Some(quote) = open_quote {
+            if is_escaped {
+                is_escaped = false;
+            } else if character == '\\' && quote == '"' {
+                is_escaped = true;
+            } else if character == quote {
+                open_quote = None;
+            }
+        } else if character == comment_character {
+            return &line[..character_index];
+        } else if character == '"' || character == '\'' {
+            open_quote = Some(character);
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Result<ConfigValue, String> {
+    if text == "true" {
+        return Ok(ConfigValue::Bool(true));
+    }
+    if text == "false" {
+        return Ok(ConfigValue::Bool(false));
+    }
+    if text.starts_with('"') || text.starts_with('\'') {
+        let (value, length) = parse_quoted_string(text)?;
+        if length != text.len() {
+            return Err(format!("unexpected characters after string: {}", text));
+        }
+        return Ok(ConfigValue::String(value));
+    }
+    // Godot writes typed arrays as PackedStringArray("a", "b").
+    if let Some(items_text) = text
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .or_else(|| {
+            text.strip_prefix("PackedStringArray(")
+                .and_then(|rest| rest.strip_suffix(')'))
+        })
+    {
+        return parse_string_array(items_text).map(ConfigValue::StringArray);
+    }
+    match text.parse::<i64>() {
+        Ok(value) => Ok(ConfigValue::Integer(value)),
+        Err(_) => Err(format!("invalid value: {}", text)),
+    }
+}
+
+/// Parses comma-separated quoted strings. A trailing comma is allowed.
+fn parse_string_array(text: &str) -> Result<Vec<String>, String> {
+    let mut items = Vec::new();
+    let mut remaining = text.trim_start();
+    while !remaining.is_empty() {
+        let (item, length) = parse_quoted_string(remaining)?;
+        items.push(item);
+        remaining = remaining[length..].trim_start();
+        if let Some(after_comma) = remaining.strip_prefix(',') {
+            remaining = after_comma.trim_start();
+        } else if !remaining.is_empty() {
+            return Err(format!(
+                "expected a comma between array items, got: {}",
+                remaining
+            ));
+        }
+    }
+    Ok(items)
+}
+
+/// Parses the string at the start of `text`, delimited by double quotes with
+/// backslash escapes, or by single quotes without escapes like TOML literal
+/// strings. Returns the string and the length of its source text in bytes.
+fn parse_quoted_string(text: &str) -> Result<(String, usize), String> {
+    let mut characters = text.char_indices();
+    let quote = match characters.next() {
+        Some((_, character)) if character == '"' || character == '\'' => character,
+        _ => return Err(format!("expected a quoted string, got: {}", text)),
+    };
+
+    let mut value = String::new();
+    while let Some((character_index, character)) = characters.next() {
+        if character == quote {
+            return Ok((value, character_index + 1));
+        }
+        if character != '\\' || quote == '\'' {
+            value.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some((_, 'n')) => value.push('\n'),
+            Some((_, 't')) => value.push('\t'),
+            Some((_, escaped_character)) => value.push(escaped_character),
+            None => break,
+        }
+    }
+    Err(format!("unterminated string: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use crate::reorder::DeclarationKind;
+
+    #[test]
+    fn parses_toml_sections_and_applies_settings() {
+        let text = r#"
+# Shared settings for the team
+[formatter]
+use_spaces = true
+indent_size = 2
+quote_style = "double" # Strings use double quotes
+line_ending = "crlf"
+
+[formatter.reorder]
+category_order = ["signals", "onready_variables", "export_variables"]
+sort_mode = "alphabetical"
+virtual_method_order = ["_ready", "_notification"]
+region_mode = "in-place"
+
+[linter]
+max_line_length = 120
+disabled_rules = [
+    "private-access",
+    "max-line-length",
+]
+
+[linter.rules]
+unused-argument = "error"
+no-else-return = 'off'
+comparison-with-itself = "hint"
+
+[linter.naming.signal-name]
+pattern = '^[a-z][a-zA-Z0-9]*$'
+allowed_names = ["Legacy_Signal"]
+"#;
+        let project_config =
+            ProjectConfig::parse_toml(text, Path::new("gdscript-formatter.toml")).unwrap();
+
+        let mut formatter_config = FormatterConfiguration::default();
+        project_config
+            .apply_to_formatter_config(&mut formatter_config)
+            .unwrap();
+        assert!(formatter_config.printer.use_spaces);
+        assert_eq!(formatter_config.printer.indent_size, 2);
+        assert_eq!(formatter_config.quote_style, QuoteStyle::Double);
+        assert_eq!(formatter_config.printer.line_ending, LineEnding::CrLf);
+        assert_eq!(
+            formatter_config.reorder.category_order,
+            vec![
+                DeclarationKind::Signal,
+                DeclarationKind::OnReadyVariable,
+                DeclarationKind::ExportVariable,
+            ]
+        );
+        assert_eq!(
+            formatter_config.reorder.sort_mode,
+            ReorderSortMode::Alphabetical
+        );
+        assert_eq!(
+            formatter_config.reorder.virtual_method_order,
+            vec!["_ready", "_notification"]
+        );
+        assert_eq!(
+            formatter_config.reorder.region_mode,
+            ReorderRegionMode::InPlace
+        );
+
+        let mut linter_config = LinterConfig::default();
+        project_config
+            .apply_to_linter_config(&mut linter_config)
+            .unwrap();
+        assert_eq!(linter_config.max_line_length, 120);
+        assert_eq!(linter_config.reorder, formatter_config.reorder);
+        assert!(linter_config.disabled_rules.contains("private-access"));
+        assert!(linter_config.disabled_rules.contains("max-line-length"));
+        assert!(linter_config.disabled_rules.contains("no-else-return"));
+        assert_eq!(
+            linter_config.rule_severities.get("unused-argument"),
+            Some(&LintSeverity::Error)
+        );
+        assert_eq!(
+            linter_config.rule_severities.get("comparison-with-itself"),
+            Some(&LintSeverity::Hint)
+        );
+        let signal_naming = linter_config.naming_convention("signal-name");
+        assert!(signal_naming.is_valid("healthChanged", |_| false));
+        assert!(signal_naming.is_valid("Legacy_Signal", |_| false));
+        assert!(!signal_naming.is_valid("health_changed", |_| true));
+    }
+
+    #[test]
+    fn parses_project_godot_section() {
+        let text = r#"; Engine configuration file.
+config_version=5
+
+[application]
+
+config/name="Demo"
+
+[gdscript_formatter]
+
+formatter/indent_size=8
+linter/disabled_rules=PackedStringArray("class-name")
+"#;
+        let project_config = ProjectConfig::parse_project_godot(text, Path::new("project.godot"))
+            .unwrap()
+            .unwrap();
+
+        let mut formatter_config = FormatterConfiguration::default();
+        project_config
+            .apply_to_formatter_config(&mut formatter_config)
+            .unwrap();
+        assert_eq!(formatter_config.printer.indent_size, 8);
+
+        let mut linter_config = LinterConfig::default();
+        project_config
+            .apply_to_linter_config(&mut linter_config)
+            .unwrap();
+        assert!(linter_config.disabled_rules.contains("class-name"));
+
+        let without_section = "config_version=5\n\n[application]\n\nconfig/name=\"Demo\"\n";
+        assert!(
+            ProjectConfig::parse_project_godot(without_section, Path::new("project.godot"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn cache_reads_each_configuration_file_once() {
+        let directory = std::env::temp_dir().join(format!(
+            "gdscript-formatter-project-config-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(directory.join("nested")).unwrap();
+        let config_path = directory.join(PROJECT_CONFIG_FILE_NAME);
+        fs::write(&config_path, "[formatter]\nindent_size = 2\n").unwrap();
+
+        let mut cache = ProjectConfigCache::default();
+        let mut first_config = FormatterConfiguration::default();
+        cache
+            .apply_to_formatter_config(&mut first_config, &directory.join("a.gd"))
+            .unwrap();
+        // The cache doesn't see changes made during the run.
+        fs::write(&config_path, "[formatter]\nindent_size = 8\n").unwrap();
+        let mut second_config = FormatterConfiguration::default();
+        cache
+            .apply_to_formatter_config(&mut second_config, &directory.join("nested/b.gd"))
+            .unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(first_config.printer.indent_size, 2);
+        assert_eq!(second_config.printer.indent_size, 2);
+    }
+
+    #[test]
+    fn reports_unknown_keys_and_invalid_values() {
+        let error = ProjectConfig::parse_toml(
+            "[formatter]\nindent_sise = 2\n",
+            Path::new("gdscript-formatter.toml"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "gdscript-formatter.toml:2: unknown key 'formatter/indent_sise'"
+        );
+
+        let error = ProjectConfig::parse_toml(
+            "[formatter]\nuse_spaces = \"yes\"\n",
+            Path::new("gdscript-formatter.toml"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "gdscript-formatter.toml:2: expected true or false for 'formatter/use_spaces'"
+        );
+
+        let error = ProjectConfig::parse_toml(
+            "[linter.rules]\nunknown-rule = \"off\"\n",
+            Path::new("gdscript-formatter.toml"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "gdscript-formatter.toml:2: unknown linter rule 'unknown-rule'"
+        );
+
+        let error = ProjectConfig::parse_toml(
+            "[formatter.reorder]\ncategory_order = [\"methods\", \"methods\"]\n",
+            Path::new("gdscript-formatter.toml"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "gdscript-formatter.toml:2: category 'methods' is listed more than once"
+        );
+
+        let error = ProjectConfig::parse_toml(
+            "[formatter]\nmax_line_length = 0\n",
+            Path::new("gdscript-formatter.toml"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "gdscript-formatter.toml:2: expected a positive integer for 'formatter/max_line_length'"
+        );
+
+        let error = ProjectConfig::parse_toml(
+            "[formatter]\nmaximum_blank_lines = -1\n",
+            Path::new("gdscript-formatter.toml"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "gdscript-formatter.toml:2: expected a non-negative integer for 'formatter/maximum_blank_lines'"
+        );
+    }
+}
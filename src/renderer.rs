@@ -6,13 +6,18 @@
 //! on one line. If a group's contents is longer than the desired maximum line
 //! width, the renderer breaks it across multiple lines instead.
 //!
-//! Tabs count as `indent_size` columns for width measurement (1 tab = 4 spaces
-//! by default). Blank lines are always merged or limited to a maximum (2 blank
-//! lines by default) and the output always ends with a trailing newline.
+//! Tabs advance to the next multiple of `indent_size` columns for width
+//! measurement (4 columns by default), and other characters count by their
+//! display width (see the `display_width` module). Blank lines are always
+//! merged or limited to a maximum (2 blank lines by default) and the output
+//! always ends with a trailing newline.
 //!
 //! Wadler's paper for reference:
 //! https://homepages.inf.ed.ac.uk/wadler/papers/prettier/prettier.pdf
 
+use crate::display_width;
+use crate::parser::BYTE_ORDER_MARK;
+
 /// A byte range in the original source text. Both fields are byte offsets and
 /// end_byte is exclusive.
 pub struct RangeSourceBytes {
@@ -20,6 +25,18 @@ pub struct RangeSourceBytes {
     pub end_byte: usize,
 }
 
+/// Where the text of an `UnformattedSource` element ended up in the output.
+/// Safe mode uses it to check that code kept as-is comes out unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnformattedSourceOutput {
+    pub source_start: usize,
+    pub source_end: usize,
+    /// Byte offset of the element's text in the output with LF line endings
+    /// and no byte order mark, like the output [crate::parser::normalize_source]
+    /// returns.
+    pub output_start: usize,
+}
+
 /// A range of indices to use on a RenderElement list.
 /// `end` is exclusive.
 pub struct RangeRenderElement {
@@ -60,8 +77,12 @@ pub enum RenderElement {
     TextStatic(&'static str),
     /// Text produced or edited by the formatter rather than copied from the
     /// source. Used if the user set the option to change quote style, to edit
-    /// the quotes of input strings.
-    TextProducedByFormatter(String),
+    /// the quotes of input strings. `range` is the source range the text
+    /// replaces.
+    TextProducedByFormatter {
+        text: String,
+        range: RangeSourceBytes,
+    },
     /// Represents a single space character.
     Space,
     /// Represents an optional line return that may be output at render time (if
@@ -127,6 +148,29 @@ pub enum RenderElement {
     },
 }
 
+/// The line break the renderer writes at the end of each line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Use the line ending of the input file. The renderer itself treats this
+    /// like LF, the formatter resolves it before rendering.
+    Auto,
+    /// Unix line endings, `\n`.
+    Lf,
+    /// Windows line endings, `\r\n`.
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn from_name(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(Self::Auto),
+            "lf" => Some(Self::Lf),
+            "crlf" => Some(Self::CrLf),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PrinterConfiguration {
     /// The maximum line length for the formatter (default: 100).
@@ -149,6 +193,12 @@ pub struct PrinterConfiguration {
     /// it does not have visual indent guides. This will keep tabs and make it
     /// easier to see the indentation structure of the code.
     pub indent_blank_lines: bool,
+    /// Line endings of the output (default: auto, which keeps the line ending
+    /// of the input file).
+    pub line_ending: LineEnding,
+    /// Start the output with a UTF-8 byte order mark (off by default). The
+    /// formatter turns this on for input files that start with one.
+    pub byte_order_mark: bool,
 }
 
 impl Default for PrinterConfiguration {
@@ -162,6 +212,8 @@ impl Default for PrinterConfiguration {
             insert_final_newline: true,
             trim_trailing_whitespace: true,
             indent_blank_lines: false,
+            line_ending: LineEnding::Auto,
+            byte_order_mark: false,
         }
     }
 }
@@ -171,8 +223,42 @@ pub fn render(
     source: &str,
     config: &PrinterConfiguration,
     output: &mut String,
+) {
+    render_tracking_source_offsets(
+        render_elements,
+        source,
+        config,
+        &[],
+        output,
+        &mut Vec::new(),
+        &mut Vec::new(),
+    );
+}
+
+/// Renders like [render] and also maps source byte offsets to output byte
+/// offsets. `tracked_source_offsets` must be sorted in ascending order.
+///
+/// For each tracked offset, `tracked_output_offsets` receives the end of the
+/// output content that precedes the first source text starting at or after
+/// that offset, before any line break is inserted. If no source text starts
+/// after a tracked offset, it maps to the end of the output content. Range
+/// formatting uses this to find where a statement's formatted code starts and
+/// ends in the output.
+///
+/// `unformatted_sources` receives where each `UnformattedSource` element was
+/// written in the output, in render order.
+pub fn render_tracking_source_offsets(
+    render_elements: &[RenderElement],
+    source: &str,
+    config: &PrinterConfiguration,
+    tracked_source_offsets: &[usize],
+    output: &mut String,
+    tracked_output_offsets: &mut Vec<usize>,
+    unformatted_sources: &mut Vec<UnformattedSourceOutput>,
 ) {
     output.clear();
+    tracked_output_offsets.clear();
+    unformatted_sources.clear();
     output.reserve(source.len());
     let spaces;
     let indent_unit: &str = if config.use_spaces {
@@ -191,9 +277,60 @@ pub fn render(
         pending_newlines: 0u16,
         indent_level: 0,
         balanced_break_plans: Vec::new(),
+        tracked_source_offsets,
+        tracked_output_offsets: std::mem::take(tracked_output_offsets),
+        unformatted_sources: std::mem::take(unformatted_sources),
     };
     printer.render_range(0, render_elements.len(), Mode::Flat);
+    printer.record_tracked_source_offsets(usize::MAX);
+    *tracked_output_offsets = std::mem::take(&mut printer.tracked_output_offsets);
+    *unformatted_sources = std::mem::take(&mut printer.unformatted_sources);
     *output = printer.add_to_output_finish();
+
+    // The printer only writes LF line breaks. We convert them at the end, and
+    // move the tracked offsets by the bytes inserted before them.
+    let uses_crlf = config.line_ending == LineEnding::CrLf;
+    if uses_crlf || config.byte_order_mark {
+        for tracked_offset in tracked_output_offsets.iter_mut() {
+            if uses_crlf {
+                *tracked_offset += output.as_bytes()[..*tracked_offset]
+                    .iter()
+                    .filter(|byte| **byte == b'\n')
+                    .count();
+            }
+            if config.byte_order_mark && !output.starts_with(BYTE_ORDER_MARK) {
+                *tracked_offset += BYTE_ORDER_MARK.len_utf8();
+            }
+        }
+        convert_line_endings_and_byte_order_mark(output, config);
+    }
+}
+
+/// Converts the LF line breaks of `text` to the line ending of `config` and
+/// adds the byte order mark if `config` asks for it. Line breaks that already
+/// are CRLF stay as they are.
+pub fn convert_line_endings_and_byte_order_mark(text: &mut String, config: &PrinterConfiguration) {
+    let uses_crlf = config.line_ending == LineEnding::CrLf;
+    if !uses_crlf && !config.byte_order_mark {
+        return;
+    }
+    let mut converted = String::with_capacity(text.len() + text.len() / 32 + 3);
+    if config.byte_order_mark && !text.starts_with(BYTE_ORDER_MARK) {
+        converted.push(BYTE_ORDER_MARK);
+    }
+    if uses_crlf {
+        let mut previous_character = '\0';
+        for character in text.chars() {
+            if character == '\n' && previous_character != '\r' {
+                converted.push('\r');
+            }
+            converted.push(character);
+            previous_character = character;
+        }
+    } else {
+        converted.push_str(text);
+    }
+    *text = converted;
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -219,6 +356,9 @@ struct Printer<'a> {
     pending_newlines: u16,
     indent_level: u16,
     balanced_break_plans: Vec<Vec<usize>>,
+    tracked_source_offsets: &'a [usize],
+    tracked_output_offsets: Vec<usize>,
+    unformatted_sources: Vec<UnformattedSourceOutput>,
 }
 
 impl<'a> Printer<'a> {
@@ -227,6 +367,7 @@ impl<'a> Printer<'a> {
         while index < end {
             match &self.render_elements[index] {
                 RenderElement::Text { range } => {
+                    self.record_tracked_source_offsets(range.start_byte);
                     let text = slice(self.source, range);
                     self.add_to_output(text);
                     index += 1;
@@ -235,7 +376,8 @@ impl<'a> Printer<'a> {
                     self.add_to_output(text);
                     index += 1;
                 }
-                RenderElement::TextProducedByFormatter(text) => {
+                RenderElement::TextProducedByFormatter { text, range } => {
+                    self.record_tracked_source_offsets(range.start_byte);
                     self.add_to_output(text);
                     index += 1;
                 }
@@ -336,24 +478,29 @@ impl<'a> Printer<'a> {
                     index += 1;
                 }
                 RenderElement::UnformattedSource { range } => {
+                    self.record_tracked_source_offsets(range.start_byte);
                     let text = slice(self.source, range);
                     // Insert raw text to the output without any formatting
                     // while tracking the current column and pending newlines to
                     // keep track of indentation.
                     self.add_to_output_process_newlines();
-                    for c in text.chars() {
-                        if c == '\n' {
+                    self.unformatted_sources.push(UnformattedSourceOutput {
+                        source_start: range.start_byte,
+                        source_end: range.end_byte,
+                        output_start: self.output.len(),
+                    });
+                    for (line_index, line) in text.split('\n').enumerate() {
+                        if line_index > 0 {
                             self.output.push('\n');
                             self.column = 0;
                             self.pending_newlines = 0;
-                        } else {
-                            self.output.push(c);
-                            if c == '\t' {
-                                self.column += self.config.indent_size;
-                            } else {
-                                self.column += 1;
-                            }
                         }
+                        self.output.push_str(line);
+                        self.column = display_width::advance_column(
+                            self.column,
+                            line,
+                            self.config.indent_size,
+                        );
                     }
                     index += 1;
                 }
@@ -361,6 +508,23 @@ impl<'a> Printer<'a> {
         }
     }
 
+    /// Maps every tracked source offset that the source text starting at
+    /// `start_byte` reaches to the end of the content written so far. Trailing
+    /// whitespace that the next line break will trim is excluded.
+    fn record_tracked_source_offsets(&mut self, start_byte: usize) {
+        while self.tracked_output_offsets.len() < self.tracked_source_offsets.len() {
+            let tracked_offset = self.tracked_source_offsets[self.tracked_output_offsets.len()];
+            if start_byte < tracked_offset {
+                return;
+            }
+            let mut content_end = self.output.len();
+            if self.pending_newlines > 0 && self.config.trim_trailing_whitespace {
+                content_end = self.output.trim_end_matches([' ', '\t']).len();
+            }
+            self.tracked_output_offsets.push(content_end);
+        }
+    }
+
     fn request_newline(&mut self, want: u16) {
         if want > self.pending_newlines {
             self.pending_newlines = want;
@@ -425,7 +589,7 @@ impl<'a> Printer<'a> {
                     }
                     index += 1;
                 }
-                RenderElement::TextProducedByFormatter(text) => {
+                RenderElement::TextProducedByFormatter { text, .. } => {
                     if !self.measure_text(text, column) {
                         return false;
                     }
@@ -525,7 +689,7 @@ impl<'a> Printer<'a> {
                     }
                     index += 1;
                 }
-                RenderElement::TextProducedByFormatter(text) => {
+                RenderElement::TextProducedByFormatter { text, .. } => {
                     if !self.measure_text(text, column) {
                         return false;
                     }
@@ -570,18 +734,28 @@ impl<'a> Printer<'a> {
         true
     }
 
+    /// Adds the display width of `text` to `column`. Returns false if the text
+    /// contains a line break, after measuring the text up to that line break.
     fn measure_text(&self, text: &str, column: &mut usize) -> bool {
-        for c in text.chars() {
-            if c == '\n' {
-                return false;
-            }
-            if c == '\t' {
-                *column = column.saturating_add(self.config.indent_size);
-            } else {
-                *column = column.saturating_add(1);
-            }
-        }
-        true
+        let first_line = match text.find('\n') {
+            Some(newline_position) => &text[..newline_position],
+            None => text,
+        };
+        *column = display_width::advance_column(*column, first_line, self.config.indent_size);
+        first_line.len() == text.len()
+    }
+
+    /// Returns the column after rendering a segment of a balanced group flat
+    /// from `column`. `segment` is a range of render element indices.
+    fn advance_past_segment(&self, segment: (usize, usize), column: usize) -> usize {
+        let mut column = column;
+        self.does_range_fit_flat_layout(
+            segment.0,
+            segment.1,
+            &mut column,
+            ForceBreakMode::AnyDepth,
+        );
+        column
     }
 
     fn plan_balanced_breaks(&self, start: usize, end: usize) -> Vec<usize> {
@@ -611,7 +785,12 @@ impl<'a> Printer<'a> {
             return Vec::new();
         }
 
+        // Tabs advance to tab stops, so a segment's width depends on the
+        // column it starts at. These widths, measured from column 0, only
+        // estimate how to balance lines. We measure segments again from
+        // their column when packing them into lines.
         let segment_count = boundaries.len() + 1;
+        let mut segments = Vec::with_capacity(segment_count);
         let mut widths = Vec::with_capacity(segment_count);
         let mut multiline = Vec::with_capacity(segment_count);
         let mut segment_index = 0;
@@ -633,6 +812,7 @@ impl<'a> Printer<'a> {
                 &mut width,
                 ForceBreakMode::AnyDepth,
             );
+            segments.push((segment_start, segment_end));
             widths.push(width);
             multiline.push(!fits_one_line);
             segment_index += 1;
@@ -666,12 +846,14 @@ impl<'a> Printer<'a> {
                     previous_was_multiline = false;
                 }
                 let separator_width = usize::from(line_has_segment);
-                let next_column = column
-                    .saturating_add(separator_width)
-                    .saturating_add(widths[segment_index]);
+                let next_column = self.advance_past_segment(
+                    segments[segment_index],
+                    column.saturating_add(separator_width),
+                );
                 if line_has_segment && next_column > self.config.max_line_length {
                     line_count += 1;
-                    column = continuation_column.saturating_add(widths[segment_index]);
+                    column =
+                        self.advance_past_segment(segments[segment_index], continuation_column);
                 } else {
                     column = next_column;
                 }
@@ -707,19 +889,23 @@ impl<'a> Printer<'a> {
                 .min(available_width);
             let last_allowed_segment = segment_count - remaining_lines;
             let mut next_segment = first_segment + 1;
-            let mut line_width = widths[first_segment];
+            let mut line_end_column =
+                self.advance_past_segment(segments[first_segment], line_start_column);
 
             if !multiline[first_segment] {
                 while next_segment <= last_allowed_segment && !multiline[next_segment] {
-                    let candidate_width = line_width
-                        .saturating_add(1)
-                        .saturating_add(widths[next_segment]);
+                    let line_width = line_end_column - line_start_column;
+                    let candidate_end_column = self.advance_past_segment(
+                        segments[next_segment],
+                        line_end_column.saturating_add(1),
+                    );
+                    let candidate_width = candidate_end_column - line_start_column;
                     let worsens_balance =
                         candidate_width.abs_diff(target_width) > line_width.abs_diff(target_width);
                     if candidate_width > available_width || worsens_balance {
                         break;
                     }
-                    line_width = candidate_width;
+                    line_end_column = candidate_end_column;
                     next_segment += 1;
                 }
             }
@@ -736,18 +922,16 @@ impl<'a> Printer<'a> {
     /// We support optional trimming trailing whitespace and reconstructing
     /// indentation on empty lines between statements.
     fn add_to_output(&mut self, text: &str) {
-        for c in text.chars() {
-            if c == '\n' {
+        for (line_index, line) in text.split('\n').enumerate() {
+            if line_index > 0 {
                 self.pending_newlines += 1;
-            } else {
-                self.add_to_output_process_newlines();
-                self.output.push(c);
-                if c == '\t' {
-                    self.column += self.config.indent_size;
-                } else {
-                    self.column += 1;
-                }
             }
+            if line.is_empty() {
+                continue;
+            }
+            self.add_to_output_process_newlines();
+            self.output.push_str(line);
+            self.column = display_width::advance_column(self.column, line, self.config.indent_size);
         }
     }
 
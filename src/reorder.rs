@@ -1,12 +1,25 @@
 //! Compute a `ReorderPlan` that tells the formatter builder which order to
-//! visit top-level declarations in. The plan groups declarations by category
-//! (signals, enums, consts, vars, funcs, inner classes) and sorts within each
-//! category by name, privacy, and method type where applicable.
+//! visit the declarations of a script or an inner class body in. The plan
+//! groups declarations by category (signals, enums, consts, vars, funcs, inner
+//! classes) and sorts within each category by name, privacy, and method type
+//! where applicable.
 //!
 //! Comments and annotations that precede a declaration in source order are
 //! bundled with it so they move together when reordered.
+//!
+//! Export grouping annotations like `@export_group` and the export variables
+//! that follow them form blocks that keep their order, so variables stay in
+//! the same inspector group.
+//!
+//! Depending on the configuration, `#region` blocks either let declarations
+//! leave them, or sort as a whole with their declarations reordered inside.
+//!
+//! Code in a `# fmt: off` region must stay as it is, so the children a region
+//! covers form one unit: it moves with the declaration it contains, or stays
+//! in place when it contains several declarations.
 
 use crate::node_kind::GDScriptNodeKind;
+use crate::parser::RegionWithDisabledFormatting;
 use tree_sitter::Node;
 
 // Public types
@@ -14,6 +27,37 @@ use tree_sitter::Node;
 #[derive(Debug, Clone)]
 pub struct ReorderPlan<'a> {
     pub items: Vec<ReorderItem<'a>>,
+    /// Runs of children covered by `# fmt: off` regions. The plan refers to
+    /// each unit by the index of its first child, so the formatter outputs
+    /// all the unit's children wherever the plan places that index.
+    pub disabled_region_units: Vec<DisabledRegionUnit>,
+}
+
+impl ReorderPlan<'_> {
+    /// Returns the disabled region unit that starts at `child_index`, if any.
+    pub fn disabled_region_unit_at(&self, child_index: usize) -> Option<&DisabledRegionUnit> {
+        self.disabled_region_units
+            .iter()
+            .find(|unit| unit.first_child_index == child_index)
+    }
+
+    /// Returns the end byte of the child at `child_index` of `parent`, or of
+    /// the last child of the disabled region unit that starts there.
+    pub fn child_end_byte(&self, parent: Node, child_index: usize) -> usize {
+        let last_child_index =
+            get_last_child_index_of_unit(&self.disabled_region_units, child_index);
+        parent
+            .child(last_child_index as u32)
+            .map_or(0, |child| child.end_byte())
+    }
+}
+
+/// Consecutive children of the reordered node that one or more disabled
+/// regions cover, even partially.
+#[derive(Debug, Clone, Copy)]
+pub struct DisabledRegionUnit {
+    pub first_child_index: usize,
+    pub last_child_index: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +89,17 @@ pub struct ReorderItem<'a> {
     /// When true, the class_name_statement node contains an inline extends
     /// child that should be skipped when building (emitted as separate item).
     pub split_extends: bool,
+    /// When true, this item is a disabled region unit with several
+    /// declarations. It stays at its source position and declarations only
+    /// get sorted between pinned items.
+    pub is_pinned: bool,
+    /// For export variables and export grouping annotations, the number of
+    /// grouping annotations (`@export_category`, `@export_group`, and
+    /// `@export_subgroup`) up to this item in source order. A grouping
+    /// annotation only applies to the export variables after it, so each
+    /// annotation stays in front of its variables, and groups keep their
+    /// source order.
+    pub export_group_index: usize,
 }
 
 /// The broad category of a top-level code declaration.
@@ -70,10 +125,209 @@ pub enum DeclarationKind {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MethodType {
-    StaticInit,         // _static_init()
-    StaticFunction,     // static func
-    BuiltinVirtual(u8), // _ready, _process, etc. (priority from Godot lifecycle)
-    Custom,             // all other user methods
+    StaticInit,            // _static_init()
+    StaticFunction,        // static func
+    BuiltinVirtual(usize), // _ready, _process, etc. (index in the virtual method order)
+    Custom,                // all other user methods
+}
+
+impl DeclarationKind {
+    /// Returns the declaration kind for a category name of the reorder
+    /// configuration. The class header kinds (annotations, class_name,
+    /// extends, and the class docstring) always come first, so they have no
+    /// category name.
+    pub fn from_category_name(name: &str) -> Option<Self> {
+        match name {
+            "signals" => Some(Self::Signal),
+            "enums" => Some(Self::Enum),
+            "constants" => Some(Self::Constant),
+            "static_variables" => Some(Self::StaticVariable),
+            "export_variables" => Some(Self::ExportVariable),
+            "variables" => Some(Self::RegularVariable),
+            "onready_variables" => Some(Self::OnReadyVariable),
+            "methods" => Some(Self::Method),
+            "inner_classes" => Some(Self::InnerClass),
+            _ => None,
+        }
+    }
+}
+
+/// How to sort declarations within a category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReorderSortMode {
+    /// Keep declarations in source order.
+    Source,
+    /// Public declarations first, then pseudo-private ones (starting with
+    /// `_`), each sorted by name.
+    Alphabetical,
+    /// Public declarations first, then pseudo-private ones, each in source
+    /// order. This follows the GDScript style guide.
+    #[default]
+    PublicFirst,
+}
+
+impl ReorderSortMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "source" => Some(Self::Source),
+            "alphabetical" => Some(Self::Alphabetical),
+            "public-first" => Some(Self::PublicFirst),
+            _ => None,
+        }
+    }
+}
+
+/// How to reorder declarations in `#region` blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReorderRegionMode {
+    /// Region markers move with the declarations next to them, so declarations
+    /// can leave their region.
+    #[default]
+    Attach,
+    /// Each region stays in place, and declarations get reordered inside it.
+    InPlace,
+    /// Declarations get reordered inside each region, then the region moves
+    /// as a whole, sorted by its first declaration.
+    FirstMember,
+}
+
+impl ReorderRegionMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "attach" => Some(Self::Attach),
+            "in-place" => Some(Self::InPlace),
+            "first-member" => Some(Self::FirstMember),
+            _ => None,
+        }
+    }
+}
+
+/// The order of the style guide, used by default.
+pub const DEFAULT_CATEGORY_ORDER: [DeclarationKind; 9] = [
+    DeclarationKind::Signal,
+    DeclarationKind::Enum,
+    DeclarationKind::Constant,
+    DeclarationKind::StaticVariable,
+    DeclarationKind::ExportVariable,
+    DeclarationKind::RegularVariable,
+    DeclarationKind::OnReadyVariable,
+    DeclarationKind::Method,
+    DeclarationKind::InnerClass,
+];
+
+/// Built-in virtual methods in the order of the Godot lifecycle, used by
+/// default. `_unhandled_key_input()` and `_gui_input()` share a rank and keep
+/// their source order.
+pub const DEFAULT_VIRTUAL_METHOD_ORDER: [&str; 29] = [
+    "_init",
+    "_enter_tree",
+    "_ready",
+    "_process",
+    "_physics_process",
+    "_exit_tree",
+    "_input",
+    "_unhandled_input",
+    "_unhandled_key_input|_gui_input",
+    "_draw",
+    "_notification",
+    "_get_configuration_warnings",
+    "_validate_property",
+    "_get_property_list",
+    "_property_can_revert",
+    "_property_get_revert",
+    "_get",
+    "_set",
+    "_to_string",
+    "_accessibility_get_contextual_info",
+    "_can_drop_data",
+    "_drop_data",
+    "_get_accessibility_container_name",
+    "_get_drag_data",
+    "_get_minimum_size",
+    "_get_tooltip",
+    "_has_point",
+    "_make_custom_tooltip",
+    "_structured_text_parser",
+];
+
+/// Controls the order of declarations when reordering code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorderConfiguration {
+    /// The order of declaration categories after the class header. Categories
+    /// missing from the list come after the listed ones, in their default
+    /// order.
+    pub category_order: Vec<DeclarationKind>,
+    pub sort_mode: ReorderSortMode,
+    /// Methods in this list come first among methods, in this order, after
+    /// `_static_init()` and static functions. An entry can list several names
+    /// separated by `|` to give them the same rank.
+    pub virtual_method_order: Vec<String>,
+    pub region_mode: ReorderRegionMode,
+}
+
+impl Default for ReorderConfiguration {
+    fn default() -> Self {
+        Self {
+            category_order: DEFAULT_CATEGORY_ORDER.to_vec(),
+            sort_mode: ReorderSortMode::default(),
+            virtual_method_order: DEFAULT_VIRTUAL_METHOD_ORDER
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            region_mode: ReorderRegionMode::default(),
+        }
+    }
+}
+
+impl ReorderConfiguration {
+    /// Parses a list of category names, like `["signals", "methods"]`, into a
+    /// category order. Returns an error message for unknown or repeated
+    /// names.
+    pub fn parse_category_order(names: &[String]) -> Result<Vec<DeclarationKind>, String> {
+        let mut category_order = Vec::with_capacity(names.len());
+        for name in names {
+            let Some(kind) = DeclarationKind::from_category_name(name) else {
+                return Err(format!(
+                    "unknown category '{}', expected one of signals, enums, constants, static_variables, export_variables, variables, onready_variables, methods, or inner_classes",
+                    name
+                ));
+            };
+            if category_order.contains(&kind) {
+                return Err(format!("category '{}' is listed more than once", name));
+            }
+            category_order.push(kind);
+        }
+        Ok(category_order)
+    }
+
+    /// Returns the sort rank of a declaration kind. The class header always
+    /// comes first and unknown nodes last.
+    fn get_category_rank(&self, kind: DeclarationKind) -> usize {
+        match kind {
+            DeclarationKind::ClassAnnotation
+            | DeclarationKind::ClassName
+            | DeclarationKind::Extends
+            | DeclarationKind::Docstring => kind as usize,
+            DeclarationKind::Unknown => usize::MAX,
+            _ => {
+                let first_category_rank = DeclarationKind::Signal as usize;
+                match self
+                    .category_order
+                    .iter()
+                    .position(|&listed| listed == kind)
+                {
+                    Some(position) => first_category_rank + position,
+                    None => first_category_rank + self.category_order.len() + kind as usize,
+                }
+            }
+        }
+    }
+
+    fn get_virtual_method_priority(&self, method_name: &str) -> Option<usize> {
+        self.virtual_method_order
+            .iter()
+            .position(|entry| entry.split('|').any(|name| name.trim() == method_name))
+    }
 }
 
 /// Slice the source string at a node's byte range.
@@ -103,17 +357,146 @@ fn has_blank_line(content: &str, from: usize, to: usize) -> bool {
     false
 }
 
-/// Builds a `ReorderPlan` for the children of `parent` (typically a `source`
-/// node). `content` is the source string used for name extraction.
-pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan<'a> {
+/// Returns the index of the last child of the disabled region unit starting
+/// at `child_index`, or `child_index` if no unit starts there.
+fn get_last_child_index_of_unit(units: &[DisabledRegionUnit], child_index: usize) -> usize {
+    for unit in units {
+        if unit.first_child_index == child_index {
+            return unit.last_child_index;
+        }
+    }
+    child_index
+}
+
+/// Finds the runs of children of `parent` that disabled regions overlap. A
+/// region inside a single child, like a function body, doesn't affect the
+/// order of `parent`'s children, so we skip it. Overlapping runs merge into
+/// one unit.
+fn find_disabled_region_units(
+    parent: Node,
+    disabled_regions: &[RegionWithDisabledFormatting],
+) -> Vec<DisabledRegionUnit> {
+    let child_count = parent.child_count();
+    let mut units: Vec<DisabledRegionUnit> = Vec::new();
+    for region in disabled_regions {
+        let mut first_child_index = None;
+        let mut last_child_index = 0;
+        for child_index in 0..child_count {
+            let Some(child) = parent.child(child_index as u32) else {
+                continue;
+            };
+            if child.end_byte() <= region.start || child.start_byte() >= region.end {
+                continue;
+            }
+            if first_child_index.is_none() {
+                first_child_index = Some(child_index);
+            }
+            last_child_index = child_index;
+        }
+        let Some(first_child_index) = first_child_index else {
+            continue;
+        };
+        if first_child_index == last_child_index
+            && let Some(child) = parent.child(first_child_index as u32)
+            && child.start_byte() < region.start
+            && child.end_byte() >= region.end
+        {
+            continue;
+        }
+
+        if let Some(previous_unit) = units.last_mut()
+            && first_child_index <= previous_unit.last_child_index
+        {
+            previous_unit.last_child_index = previous_unit.last_child_index.max(last_child_index);
+            continue;
+        }
+        units.push(DisabledRegionUnit {
+            first_child_index,
+            last_child_index,
+        });
+    }
+    units
+}
+
+/// Returns true for `@export_category`, `@export_group`, and
+/// `@export_subgroup` annotations.
+fn is_export_grouping_annotation(annotation_text: &str) -> bool {
+    ["@export_category", "@export_group", "@export_subgroup"]
+        .iter()
+        .any(|name| {
+            annotation_text.strip_prefix(name).is_some_and(|rest| {
+                !rest.starts_with(|character: char| character.is_alphanumeric() || character == '_')
+            })
+        })
+}
+
+/// Counts the export grouping annotations of a standalone annotation or of a
+/// declaration's own annotations, like in `@export_group("Stats") @export var
+/// health := 10`.
+fn count_export_grouping_annotations(node: Node, content: &str) -> usize {
+    match GDScriptNodeKind::get_kind_from_ast_node(node) {
+        GDScriptNodeKind::Annotation => {
+            usize::from(is_export_grouping_annotation(get_node_text(node, content)))
+        }
+        GDScriptNodeKind::Annotations => {
+            let mut count = 0;
+            for child_index in 0..node.child_count() {
+                if let Some(annotation) = node.child(child_index as u32) {
+                    count += count_export_grouping_annotations(annotation, content);
+                }
+            }
+            count
+        }
+        _ => {
+            let mut count = 0;
+            for child_index in 0..node.child_count() {
+                if let Some(child) = node.child(child_index as u32)
+                    && GDScriptNodeKind::get_kind_from_ast_node(child)
+                        == GDScriptNodeKind::Annotations
+                {
+                    count += count_export_grouping_annotations(child, content);
+                }
+            }
+            count
+        }
+    }
+}
+
+/// Returns true if the child becomes a reorder item of its own, as opposed to
+/// comments, annotations, and region markers that move with a declaration.
+fn is_declaration_child(child: Node, content: &str) -> bool {
+    match GDScriptNodeKind::get_kind_from_ast_node(child) {
+        GDScriptNodeKind::Comment
+        | GDScriptNodeKind::RegionStart
+        | GDScriptNodeKind::RegionEnd
+        | GDScriptNodeKind::SemiColon => false,
+        GDScriptNodeKind::Annotation => {
+            let annotation_name = get_node_text(child, content);
+            annotation_name.starts_with("@tool") || annotation_name.starts_with("@icon")
+        }
+        _ => true,
+    }
+}
+
+/// Builds a `ReorderPlan` for the children of `parent` (a `source` node or an
+/// inner class body). `content` is the source string used for name extraction.
+/// `disabled_regions` are the `# fmt: off` regions of the whole file.
+pub fn build_reorder_plan<'a>(
+    parent: Node<'a>,
+    content: &'a str,
+    disabled_regions: &[RegionWithDisabledFormatting],
+    config: &ReorderConfiguration,
+) -> ReorderPlan<'a> {
     let child_count = parent.child_count();
     let mut items = Vec::with_capacity(child_count);
+    let disabled_region_units = find_disabled_region_units(parent, disabled_regions);
 
     // Pass 1: classify each child.
     // These source children move with a nearby declaration. They include
     // comments, annotations, and region markers.
     let mut is_child_attached_to_declaration = vec![false; child_count];
     let mut is_region_end = vec![false; child_count];
+    let mut export_group_count = 0;
 
     let mut child_index = 0;
     while child_index < child_count {
@@ -121,6 +504,64 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
             child_index += 1;
             continue;
         };
+        if let Some(unit) = disabled_region_units
+            .iter()
+            .find(|unit| unit.first_child_index == child_index)
+        {
+            let mut declarations = Vec::new();
+            for unit_child_index in unit.first_child_index..=unit.last_child_index {
+                let Some(unit_child) = parent.child(unit_child_index as u32) else {
+                    continue;
+                };
+                export_group_count += count_export_grouping_annotations(unit_child, content);
+                if is_declaration_child(unit_child, content) {
+                    declarations.push(unit_child);
+                }
+            }
+            match declarations.as_slice() {
+                // Without a declaration, the unit moves with the next
+                // declaration like a comment.
+                [] => is_child_attached_to_declaration[child_index] = true,
+                [declaration] => {
+                    let declaration_classification = classify_child(*declaration, content, config);
+                    items.push(ReorderItem {
+                        child_index,
+                        sub_child: None,
+                        child_indices_attached_before_declaration: Vec::new(),
+                        child_indices_attached_after_declaration: Vec::new(),
+                        has_blank_line_before: false,
+                        classification: declaration_classification.classification,
+                        name: declaration_classification.name,
+                        is_private: declaration_classification.name.starts_with('_'),
+                        method_type: declaration_classification.method_type,
+                        split_extends: false,
+                        is_pinned: false,
+                        export_group_index: export_group_count,
+                    });
+                }
+                [.., last_declaration] => {
+                    // We use the kind of the last declaration for the blank
+                    // lines after the unit.
+                    items.push(ReorderItem {
+                        child_index,
+                        sub_child: None,
+                        child_indices_attached_before_declaration: Vec::new(),
+                        child_indices_attached_after_declaration: Vec::new(),
+                        has_blank_line_before: false,
+                        classification: classify_child(*last_declaration, content, config)
+                            .classification,
+                        name: "",
+                        is_private: false,
+                        method_type: None,
+                        split_extends: false,
+                        is_pinned: true,
+                        export_group_index: 0,
+                    });
+                }
+            }
+            child_index = unit.last_child_index + 1;
+            continue;
+        }
         let kind = GDScriptNodeKind::get_kind_from_ast_node(child);
         if kind == GDScriptNodeKind::Comment || kind == GDScriptNodeKind::RegionStart {
             is_child_attached_to_declaration[child_index] = true;
@@ -140,6 +581,27 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
                     is_private: false,
                     method_type: None,
                     split_extends: false,
+                    is_pinned: false,
+                    export_group_index: 0,
+                });
+            } else if is_export_grouping_annotation(annotation_name) {
+                // The annotation leads its block of export variables instead
+                // of moving with the next declaration, which may not be an
+                // export variable.
+                export_group_count += 1;
+                items.push(ReorderItem {
+                    child_index,
+                    sub_child: None,
+                    child_indices_attached_before_declaration: Vec::new(),
+                    child_indices_attached_after_declaration: Vec::new(),
+                    has_blank_line_before: false,
+                    classification: DeclarationKind::ExportVariable,
+                    name: "",
+                    is_private: false,
+                    method_type: None,
+                    split_extends: false,
+                    is_pinned: false,
+                    export_group_index: export_group_count,
                 });
             } else {
                 is_child_attached_to_declaration[child_index] = true;
@@ -150,8 +612,9 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
         } else if kind == GDScriptNodeKind::SemiColon {
             // skip; handled by builder spacing
         } else {
-            let child_classification = classify_child(child, content);
+            let child_classification = classify_child(child, content, config);
             let is_private = child_classification.name.starts_with('_');
+            export_group_count += count_export_grouping_annotations(child, content);
             items.push(ReorderItem {
                 child_index,
                 sub_child: None,
@@ -163,6 +626,8 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
                 is_private,
                 method_type: child_classification.method_type,
                 split_extends: child_classification.split_extends,
+                is_pinned: false,
+                export_group_index: export_group_count,
             });
 
             if child_classification.split_extends {
@@ -178,6 +643,8 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
                         is_private: false,
                         method_type: None,
                         split_extends: false,
+                        is_pinned: false,
+                        export_group_index: 0,
                     });
                 }
             }
@@ -266,6 +733,8 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
             is_private: false,
             method_type: None,
             split_extends: false,
+            is_pinned: false,
+            export_group_index: 0,
         });
     }
 
@@ -316,9 +785,15 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
             if let (Some(previous_declaration), Some(first_item_child)) =
                 (previous_declaration, first_item_child)
             {
+                let previous_declaration_end_byte = parent
+                    .child(get_last_child_index_of_unit(
+                        &disabled_region_units,
+                        previous_declaration_child_index,
+                    ) as u32)
+                    .map_or(previous_declaration.end_byte(), |child| child.end_byte());
                 items[declaration_index].has_blank_line_before = has_blank_line(
                     content,
-                    previous_declaration.end_byte(),
+                    previous_declaration_end_byte,
                     first_item_child.start_byte(),
                 );
             }
@@ -327,10 +802,12 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
         // Attach region ends and an inline comment after the declaration to
         // that declaration.
         let mut indices_of_children_attached_after_declaration = Vec::new();
-        let mut next_child_index = declaration_child_index + 1;
+        let last_declaration_child_index =
+            get_last_child_index_of_unit(&disabled_region_units, declaration_child_index);
+        let mut next_child_index = last_declaration_child_index + 1;
         if let Some(next_declaration_child_index) = next_declaration_child_index {
             let current_declaration = parent
-                .child(declaration_child_index as u32)
+                .child(last_declaration_child_index as u32)
                 .expect("declaration child index came from this parent");
             while next_child_index < next_declaration_child_index {
                 if is_region_end[next_child_index] {
@@ -370,10 +847,217 @@ pub fn build_reorder_plan<'a>(parent: Node<'a>, content: &'a str) -> ReorderPlan
         declaration_index += 1;
     }
 
-    // Pass 3: sort.
-    items.sort_by(compare_reorder_items);
+    // Pass 3: sort. We group the items of `#region` blocks first so each block
+    // sorts as a whole.
+    items.sort_by_key(|item| item.child_index);
+    let region_blocks = if config.region_mode == ReorderRegionMode::Attach {
+        Vec::new()
+    } else {
+        find_region_blocks(parent, &disabled_region_units)
+    };
+    let mut item_iterator = items.into_iter().peekable();
+    let mut region_block_index = 0;
+    let mut entries = build_reorder_entries(
+        &mut item_iterator,
+        &region_blocks,
+        &mut region_block_index,
+        usize::MAX,
+    );
+    sort_reorder_entries(&mut entries, config);
+    let mut items = Vec::with_capacity(child_count);
+    flatten_reorder_entries(entries, &mut items);
+
+    ReorderPlan {
+        items,
+        disabled_region_units,
+    }
+}
+
+/// The child indices of a matching pair of `#region` and `#endregion` markers.
+#[derive(Debug, Clone, Copy)]
+struct RegionBlock {
+    start_child_index: usize,
+    end_child_index: usize,
+}
+
+/// A reorder item, or a `#region` block of entries that sorts as a whole.
+enum ReorderEntry<'a> {
+    Item(ReorderItem<'a>),
+    Region {
+        block: RegionBlock,
+        entries: Vec<ReorderEntry<'a>>,
+    },
+}
+
+impl<'a> ReorderEntry<'a> {
+    /// Returns the item that decides where the entry goes when sorting. For a
+    /// region, it's the first item once sorted.
+    fn get_first_item(&self) -> &ReorderItem<'a> {
+        match self {
+            ReorderEntry::Item(item) => item,
+            ReorderEntry::Region { entries, .. } => entries[0].get_first_item(),
+        }
+    }
+
+    fn is_pinned(&self, region_mode: ReorderRegionMode) -> bool {
+        match self {
+            ReorderEntry::Item(item) => item.is_pinned,
+            ReorderEntry::Region { .. } => region_mode == ReorderRegionMode::InPlace,
+        }
+    }
+}
+
+/// Finds the matching `#region` and `#endregion` markers among the children
+/// of `parent`, sorted by start. We skip markers without a match and markers
+/// in disabled region units, as these units don't move.
+fn find_region_blocks(
+    parent: Node,
+    disabled_region_units: &[DisabledRegionUnit],
+) -> Vec<RegionBlock> {
+    let mut region_blocks = Vec::new();
+    let mut open_region_start_indices = Vec::new();
+    for child_index in 0..parent.child_count() {
+        let is_in_disabled_region_unit = disabled_region_units.iter().any(|unit| {
+            unit.first_child_index <= child_index && child_index <= unit.last_child_index
+        });
+        if is_in_disabled_region_unit {
+            continue;
+        }
+        let Some(child) = parent.child(child_index as u32) else {
+            continue;
+        };
+        match GDScriptNodeKind::get_kind_from_ast_node(child) {
+            GDScriptNodeKind::RegionStart => open_region_start_indices.push(child_index),
+            GDScriptNodeKind::RegionEnd => {
+                if let Some(start_child_index) = open_region_start_indices.pop() {
+                    region_blocks.push(RegionBlock {
+                        start_child_index,
+                        end_child_index: child_index,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    region_blocks.sort_by_key(|block| block.start_child_index);
+    region_blocks
+}
+
+/// Groups the items, sorted by child index, into entries. Items between the
+/// markers of a region block go into a region entry, and nested blocks into
+/// nested entries. Stops at the first item after `end_child_index`.
+fn build_reorder_entries<'a>(
+    items: &mut std::iter::Peekable<std::vec::IntoIter<ReorderItem<'a>>>,
+    region_blocks: &[RegionBlock],
+    region_block_index: &mut usize,
+    end_child_index: usize,
+) -> Vec<ReorderEntry<'a>> {
+    let mut entries = Vec::new();
+    while let Some(next_item) = items.peek() {
+        let next_child_index = next_item.child_index;
+        if next_child_index > end_child_index {
+            break;
+        }
+        if let Some(&block) = region_blocks.get(*region_block_index)
+            && block.start_child_index < next_child_index
+        {
+            *region_block_index += 1;
+            // A region without declarations keeps its markers attached to
+            // the declarations around it.
+            if block.end_child_index > next_child_index {
+                let block_entries = build_reorder_entries(
+                    items,
+                    region_blocks,
+                    region_block_index,
+                    block.end_child_index,
+                );
+                entries.push(ReorderEntry::Region {
+                    block,
+                    entries: block_entries,
+                });
+            }
+            continue;
+        }
+        if let Some(item) = items.next() {
+            entries.push(ReorderEntry::Item(item));
+        }
+    }
+    entries
+}
 
-    ReorderPlan { items }
+/// Sorts entries, and the entries inside each region, by their first item.
+/// Pinned entries stay in place, so we sort the entries between them
+/// separately.
+fn sort_reorder_entries(entries: &mut [ReorderEntry], config: &ReorderConfiguration) {
+    for entry in entries.iter_mut() {
+        if let ReorderEntry::Region { entries, .. } = entry {
+            sort_reorder_entries(entries, config);
+        }
+    }
+    let mut segment_start = 0;
+    while segment_start < entries.len() {
+        let mut segment_end = segment_start;
+        while segment_end < entries.len() && !entries[segment_end].is_pinned(config.region_mode) {
+            segment_end += 1;
+        }
+        entries[segment_start..segment_end].sort_by(|left, right| {
+            compare_reorder_items(left.get_first_item(), right.get_first_item(), config)
+        });
+        segment_start = segment_end + 1;
+    }
+}
+
+/// Appends the items of the entries to `items` in order. The markers of each
+/// region block move to the first and last items of the block.
+fn flatten_reorder_entries<'a>(entries: Vec<ReorderEntry<'a>>, items: &mut Vec<ReorderItem<'a>>) {
+    for entry in entries {
+        match entry {
+            ReorderEntry::Item(item) => items.push(item),
+            ReorderEntry::Region { block, entries } => {
+                let first_block_item_index = items.len();
+                flatten_reorder_entries(entries, items);
+                move_region_markers(&mut items[first_block_item_index..], block);
+            }
+        }
+    }
+}
+
+/// Attaches the `#region` marker, with the comments before it, to the first
+/// item of the block, and the `#endregion` marker, with the children after
+/// it, to the last item. Before sorting, the markers were attached to the
+/// first and last items in source order.
+fn move_region_markers(block_items: &mut [ReorderItem], block: RegionBlock) {
+    let mut leading_child_indices = Vec::new();
+    let mut trailing_child_indices = Vec::new();
+    for item in block_items.iter_mut() {
+        item.child_indices_attached_before_declaration
+            .retain(|&child_index| {
+                if child_index <= block.start_child_index {
+                    leading_child_indices.push(child_index);
+                    return false;
+                }
+                true
+            });
+        item.child_indices_attached_after_declaration
+            .retain(|&child_index| {
+                if child_index >= block.end_child_index {
+                    trailing_child_indices.push(child_index);
+                    return false;
+                }
+                true
+            });
+    }
+    leading_child_indices.sort_unstable();
+    trailing_child_indices.sort_unstable();
+    if let Some(first_item) = block_items.first_mut() {
+        leading_child_indices.append(&mut first_item.child_indices_attached_before_declaration);
+        first_item.child_indices_attached_before_declaration = leading_child_indices;
+    }
+    if let Some(last_item) = block_items.last_mut() {
+        last_item
+            .child_indices_attached_after_declaration
+            .append(&mut trailing_child_indices);
+    }
 }
 
 /// Result of classifying a single child node during reorder planning.
@@ -398,7 +1082,11 @@ impl<'a> ChildClassification<'a> {
     }
 }
 
-fn classify_child<'a>(node: Node<'a>, content: &'a str) -> ChildClassification<'a> {
+fn classify_child<'a>(
+    node: Node<'a>,
+    content: &'a str,
+    config: &ReorderConfiguration,
+) -> ChildClassification<'a> {
     let kind = GDScriptNodeKind::get_kind_from_ast_node(node);
     match kind {
         GDScriptNodeKind::Annotation => {
@@ -452,11 +1140,9 @@ fn classify_child<'a>(node: Node<'a>, content: &'a str) -> ChildClassification<'
             } else if has_static_keyword_child(node) {
                 MethodType::StaticFunction
             } else {
-                let priority = get_builtin_virtual_priority(name);
-                if priority != 0 {
-                    MethodType::BuiltinVirtual(priority)
-                } else {
-                    MethodType::Custom
+                match config.get_virtual_method_priority(name) {
+                    Some(priority) => MethodType::BuiltinVirtual(priority),
+                    None => MethodType::Custom,
                 }
             };
             ChildClassification {
@@ -578,50 +1264,36 @@ fn has_static_keyword_child(node: Node) -> bool {
     false
 }
 
-/// Maps built-in virtual method names to Godot lifecycle priority.
-fn get_builtin_virtual_priority(method_name: &str) -> u8 {
-    match method_name {
-        "_init" => 1,
-        "_enter_tree" => 2,
-        "_ready" => 3,
-        "_process" => 4,
-        "_physics_process" => 5,
-        "_exit_tree" => 6,
-        "_input" => 7,
-        "_unhandled_input" => 8,
-        "_unhandled_key_input" | "_gui_input" => 9,
-        "_draw" => 10,
-        "_notification" => 11,
-        "_get_configuration_warnings" => 12,
-        "_validate_property" => 13,
-        "_get_property_list" => 14,
-        "_property_can_revert" => 15,
-        "_property_get_revert" => 16,
-        "_get" => 17,
-        "_set" => 18,
-        "_to_string" => 19,
-        "_accessibility_get_contextual_info" => 20,
-        "_can_drop_data" => 21,
-        "_drop_data" => 22,
-        "_get_accessibility_container_name" => 23,
-        "_get_drag_data" => 24,
-        "_get_minimum_size" => 25,
-        "_get_tooltip" => 26,
-        "_has_point" => 27,
-        "_make_custom_tooltip" => 28,
-        "_structured_text_parser" => 29,
-        _ => 0,
-    }
-}
-
-fn compare_reorder_items(left: &ReorderItem, right: &ReorderItem) -> std::cmp::Ordering {
-    // 1. DeclarationKind (numeric discriminant)
-    let kind_cmp = (left.classification as u8).cmp(&(right.classification as u8));
+fn compare_reorder_items(
+    left: &ReorderItem,
+    right: &ReorderItem,
+    config: &ReorderConfiguration,
+) -> std::cmp::Ordering {
+    // 1. DeclarationKind (configured category order)
+    let kind_cmp = config
+        .get_category_rank(left.classification)
+        .cmp(&config.get_category_rank(right.classification));
     if kind_cmp != std::cmp::Ordering::Equal {
         return kind_cmp;
     }
 
-    // 2. MethodType sub-sorting for Method items
+    // 2. Export groups stay together in source order, with the grouping
+    // annotation first. Variables in a group sort like other declarations.
+    if left.classification == DeclarationKind::ExportVariable
+        && right.classification == DeclarationKind::ExportVariable
+    {
+        let group_cmp = left.export_group_index.cmp(&right.export_group_index);
+        if group_cmp != std::cmp::Ordering::Equal {
+            return group_cmp;
+        }
+        // Grouping annotation items have no name.
+        let annotation_cmp = right.name.is_empty().cmp(&left.name.is_empty());
+        if annotation_cmp != std::cmp::Ordering::Equal {
+            return annotation_cmp;
+        }
+    }
+
+    // 3. MethodType sub-sorting for Method items
     if let (Some(method_type_left), Some(method_type_right)) = (left.method_type, right.method_type)
     {
         let type_cmp = method_type_left.cmp(&method_type_right);
@@ -630,13 +1302,15 @@ fn compare_reorder_items(left: &ReorderItem, right: &ReorderItem) -> std::cmp::O
         }
     }
 
-    // 3. Privacy: public before pseudo-private
-    let privacy_cmp = left.is_private.cmp(&right.is_private);
-    if privacy_cmp != std::cmp::Ordering::Equal {
-        return privacy_cmp;
+    // 4. Privacy: public before pseudo-private
+    if config.sort_mode != ReorderSortMode::Source {
+        let privacy_cmp = left.is_private.cmp(&right.is_private);
+        if privacy_cmp != std::cmp::Ordering::Equal {
+            return privacy_cmp;
+        }
     }
 
-    // 4. ClassAnnotation special ordering: @tool < @icon < other
+    // 5. ClassAnnotation special ordering: @tool < @icon < other
     if left.classification == DeclarationKind::ClassAnnotation
         && right.classification == DeclarationKind::ClassAnnotation
     {
@@ -648,10 +1322,29 @@ fn compare_reorder_items(left: &ReorderItem, right: &ReorderItem) -> std::cmp::O
         }
     }
 
-    // 5. Stable: original source order (child_index)
+    // 6. Name, in alphabetical mode. The class header keeps its order.
+    if config.sort_mode == ReorderSortMode::Alphabetical
+        && config.get_category_rank(left.classification) >= DeclarationKind::Signal as usize
+    {
+        let name_cmp = compare_names(left.name, right.name);
+        if name_cmp != std::cmp::Ordering::Equal {
+            return name_cmp;
+        }
+    }
+
+    // 7. Stable: original source order (child_index)
     left.child_index.cmp(&right.child_index)
 }
 
+/// Compares names ignoring case first, so `health` comes before `Speed`.
+fn compare_names(left: &str, right: &str) -> std::cmp::Ordering {
+    let left_lowercase = left.chars().flat_map(char::to_lowercase);
+    let right_lowercase = right.chars().flat_map(char::to_lowercase);
+    left_lowercase
+        .cmp(right_lowercase)
+        .then_with(|| left.cmp(right))
+}
+
 fn annotation_priority(text: &str) -> u8 {
     if text.starts_with("@tool") {
         0
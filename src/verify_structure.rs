@@ -26,17 +26,177 @@
 //!
 //! We normalize ASTs recursively checking for these things before comparing
 //! them (i.e. verify that node kind + children match).
+//!
+//! Comments don't show up in the normalized trees, as the formatter moves
+//! them around between nodes. [find_comment_mismatch] checks them separately:
+//! the input and output must contain the same comments, in any order.
+//!
+//! Reordering code moves declarations, so the trees don't match anymore.
+//! [find_export_group_mismatch] checks what matters for exported variables
+//! instead: each one must stay in the same inspector group.
+//! [find_unmatched_region] checks that every `#region` marker still has its
+//! `#endregion`.
 use crate::node_kind::GDScriptNodeKind;
+use std::collections::HashMap;
 use tree_sitter::Node;
 
 /// Lightweight normalized node used for structural comparison.
 /// Owns its children so we can compare two normalized trees independently.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Only `kind` and `children` take part in the comparison. The kind name and
+/// lines are there to report where the trees differ.
+#[derive(Debug, Clone)]
 struct NormalizedNode {
     kind: GDScriptNodeKind,
+    kind_name: &'static str,
+    /// 1-based, inclusive line range of the node in its source code.
+    start_line: usize,
+    end_line: usize,
     children: Vec<NormalizedNode>,
 }
 
+/// A node in the input or output tree where the two trees start to differ.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MismatchedNode {
+    /// The tree-sitter kind of the node, like `variable_statement`.
+    pub kind: &'static str,
+    /// 1-based, inclusive line range of the node.
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// The first place where the input and output trees differ, in depth-first
+/// order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureMismatch {
+    /// Path from the root to the mismatching node, with the index of each node
+    /// among its siblings, like `source > function_definition[1] > body[3]`.
+    pub path: String,
+    /// The node in the input tree. None if the output has an extra node here.
+    pub input: Option<MismatchedNode>,
+    /// The node in the output tree. None if the output lacks this node.
+    pub output: Option<MismatchedNode>,
+}
+
+impl std::fmt::Display for MismatchedNode {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.start_line == self.end_line {
+            write!(formatter, "`{}` on line {}", self.kind, self.start_line)
+        } else {
+            write!(
+                formatter,
+                "`{}` on lines {}-{}",
+                self.kind, self.start_line, self.end_line
+            )
+        }
+    }
+}
+
+impl std::fmt::Display for StructureMismatch {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "first difference at {}: ", self.path)?;
+        match &self.input {
+            Some(input) => write!(formatter, "input has {}", input)?,
+            None => write!(formatter, "input has no node")?,
+        }
+        match &self.output {
+            Some(output) => write!(formatter, ", output has {}", output),
+            None => write!(formatter, ", output has no node"),
+        }
+    }
+}
+
+/// A comment the formatter dropped or added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentMismatch {
+    /// The comment text, without trailing whitespace.
+    pub text: String,
+    /// 1-based line of the comment: in the input for a missing comment, in
+    /// the output for an extra one.
+    pub line: usize,
+    /// True if the output lacks the comment, false if the output has one more
+    /// copy of it than the input.
+    pub is_missing: bool,
+}
+
+impl std::fmt::Display for CommentMismatch {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_missing {
+            write!(
+                formatter,
+                "formatted output lost the comment `{}` from line {} of the input",
+                self.text, self.line
+            )
+        } else {
+            write!(
+                formatter,
+                "formatted output has an extra comment `{}` on line {}",
+                self.text, self.line
+            )
+        }
+    }
+}
+
+/// An exported variable that ended up in a different inspector group, for
+/// example because reordering moved it below another `@export_group`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportGroupMismatch {
+    /// The variable name, preceded by the names of the inner classes it's in,
+    /// like `Stats.health`.
+    pub variable: String,
+    /// The inspector group path in the input, like `Stats > Defense`. Empty
+    /// if the variable isn't in a group.
+    pub input_group: String,
+    pub output_group: String,
+    /// 1-based line of the variable in the output.
+    pub line: usize,
+}
+
+impl std::fmt::Display for ExportGroupMismatch {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let describe_group = |group: &str| {
+            if group.is_empty() {
+                "no inspector group".to_string()
+            } else {
+                format!("inspector group `{}`", group)
+            }
+        };
+        write!(
+            formatter,
+            "exported variable `{}` on line {} moved from {} to {}",
+            self.variable,
+            self.line,
+            describe_group(&self.input_group),
+            describe_group(&self.output_group)
+        )
+    }
+}
+
+/// A `#region` marker without a matching `#endregion`, or the reverse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnmatchedRegion {
+    /// The marker text, like `#region Movement`.
+    pub text: String,
+    /// 1-based line of the marker.
+    pub line: usize,
+    pub is_region_start: bool,
+}
+
+impl std::fmt::Display for UnmatchedRegion {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let missing_marker = if self.is_region_start {
+            "#endregion"
+        } else {
+            "#region"
+        };
+        write!(
+            formatter,
+            "`{}` on line {} has no matching `{}`",
+            self.text, self.line, missing_marker
+        )
+    }
+}
+
 /// Entry point: compare two tree-sitter trees for structural equivalence,
 /// accounting for formatting-induced CST changes.
 pub fn trees_structurally_equal(
@@ -44,9 +204,365 @@ pub fn trees_structurally_equal(
     output_tree: &tree_sitter::Tree,
     lookup: &[GDScriptNodeKind; 256],
 ) -> bool {
+    find_first_structure_mismatch(input_tree, output_tree, lookup).is_none()
+}
+
+/// Compares two trees like [trees_structurally_equal] and returns the first
+/// node where they differ, or None if they're structurally equal.
+pub fn find_first_structure_mismatch(
+    input_tree: &tree_sitter::Tree,
+    output_tree: &tree_sitter::Tree,
+    lookup: &[GDScriptNodeKind; 256],
+) -> Option<StructureMismatch> {
     let input = normalize_node(input_tree.root_node(), lookup);
     let output = normalize_node(output_tree.root_node(), lookup);
-    input == output
+    let mut path = vec![input.kind_name.to_string()];
+    find_mismatch(&input, &output, &mut path)
+}
+
+/// Checks that the output has exactly the same comments as the input,
+/// including doc comments and `#region` and `# fmt: off` markers. Returns the
+/// first comment missing from the output in input order, or else the first
+/// extra comment in output order. Trailing whitespace doesn't count as the
+/// formatter trims it.
+pub fn find_comment_mismatch(
+    input_tree: &tree_sitter::Tree,
+    input_source: &str,
+    output_tree: &tree_sitter::Tree,
+    output_source: &str,
+) -> Option<CommentMismatch> {
+    let mut input_comments = Vec::new();
+    collect_comments(input_tree.root_node(), input_source, &mut input_comments);
+    let mut output_comments = Vec::new();
+    collect_comments(output_tree.root_node(), output_source, &mut output_comments);
+
+    // For each comment text, the output lines where it appears and how many
+    // of them the input comments matched so far.
+    let mut output_lines_by_text: HashMap<&str, (Vec<usize>, usize)> = HashMap::new();
+    for (text, line) in &output_comments {
+        output_lines_by_text.entry(text).or_default().0.push(*line);
+    }
+    for (text, line) in &input_comments {
+        match output_lines_by_text.get_mut(text) {
+            Some((lines, matched_count)) if *matched_count < lines.len() => {
+                *matched_count += 1;
+            }
+            _ => {
+                return Some(CommentMismatch {
+                    text: text.to_string(),
+                    line: *line,
+                    is_missing: true,
+                });
+            }
+        }
+    }
+
+    let mut first_extra_comment: Option<CommentMismatch> = None;
+    for (text, (lines, matched_count)) in output_lines_by_text {
+        if let Some(&line) = lines.get(matched_count)
+            && first_extra_comment
+                .as_ref()
+                .is_none_or(|extra_comment| line < extra_comment.line)
+        {
+            first_extra_comment = Some(CommentMismatch {
+                text: text.to_string(),
+                line,
+                is_missing: false,
+            });
+        }
+    }
+    first_extra_comment
+}
+
+/// Returns the first `#endregion` without a `#region` before it, or else the
+/// first `#region` that has no `#endregion`.
+pub fn find_unmatched_region(tree: &tree_sitter::Tree, source: &str) -> Option<UnmatchedRegion> {
+    let mut markers = Vec::new();
+    collect_region_markers(tree.root_node(), source, &mut markers);
+    let mut open_regions = Vec::new();
+    for marker in markers {
+        if marker.is_region_start {
+            open_regions.push(marker);
+        } else if open_regions.pop().is_none() {
+            return Some(marker);
+        }
+    }
+    open_regions.into_iter().next()
+}
+
+/// Collects the `#region` and `#endregion` markers under `node`, in source
+/// order.
+fn collect_region_markers(node: Node, source: &str, markers: &mut Vec<UnmatchedRegion>) {
+    let kind = GDScriptNodeKind::get_kind_from_ast_node(node);
+    if kind == GDScriptNodeKind::RegionStart || kind == GDScriptNodeKind::RegionEnd {
+        markers.push(UnmatchedRegion {
+            text: source[node.byte_range()].trim_end().to_string(),
+            line: node.start_position().row + 1,
+            is_region_start: kind == GDScriptNodeKind::RegionStart,
+        });
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_region_markers(child, source, markers);
+    }
+}
+
+/// Checks that every exported variable of the input is in the same inspector
+/// group in the output. Groups come from the `@export_category`,
+/// `@export_group`, and `@export_subgroup` annotations before the variable in
+/// its class. Returns the first variable in output order that changed group.
+pub fn find_export_group_mismatch(
+    input_tree: &tree_sitter::Tree,
+    input_source: &str,
+    output_tree: &tree_sitter::Tree,
+    output_source: &str,
+) -> Option<ExportGroupMismatch> {
+    let mut input_variables = Vec::new();
+    collect_exported_variables(
+        input_tree.root_node(),
+        input_source,
+        "",
+        &mut input_variables,
+    );
+    let mut output_variables = Vec::new();
+    collect_exported_variables(
+        output_tree.root_node(),
+        output_source,
+        "",
+        &mut output_variables,
+    );
+
+    let input_groups: HashMap<&str, &str> = input_variables
+        .iter()
+        .map(|variable| (variable.name.as_str(), variable.group.as_str()))
+        .collect();
+    for variable in &output_variables {
+        if let Some(&input_group) = input_groups.get(variable.name.as_str())
+            && input_group != variable.group
+        {
+            return Some(ExportGroupMismatch {
+                variable: variable.name.clone(),
+                input_group: input_group.to_string(),
+                output_group: variable.group.clone(),
+                line: variable.line,
+            });
+        }
+    }
+    None
+}
+
+/// An exported variable and the inspector group it appears in.
+struct ExportedVariable {
+    name: String,
+    group: String,
+    /// 1-based line of the variable.
+    line: usize,
+}
+
+/// The inspector group that applies to the next exported variables of a
+/// class.
+#[derive(Default)]
+struct ExportGroupState<'a> {
+    category: &'a str,
+    group: &'a str,
+    subgroup: &'a str,
+}
+
+impl ExportGroupState<'_> {
+    fn get_path(&self) -> String {
+        let mut path = String::new();
+        for part in [self.category, self.group, self.subgroup] {
+            if part.is_empty() {
+                continue;
+            }
+            if !path.is_empty() {
+                path.push_str(" > ");
+            }
+            path.push_str(part);
+        }
+        path
+    }
+}
+
+/// Collects the exported variables of the class whose declarations are the
+/// children of `class_node`, then of its inner classes. `class_path` is
+/// prepended to variable names.
+fn collect_exported_variables(
+    class_node: Node,
+    source: &str,
+    class_path: &str,
+    variables: &mut Vec<ExportedVariable>,
+) {
+    let mut state = ExportGroupState::default();
+    // A standalone `@export` annotation applies to the variable on the next
+    // line.
+    let mut has_pending_export_annotation = false;
+    let mut cursor = class_node.walk();
+    for child in class_node.children(&mut cursor) {
+        match GDScriptNodeKind::get_kind_from_ast_node(child) {
+            GDScriptNodeKind::Annotation => {
+                if apply_annotation(child, source, &mut state) {
+                    has_pending_export_annotation = true;
+                }
+            }
+            GDScriptNodeKind::Variable
+            | GDScriptNodeKind::ExportVariable
+            | GDScriptNodeKind::OnReadyVariable => {
+                let mut is_exported = has_pending_export_annotation;
+                has_pending_export_annotation = false;
+                let mut variable_cursor = child.walk();
+                for variable_child in child.children(&mut variable_cursor) {
+                    if GDScriptNodeKind::get_kind_from_ast_node(variable_child)
+                        != GDScriptNodeKind::Annotations
+                    {
+                        continue;
+                    }
+                    let mut annotations_cursor = variable_child.walk();
+                    for annotation in variable_child.children(&mut annotations_cursor) {
+                        if apply_annotation(annotation, source, &mut state) {
+                            is_exported = true;
+                        }
+                    }
+                }
+                if let (true, Some(name)) = (is_exported, child.child_by_field_name("name")) {
+                    variables.push(ExportedVariable {
+                        name: format!("{}{}", class_path, &source[name.byte_range()]),
+                        group: state.get_path(),
+                        line: child.start_position().row + 1,
+                    });
+                }
+            }
+            GDScriptNodeKind::ClassDefinition | GDScriptNodeKind::InnerClass => {
+                has_pending_export_annotation = false;
+                let Some(name) = child.child_by_field_name("name") else {
+                    continue;
+                };
+                let inner_class_path = format!("{}{}.", class_path, &source[name.byte_range()]);
+                let mut class_cursor = child.walk();
+                for class_child in child.children(&mut class_cursor) {
+                    if GDScriptNodeKind::get_kind_from_ast_node(class_child)
+                        == GDScriptNodeKind::ClassBody
+                    {
+                        collect_exported_variables(
+                            class_child,
+                            source,
+                            &inner_class_path,
+                            variables,
+                        );
+                    }
+                }
+            }
+            GDScriptNodeKind::Comment => {}
+            _ => has_pending_export_annotation = false,
+        }
+    }
+}
+
+/// Updates the group state for an export grouping annotation. Returns true if
+/// the annotation exports a variable, like `@export` or `@export_range`.
+fn apply_annotation<'a>(
+    annotation: Node,
+    source: &'a str,
+    state: &mut ExportGroupState<'a>,
+) -> bool {
+    let mut annotation_name = "";
+    let mut first_argument = "";
+    let mut cursor = annotation.walk();
+    for child in annotation.named_children(&mut cursor) {
+        match GDScriptNodeKind::get_kind_from_ast_node(child) {
+            GDScriptNodeKind::Identifier => annotation_name = &source[child.byte_range()],
+            GDScriptNodeKind::Arguments => {
+                if let Some(argument) = child.named_child(0) {
+                    first_argument = source[argument.byte_range()].trim_matches(['"', '\'']);
+                }
+            }
+            _ => {}
+        }
+    }
+    match annotation_name {
+        "export_category" => {
+            *state = ExportGroupState {
+                category: first_argument,
+                ..Default::default()
+            };
+            false
+        }
+        "export_group" => {
+            state.group = first_argument;
+            state.subgroup = "";
+            false
+        }
+        "export_subgroup" => {
+            state.subgroup = first_argument;
+            false
+        }
+        _ => annotation_name.starts_with("export"),
+    }
+}
+
+/// Collects the trimmed text and 1-based line of every comment under `node`,
+/// in source order. `#region` and `#endregion` markers count as comments.
+fn collect_comments<'a>(node: Node, source: &'a str, comments: &mut Vec<(&'a str, usize)>) {
+    if matches!(
+        GDScriptNodeKind::get_kind_from_ast_node(node),
+        GDScriptNodeKind::Comment | GDScriptNodeKind::RegionStart | GDScriptNodeKind::RegionEnd
+    ) {
+        let text = source[node.start_byte()..node.end_byte()].trim_end();
+        comments.push((text, node.start_position().row + 1));
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_comments(child, source, comments);
+    }
+}
+
+fn find_mismatch(
+    input: &NormalizedNode,
+    output: &NormalizedNode,
+    path: &mut Vec<String>,
+) -> Option<StructureMismatch> {
+    if input.kind != output.kind {
+        return Some(make_mismatch(path, Some(input), Some(output)));
+    }
+    let child_count = input.children.len().max(output.children.len());
+    for child_index in 0..child_count {
+        let input_child = input.children.get(child_index);
+        let output_child = output.children.get(child_index);
+        let Some(named_child) = input_child.or(output_child) else {
+            continue;
+        };
+        path.push(format!("{}[{}]", named_child.kind_name, child_index));
+        let mismatch = match (input_child, output_child) {
+            (Some(input_child), Some(output_child)) => {
+                find_mismatch(input_child, output_child, path)
+            }
+            _ => Some(make_mismatch(path, input_child, output_child)),
+        };
+        if mismatch.is_some() {
+            return mismatch;
+        }
+        path.pop();
+    }
+    None
+}
+
+fn make_mismatch(
+    path: &[String],
+    input: Option<&NormalizedNode>,
+    output: Option<&NormalizedNode>,
+) -> StructureMismatch {
+    let to_mismatched_node = |node: &NormalizedNode| MismatchedNode {
+        kind: node.kind_name,
+        start_line: node.start_line,
+        end_line: node.end_line,
+    };
+    StructureMismatch {
+        path: path.join(" > "),
+        input: input.map(to_mismatched_node),
+        output: output.map(to_mismatched_node),
+    }
 }
 
 fn is_annotatable_declaration(kind: GDScriptNodeKind) -> bool {
@@ -81,6 +597,9 @@ fn normalize_node(node: Node, lookup: &[GDScriptNodeKind; 256]) -> NormalizedNod
     let children = build_normalized_children(node, lookup);
     NormalizedNode {
         kind: canonical_kind,
+        kind_name: node.kind(),
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
         children,
     }
 }
@@ -139,8 +658,19 @@ fn build_normalized_children(node: Node, lookup: &[GDScriptNodeKind; 256]) -> Ve
                             annotation_children.push(normalize_node(annotation_child, lookup));
                         }
                     }
+                    let first_annotation_line = annotation_children
+                        .first()
+                        .map_or(after.start_position().row + 1, |annotation| {
+                            annotation.start_line
+                        });
+                    let last_annotation_line = annotation_children
+                        .last()
+                        .map_or(first_annotation_line, |annotation| annotation.end_line);
                     let annotations_wrapper = NormalizedNode {
                         kind: GDScriptNodeKind::Annotations,
+                        kind_name: "annotations",
+                        start_line: first_annotation_line,
+                        end_line: last_annotation_line,
                         children: annotation_children,
                     };
                     let declaration_normalized = normalize_node(after, lookup);
@@ -168,6 +698,9 @@ fn build_normalized_children(node: Node, lookup: &[GDScriptNodeKind; 256]) -> Ve
                     }
                     out.push(NormalizedNode {
                         kind: declaration_normalized.kind,
+                        kind_name: declaration_normalized.kind_name,
+                        start_line: first_annotation_line,
+                        end_line: declaration_normalized.end_line,
                         children: merged_children,
                     });
                     current_child_index = annotation_end + 1;
@@ -213,6 +746,9 @@ fn build_normalized_children(node: Node, lookup: &[GDScriptNodeKind; 256]) -> Ve
                 }
                 out.push(NormalizedNode {
                     kind: GDScriptNodeKind::ClassName,
+                    kind_name: child.kind(),
+                    start_line: child.start_position().row + 1,
+                    end_line: child.end_position().row + 1,
                     children: class_children,
                 });
                 // Find the extends child and emit it as a separate sibling.
@@ -268,6 +804,44 @@ mod tests {
         trees_structurally_equal(&ta, &tb, lookup())
     }
 
+    fn find_export_group_change(input: &str, output: &str) -> Option<ExportGroupMismatch> {
+        find_export_group_mismatch(&parse(input), input, &parse(output), output)
+    }
+
+    #[test]
+    fn export_variables_keep_their_inspector_group() {
+        let input = "@export var speed = 1\n@export_group(\"Stats\")\n@export var health = 10\n";
+        let reordered = "@export var speed = 1\n@export_group(\"Stats\")\nvar temp = 0\n@export var health = 10\n";
+        assert_eq!(find_export_group_change(input, reordered), None);
+
+        let moved = "@export_group(\"Stats\")\n@export var health = 10\n@export var speed = 1\n";
+        assert_eq!(
+            find_export_group_change(input, moved),
+            Some(ExportGroupMismatch {
+                variable: "speed".to_string(),
+                input_group: String::new(),
+                output_group: "Stats".to_string(),
+                line: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn finds_unmatched_region_markers() {
+        let source = "#region Stats\nvar health = 10\n#endregion\n";
+        assert_eq!(find_unmatched_region(&parse(source), source), None);
+
+        let source = "#region Stats\nvar health = 10\n#endregion\n#endregion\n";
+        assert_eq!(
+            find_unmatched_region(&parse(source), source),
+            Some(UnmatchedRegion {
+                text: "#endregion".to_string(),
+                line: 4,
+                is_region_start: false,
+            })
+        );
+    }
+
     #[test]
     fn identical_trees_equal() {
         assert!(structurally_equal("var x = 1", "var x = 1"));
@@ -376,6 +950,103 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn first_mismatch_reports_path_and_lines() {
+        let input = parse("var x = 1\nfunc test():\n\tpass\n\treturn\n");
+        let output = parse("var x = 1\nfunc test():\n\tpass\n\tx\n");
+
+        let mismatch = find_first_structure_mismatch(&input, &output, lookup()).unwrap();
+
+        assert!(
+            mismatch
+                .path
+                .starts_with("source > function_definition[1] > body[")
+        );
+        let input_node = mismatch.input.unwrap();
+        let output_node = mismatch.output.unwrap();
+        assert_eq!(input_node.kind, "return_statement");
+        assert_eq!((input_node.start_line, input_node.end_line), (4, 4));
+        assert_eq!(output_node.kind, "expression_statement");
+    }
+
+    #[test]
+    fn first_mismatch_reports_missing_nodes() {
+        let input = parse("var x = 1\nvar y = 2\n");
+        let output = parse("var x = 1\n");
+
+        let mismatch = find_first_structure_mismatch(&input, &output, lookup()).unwrap();
+
+        assert_eq!(mismatch.path, "source > variable_statement[1]");
+        assert_eq!(mismatch.input.unwrap().start_line, 2);
+        assert_eq!(mismatch.output, None);
+    }
+
+    fn comment_mismatch(input: &str, output: &str) -> Option<CommentMismatch> {
+        find_comment_mismatch(&parse(input), input, &parse(output), output)
+    }
+
+    #[test]
+    fn moved_comments_match() {
+        assert_eq!(
+            comment_mismatch(
+                "#region Stats\n## Doc\nvar x = 1 # inline  \n#endregion\n",
+                "#region Stats\n# inline\n## Doc\nvar x = 1\n#endregion\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn missing_comment_reported_with_input_line() {
+        let mismatch = comment_mismatch(
+            "var x = 1\n# fmt: off\nvar y=2\n# fmt: on\n",
+            "var x = 1\n# fmt: off\nvar y=2\n",
+        )
+        .unwrap();
+        assert_eq!(mismatch.text, "# fmt: on");
+        assert_eq!(mismatch.line, 4);
+        assert!(mismatch.is_missing);
+    }
+
+    #[test]
+    fn missing_region_marker_reported() {
+        let mismatch = comment_mismatch(
+            "var x = 1\n#region Stats\nvar y = 2\n#endregion\n",
+            "var x = 1\nvar y = 2\n#endregion\n",
+        )
+        .unwrap();
+        assert_eq!(mismatch.text, "#region Stats");
+        assert_eq!(mismatch.line, 2);
+        assert!(mismatch.is_missing);
+    }
+
+    #[test]
+    fn duplicated_comment_reported_with_output_line() {
+        let mismatch =
+            comment_mismatch("# note\nvar x = 1\n", "# note\nvar x = 1\n# note\n").unwrap();
+        assert_eq!(mismatch.text, "# note");
+        assert_eq!(mismatch.line, 3);
+        assert!(!mismatch.is_missing);
+    }
+
+    #[test]
+    fn structure_mismatch_display() {
+        let mismatch = StructureMismatch {
+            path: "source > function_definition[1] > body[2] > return_statement[1]".to_string(),
+            input: Some(MismatchedNode {
+                kind: "return_statement",
+                start_line: 4,
+                end_line: 5,
+            }),
+            output: None,
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            "first difference at source > function_definition[1] > body[2] > return_statement[1]: \
+             input has `return_statement` on lines 4-5, output has no node"
+        );
+    }
+
     #[test]
     fn parentheses_that_change_operator_grouping_are_not_equal() {
         assert!(!structurally_equal(
@@ -117,3 +117,328 @@ fn stdin_and_file_modes_apply_editorconfig_and_cli_overrides() {
 
     fs::remove_dir_all(directory).expect("should remove temporary test directory");
 }
+
+#[test]
+fn lines_option_formats_only_the_selected_lines() {
+    let directory = test_directory();
+    let input_path = directory.join("input.gd");
+    fs::write(&input_path, "var a=1\nvar b=2\nvar c=3\n").expect("should write input file");
+
+    let output = formatter_command(&directory, &["--stdout", "--lines", "2:3", "input.gd"])
+        .output()
+        .expect("should format file");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("output should be valid UTF-8"),
+        "var a=1\nvar b = 2\nvar c = 3\n",
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn diff_option_prints_changes_without_writing_files() {
+    let directory = test_directory();
+    let input_path = directory.join("input.gd");
+    let input = "var a=1\nvar b = 2\n";
+    fs::write(&input_path, input).expect("should write input file");
+
+    let output = formatter_command(&directory, &["--diff", "--check", "input.gd"])
+        .output()
+        .expect("should format file");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("output should be valid UTF-8"),
+        "--- input.gd\n+++ input.gd\n@@ -1,2 +1,2 @@\n-var a=1\n+var a = 1\n var b = 2\n",
+    );
+    assert_eq!(
+        fs::read_to_string(&input_path).expect("should read input file"),
+        input
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn output_format_option_prints_lint_issues_as_github_annotations() {
+    let directory = test_directory();
+    fs::write(directory.join("lint.gd"), "var badVariable = 1\n").expect("should write lint input");
+
+    let output = formatter_command(
+        &directory,
+        &["lint", "--output-format", "github", "lint.gd"],
+    )
+    .output()
+    .expect("should lint file");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("lint output should be valid UTF-8"),
+        "::error file=lint.gd,line=1,col=5,endLine=1,endColumn=16,title=variable-name::Variable name 'badVariable' should be in snake_case or _private_snake_case format\n"
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn error_on_option_ignores_issues_below_the_threshold() {
+    let directory = test_directory();
+    fs::write(directory.join("lint.gd"), "var badVariable = 1\n").expect("should write lint input");
+
+    let error_output = formatter_command(&directory, &["lint", "--error-on", "error", "lint.gd"])
+        .output()
+        .expect("should lint file");
+    assert!(!error_output.status.success());
+
+    let demoted_output = formatter_command(
+        &directory,
+        &[
+            "lint",
+            "--severity",
+            "variable-name=warning",
+            "--error-on",
+            "error",
+            "lint.gd",
+        ],
+    )
+    .output()
+    .expect("should lint file with a severity override");
+    assert!(demoted_output.status.success());
+    assert!(
+        String::from_utf8(demoted_output.stdout)
+            .expect("lint output should be valid UTF-8")
+            .contains("lint.gd:1:variable-name:warning:")
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn lint_fix_only_turns_elif_into_if_after_returning_branches() {
+    let directory = test_directory();
+    fs::write(
+        directory.join("branches.gd"),
+        "func pick(a, b, c):\n\tvar x = 0\n\tif a:\n\t\treturn x\n\telif b:\n\t\tx = 1\n\telif c:\n\t\tx = 2\n\treturn x\n",
+    )
+    .expect("should write script");
+
+    let output = formatter_command(&directory, &["lint", "--fix", "branches.gd"])
+        .output()
+        .expect("should lint and fix file");
+
+    assert!(output.status.success());
+    // The second elif runs only if `b` is false, so it must stay an elif.
+    assert_eq!(
+        fs::read_to_string(directory.join("branches.gd")).expect("should read fixed script"),
+        "func pick(a, b, c):\n\tvar x = 0\n\tif a:\n\t\treturn x\n\tif b:\n\t\tx = 1\n\telif c:\n\t\tx = 2\n\treturn x\n"
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn lint_checks_project_rules_across_files() {
+    let directory = test_directory();
+    fs::write(directory.join("project.godot"), "config_version=5\n")
+        .expect("should write project.godot");
+    fs::write(
+        directory.join("a.gd"),
+        "class_name Player\nconst B = preload(\"res://b.gd\")\n",
+    )
+    .expect("should write first script");
+    fs::write(
+        directory.join("b.gd"),
+        "class_name Player\nconst A = preload(\"res://a.gd\")\nvar missing = load(\"res://missing.tscn\")\n",
+    )
+    .expect("should write second script");
+    fs::write(directory.join("c.gd"), "class_name Helper\n").expect("should write third script");
+
+    let output = formatter_command(&directory, &["lint", "a.gd", "b.gd", "c.gd"])
+        .output()
+        .expect("should lint files");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("lint output should be valid UTF-8"),
+        "a.gd:1:duplicate-class-name:error: Class name 'Player' is also declared in res://b.gd\n\
+         a.gd:2:cyclic-preload:warning: Cyclic preload: res://a.gd -> res://b.gd -> res://a.gd. Consider using load() for one of these scripts\n\
+         b.gd:1:duplicate-class-name:error: Class name 'Player' is also declared in res://a.gd\n\
+         b.gd:3:missing-resource:error: Resource 'res://missing.tscn' does not exist\n"
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn lint_passes_on_a_single_class_name_script() {
+    // Linting only some files of a project, like a pre-commit hook does,
+    // must not report classes the other files use.
+    let directory = test_directory();
+    fs::write(directory.join("project.godot"), "config_version=5\n")
+        .expect("should write project.godot");
+    fs::write(directory.join("helper.gd"), "class_name Helper\n").expect("should write script");
+
+    let output = formatter_command(&directory, &["lint", "helper.gd"])
+        .output()
+        .expect("should lint file");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn suppression_comments_used_by_project_rules_are_not_unused() {
+    let directory = test_directory();
+    fs::write(directory.join("project.godot"), "config_version=5\n")
+        .expect("should write project.godot");
+    fs::write(
+        directory.join("loader.gd"),
+        "var scene = load(\"res://missing.tscn\") # gdlint-ignore\nvar other = load(\"res://other.tscn\") # gdlint-ignore missing-resource\n",
+    )
+    .expect("should write script");
+
+    let output = formatter_command(&directory, &["lint", "loader.gd"])
+        .output()
+        .expect("should lint file");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn severity_option_turns_on_project_rules() {
+    let directory = test_directory();
+    fs::write(directory.join("project.godot"), "config_version=5\n")
+        .expect("should write project.godot");
+    fs::write(directory.join("helper.gd"), "class_name Helper\n").expect("should write script");
+
+    let output = formatter_command(
+        &directory,
+        &["lint", "--severity", "unused-class-name=info", "helper.gd"],
+    )
+    .output()
+    .expect("should lint file");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).expect("lint output should be valid UTF-8"),
+        "helper.gd:1:unused-class-name:info: Class name 'Helper' is not used in any linted script. Consider removing it\n"
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn project_config_file_applies_before_editorconfig_and_cli_overrides() {
+    let directory = test_directory();
+    fs::write(directory.join("project.godot"), "config_version=5\n")
+        .expect("should write project.godot");
+    fs::write(
+        directory.join("gdscript-formatter.toml"),
+        "[formatter]\nuse_spaces = true\nindent_size = 2\nquote_style = \"double\"\n\n[linter.rules]\nvariable-name = \"off\"\n",
+    )
+    .expect("should write project config");
+    fs::write(
+        directory.join(".editorconfig"),
+        "root = true\n\n[*.gd]\nindent_size = 3\n",
+    )
+    .expect("should write EditorConfig");
+    fs::create_dir(directory.join("scripts")).expect("should create scripts directory");
+    fs::write(
+        directory.join("scripts").join("input.gd"),
+        "func first():\n\tvar badName = 'first'\n",
+    )
+    .expect("should write input file");
+
+    let format_output = formatter_command(
+        &directory,
+        &["--stdout", "--quote-style", "single", "scripts/input.gd"],
+    )
+    .output()
+    .expect("should format file");
+    assert!(format_output.status.success());
+    assert_eq!(
+        String::from_utf8(format_output.stdout).expect("format output should be valid UTF-8"),
+        "func first():\n   var badName = 'first'\n",
+    );
+
+    let lint_output = formatter_command(&directory, &["lint", "scripts/input.gd"])
+        .output()
+        .expect("should lint file");
+    assert!(lint_output.status.success());
+    assert!(lint_output.stdout.is_empty());
+
+    fs::write(
+        directory.join("gdscript-formatter.toml"),
+        "[formatter]\nindent_sise = 2\n",
+    )
+    .expect("should write invalid project config");
+    let invalid_output = formatter_command(&directory, &["--stdout", "scripts/input.gd"])
+        .output()
+        .expect("should run formatter");
+    assert!(!invalid_output.status.success());
+    assert!(
+        String::from_utf8(invalid_output.stderr)
+            .expect("error output should be valid UTF-8")
+            .contains("gdscript-formatter.toml:2: unknown key 'formatter/indent_sise'")
+    );
+    let invalid_lint_output = formatter_command(&directory, &["lint", "scripts/input.gd"])
+        .output()
+        .expect("should run linter");
+    assert!(!invalid_lint_output.status.success());
+    assert!(
+        String::from_utf8(invalid_lint_output.stderr)
+            .expect("error output should be valid UTF-8")
+            .contains("gdscript-formatter.toml:2: unknown key 'formatter/indent_sise'")
+    );
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
+
+#[test]
+fn cli_rule_severities_override_project_config() {
+    let directory = test_directory();
+    fs::write(directory.join("project.godot"), "config_version=5\n")
+        .expect("should write project.godot");
+    fs::write(
+        directory.join("gdscript-formatter.toml"),
+        "[linter]\ndisabled_rules = [\"variable-name\"]\n\n[linter.rules]\nvariable-name = \"hint\"\n",
+    )
+    .expect("should write project config");
+    fs::write(directory.join("lint.gd"), "var badVariable = 1\n").expect("should write lint input");
+
+    let project_output = formatter_command(&directory, &["lint", "lint.gd"])
+        .output()
+        .expect("should lint file");
+    assert!(
+        String::from_utf8(project_output.stdout)
+            .expect("lint output should be valid UTF-8")
+            .contains("lint.gd:1:variable-name:hint:")
+    );
+
+    let cli_output = formatter_command(
+        &directory,
+        &["lint", "--severity", "variable-name=warning", "lint.gd"],
+    )
+    .output()
+    .expect("should lint file with a severity override");
+    assert!(
+        String::from_utf8(cli_output.stdout)
+            .expect("lint output should be valid UTF-8")
+            .contains("lint.gd:1:variable-name:warning:")
+    );
+
+    let disabled_output = formatter_command(
+        &directory,
+        &["lint", "--disable", "variable-name", "lint.gd"],
+    )
+    .output()
+    .expect("should lint file with a disabled rule");
+    assert!(disabled_output.stdout.is_empty());
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
@@ -2,9 +2,13 @@
 /// Runs the formatter over a series of input files and verifies the output
 /// matches the expected output file. See files in the ./input and ./expected
 /// folders.
-use gdscript_formatter::linter::{GDScriptLinter, LinterConfig};
+use gdscript_formatter::linter::{GDScriptLinter, LintIssue, LintSeverity, LinterConfig};
+use gdscript_formatter::project_config::apply_project_config_to_formatter_config;
+use gdscript_formatter::reorder::DeclarationKind;
 use gdscript_formatter::{
-    FormatterConfiguration, PrinterConfiguration, QuoteStyle, format_gdscript,
+    Error, FormatterConfiguration, LineEnding, PrinterConfiguration, QuoteStyle,
+    ReorderConfiguration, ReorderRegionMode, ReorderSortMode, format_gdscript,
+    format_gdscript_range, line_range_to_byte_range,
 };
 use similar::{ChangeTag, TextDiff};
 use std::fs;
@@ -78,10 +82,7 @@ fn test_lint_file(file_path: &Path) {
             relative_path,
             issue.line,
             issue.rule,
-            match issue.severity {
-                gdscript_formatter::linter::LintSeverity::Error => "error",
-                gdscript_formatter::linter::LintSeverity::Warning => "warning",
-            },
+            issue.severity.name(),
             issue.message
         ));
     }
@@ -156,10 +157,10 @@ var node_path = ^"Node/Path"
     let expected = r#"var double = 'double'
 var single = 'already single'
 var escaped = 'line\n'
-var contains_preferred_quote = "don't change"
+var contains_preferred_quote = 'don\'t change'
 var multiline = '''multiple
 lines'''
-var multiline_with_preferred_quote = """has ''' inside"""
+var multiline_with_preferred_quote = '''has \''' inside'''
 var string_name = &'Name'
 var node_path = ^'Node/Path'
 "#;
@@ -182,7 +183,7 @@ var multiline = '''multiple
 lines'''
 "#;
     let expected = r#"var single = "single"
-var contains_preferred_quote = 'keep "double"'
+var contains_preferred_quote = "keep \"double\""
 var multiline = """multiple
 lines"""
 "#;
@@ -194,6 +195,74 @@ lines"""
     assert_eq!(format_gdscript(input, &config).unwrap(), expected);
 }
 
+#[test]
+fn quote_style_rewrites_escapes_in_prefixed_strings() {
+    let input = r#"var escaped = 'it\'s'
+var unnecessary_escapes = 'say \"hi\"'
+var raw = r'raw \d'
+var raw_with_preferred_quote = r'say "hi"'
+var string_name = &'it\'s'
+var node_path = ^'Node/Path'
+var ends_with_quote = '''ends with "'''
+"#;
+    let expected = r#"var escaped = "it's"
+var unnecessary_escapes = "say \"hi\""
+var raw = r"raw \d"
+var raw_with_preferred_quote = r'say "hi"'
+var string_name = &"it's"
+var node_path = ^"Node/Path"
+var ends_with_quote = """ends with \""""
+"#;
+    let config = FormatterConfiguration {
+        quote_style: QuoteStyle::Double,
+        safe: true,
+        ..Default::default()
+    };
+
+    let output = format_gdscript(input, &config).unwrap();
+    assert_eq!(output, expected);
+    assert_eq!(format_gdscript(&output, &config).unwrap(), output);
+}
+
+#[test]
+fn quote_style_minimize_escapes_picks_the_delimiter_with_fewer_escapes() {
+    let input = r#"var a = 'don\'t'
+var b = "say \"hi\""
+var c = 'plain'
+var d = "it's \"both\""
+var e = '''a"""b'''
+"#;
+    let expected = r#"var a = "don't"
+var b = 'say "hi"'
+var c = 'plain'
+var d = 'it\'s "both"'
+var e = '''a"""b'''
+"#;
+    let config = FormatterConfiguration {
+        quote_style: QuoteStyle::MinimizeEscapes,
+        safe: true,
+        ..Default::default()
+    };
+
+    assert_eq!(format_gdscript(input, &config).unwrap(), expected);
+}
+
+#[test]
+fn verify_idempotent_accepts_stable_output() {
+    let input = "func test( a,b ):\n\tvar x=[1,2,\n3]\n\treturn x\n";
+    let config = FormatterConfiguration {
+        verify_idempotent: true,
+        ..Default::default()
+    };
+
+    let output = format_gdscript(input, &config).unwrap();
+
+    assert_eq!(
+        output,
+        format_gdscript(input, &FormatterConfiguration::default()).unwrap()
+    );
+}
+
 #[test]
 fn parse_errors_disable_reordering_without_disabling_formatting() {
     let input = "var b=1\nvar name.bla = value\nvar a=2\n";
@@ -207,6 +276,47 @@ fn parse_errors_disable_reordering_without_disabling_formatting() {
     assert_eq!(output, "var b = 1\nvar name.bla = value\nvar a = 2\n");
 }
 
+#[test]
+fn parse_errors_only_keep_the_broken_statement_unformatted() {
+    let input = "func test( a,b ):\n\tvar x=a+b\n\tvar name.bla = value\n\treturn x*2\n";
+    let config = FormatterConfiguration {
+        safe: true,
+        ..Default::default()
+    };
+
+    let output = format_gdscript(input, &config).unwrap();
+
+    assert_eq!(
+        output,
+        "func test(a, b):\n\tvar x = a + b\n\tvar name.bla = value\n\treturn x * 2\n"
+    );
+}
+
+#[test]
+fn invalid_project_config_is_a_config_error() {
+    let directory = std::env::temp_dir().join(format!(
+        "gdscript-formatter-integration-test-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(
+        directory.join("gdscript-formatter.toml"),
+        "[formatter]\nindent_sise = 2\n",
+    )
+    .unwrap();
+
+    let result = apply_project_config_to_formatter_config(
+        &mut FormatterConfiguration::default(),
+        &directory.join("player.gd"),
+    );
+    fs::remove_dir_all(&directory).unwrap();
+
+    let Err(Error::Config(message)) = result else {
+        panic!("expected a config error, got {:?}", result);
+    };
+    assert!(message.contains("gdscript-formatter.toml:2: unknown key 'formatter/indent_sise'"));
+}
+
 #[test]
 fn generic_type_parameters_never_break() {
     // Type-level generic parameters like Dictionary[String, String] must
@@ -241,3 +351,352 @@ fn editorconfig_applies_quote_style() {
 
     assert_eq!(config.quote_style, QuoteStyle::Single);
 }
+
+#[test]
+fn range_formatting_leaves_code_outside_the_range_untouched() {
+    let input = "var a=1\nvar b=2\nvar c=3\n";
+    let range = line_range_to_byte_range(input, 2, 2);
+
+    let output = format_gdscript_range(input, &range, &FormatterConfiguration::default()).unwrap();
+
+    assert_eq!(output, "var a=1\nvar b = 2\nvar c=3\n");
+}
+
+#[test]
+fn range_formatting_only_rewrites_the_selected_statement_in_a_body() {
+    let input = "func f():\n\tvar x=1\n\tvar y=2\nvar z=3\n";
+    let range = line_range_to_byte_range(input, 3, 3);
+
+    let output = format_gdscript_range(input, &range, &FormatterConfiguration::default()).unwrap();
+
+    assert_eq!(output, "func f():\n\tvar x=1\n\tvar y = 2\nvar z=3\n");
+}
+
+#[test]
+fn keeps_crlf_line_endings_and_byte_order_mark() {
+    let input = "\u{FEFF}func f():\r\n\tvar x=1\r\n\treturn x\r\n";
+    let config = FormatterConfiguration {
+        safe: true,
+        ..Default::default()
+    };
+
+    let output = format_gdscript(input, &config).unwrap();
+
+    assert_eq!(output, "\u{FEFF}func f():\r\n\tvar x = 1\r\n\treturn x\r\n");
+}
+
+#[test]
+fn mixed_line_endings_convert_crlf_in_strings_and_disabled_regions() {
+    // The file uses LF, so the CRLF line breaks inside the string and the
+    // `# fmt: off` region come out as LF too. This changes the string's value.
+    let input =
+        "var a=1\nvar text = \"\"\"first\r\nsecond\"\"\"\n# fmt: off\nvar  b=2\r\n# fmt: on\n";
+
+    let output = format_gdscript(input, &FormatterConfiguration::default()).unwrap();
+
+    assert_eq!(
+        output,
+        "var a = 1\nvar text = \"\"\"first\nsecond\"\"\"\n# fmt: off\nvar  b=2\n# fmt: on\n"
+    );
+}
+
+#[test]
+fn safe_mode_reports_strings_with_mixed_line_endings() {
+    let input = "var a=1\n# fmt: off\nvar text = \"\"\"first\r\nsecond\"\"\"\n# fmt: on\n";
+    let config = FormatterConfiguration {
+        safe: true,
+        ..Default::default()
+    };
+
+    let result = format_gdscript(input, &config);
+
+    let Err(Error::StringLineEndingChanged { line }) = result else {
+        panic!("expected a string line ending error, got {:?}", result);
+    };
+    assert_eq!(line, 3);
+}
+
+#[test]
+fn safe_mode_accepts_strings_with_the_line_ending_of_the_file() {
+    let input = "var a=1\r\nvar text = \"\"\"first\r\nsecond\"\"\"\r\n# fmt: off\r\nvar  b=2\n# fmt: on\r\n";
+    let config = FormatterConfiguration {
+        safe: true,
+        ..Default::default()
+    };
+
+    let output = format_gdscript(input, &config).unwrap();
+
+    assert_eq!(
+        output,
+        "var a = 1\r\nvar text = \"\"\"first\r\nsecond\"\"\"\r\n# fmt: off\r\nvar  b=2\r\n# fmt: on\r\n"
+    );
+}
+
+#[test]
+fn line_ending_setting_converts_line_endings() {
+    let input = "var a=1\r\nvar b=2\r\n";
+    let config = FormatterConfiguration {
+        printer: PrinterConfiguration {
+            line_ending: LineEnding::Lf,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let output = format_gdscript(input, &config).unwrap();
+
+    assert_eq!(output, "var a = 1\nvar b = 2\n");
+}
+
+#[test]
+fn range_formatting_keeps_crlf_line_endings() {
+    let input = "var a=1\r\nvar b=2\r\nvar c=3\r\n";
+    let range = line_range_to_byte_range(input, 2, 2);
+
+    let output = format_gdscript_range(input, &range, &FormatterConfiguration::default()).unwrap();
+
+    assert_eq!(output, "var a=1\r\nvar b = 2\r\nvar c=3\r\n");
+}
+
+#[test]
+fn range_formatting_ignores_blank_selections() {
+    let input = "var a=1\n\nvar b=2\n";
+    let range = line_range_to_byte_range(input, 2, 2);
+
+    let output = format_gdscript_range(input, &range, &FormatterConfiguration::default()).unwrap();
+
+    assert_eq!(output, input);
+}
+
+#[test]
+fn reorder_configuration_changes_declaration_order() {
+    let input = "extends Node
+
+func _private_helper():
+\tpass
+
+func jump():
+\tpass
+
+func _ready():
+\tpass
+
+func _notification(what):
+\tpass
+
+@export var speed = 10
+@onready var sprite = $Sprite
+var health = 3
+var armor = 1
+";
+    let expected = "extends Node
+
+@onready var sprite = $Sprite
+
+@export var speed = 10
+
+var armor = 1
+var health = 3
+
+
+func _notification(what):
+\tpass
+
+
+func _ready():
+\tpass
+
+
+func jump():
+\tpass
+
+
+func _private_helper():
+\tpass
+";
+    let config = FormatterConfiguration {
+        reorder_code: true,
+        reorder: ReorderConfiguration {
+            category_order: vec![
+                DeclarationKind::OnReadyVariable,
+                DeclarationKind::ExportVariable,
+                DeclarationKind::RegularVariable,
+                DeclarationKind::Method,
+            ],
+            sort_mode: ReorderSortMode::Alphabetical,
+            virtual_method_order: vec!["_notification".to_string(), "_ready".to_string()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert_eq!(format_gdscript(input, &config).unwrap(), expected);
+}
+
+#[test]
+fn reorder_keeps_source_order_of_tied_virtual_methods() {
+    let input = "extends Control
+
+
+func _gui_input(event):
+\tpass
+
+
+func _unhandled_key_input(event):
+\tpass
+
+
+func _ready():
+\tpass
+";
+    let expected = "extends Control
+
+
+func _ready():
+\tpass
+
+
+func _gui_input(event):
+\tpass
+
+
+func _unhandled_key_input(event):
+\tpass
+";
+    let config = FormatterConfiguration {
+        reorder_code: true,
+        ..Default::default()
+    };
+
+    assert_eq!(format_gdscript(input, &config).unwrap(), expected);
+}
+
+fn lint_declaration_order(source: &str, reorder: &ReorderConfiguration) -> Vec<LintIssue> {
+    let mut config = LinterConfig {
+        reorder: reorder.clone(),
+        ..Default::default()
+    };
+    config
+        .rule_severities
+        .insert("class-definitions-order".to_string(), LintSeverity::Warning);
+    let mut linter = GDScriptLinter::new(config).unwrap();
+    linter
+        .lint(source, "test.gd")
+        .unwrap()
+        .into_iter()
+        .filter(|issue| issue.rule == "class-definitions-order")
+        .collect()
+}
+
+#[test]
+fn class_definitions_order_agrees_with_reorder_code() {
+    let input = "extends Node
+
+func jump():
+\tpass
+
+@onready var sprite = $Sprite
+signal died
+";
+    let reorder = ReorderConfiguration {
+        category_order: vec![
+            DeclarationKind::OnReadyVariable,
+            DeclarationKind::Signal,
+            DeclarationKind::Method,
+        ],
+        ..Default::default()
+    };
+
+    let issues = lint_declaration_order(input, &reorder);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].line, 6);
+    assert_eq!(
+        issues[0].message,
+        "onready variable `sprite` should come before method `jump`"
+    );
+
+    let config = FormatterConfiguration {
+        reorder_code: true,
+        reorder: reorder.clone(),
+        ..Default::default()
+    };
+    let formatted = format_gdscript(input, &config).unwrap();
+    assert!(lint_declaration_order(&formatted, &reorder).is_empty());
+}
+
+const REORDER_REGIONS_INPUT: &str = "extends Node
+
+var speed = 1
+
+#region Hooks
+func _physics_process(delta):
+\tpass
+
+
+func _ready():
+\tpass
+#endregion
+
+signal died
+";
+
+fn format_with_region_mode(region_mode: ReorderRegionMode) -> String {
+    let config = FormatterConfiguration {
+        reorder_code: true,
+        reorder: ReorderConfiguration {
+            region_mode,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    format_gdscript(REORDER_REGIONS_INPUT, &config).unwrap()
+}
+
+#[test]
+fn region_mode_in_place_reorders_regions_internally() {
+    let expected = "extends Node
+
+var speed = 1
+
+
+#region Hooks
+func _ready():
+\tpass
+
+
+func _physics_process(delta):
+\tpass
+#endregion
+
+
+signal died
+";
+    assert_eq!(
+        format_with_region_mode(ReorderRegionMode::InPlace),
+        expected
+    );
+}
+
+#[test]
+fn region_mode_first_member_sorts_regions_as_blocks() {
+    let expected = "extends Node
+
+signal died
+
+var speed = 1
+
+
+#region Hooks
+func _ready():
+\tpass
+
+
+func _physics_process(delta):
+\tpass
+#endregion
+";
+    assert_eq!(
+        format_with_region_mode(ReorderRegionMode::FirstMember),
+        expected
+    );
+}
@@ -0,0 +1,19 @@
+# gdlint-ignore-file signal-name
+# gdlint-ignore-file max-line-length
+
+signal BadSignal
+
+# gdlint-disable constant-name
+const badOne = 1
+const badTwo = 2
+# gdlint-enable constant-name
+const badThree = 3
+
+# gdlint-disable
+const badFour = 4
+# gdlint-enable private-access
+const badFive = 5
+# gdlint-enable
+
+var good_variable = 1 # gdlint-ignore variable-name
+var other_variable = 2 # gdlint-ignore-next-line unknown-rule
@@ -0,0 +1,133 @@
+//! Drives `gdscript-formatter lsp` with a scripted client over stdio and checks
+//! the server's responses.
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn test_directory() -> std::path::PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock should be after the Unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!(
+        "gdscript-formatter-lsp-test-{}-{}",
+        std::process::id(),
+        timestamp
+    ));
+    fs::create_dir(&path).expect("should create temporary test directory");
+    path
+}
+
+fn language_server_command(directory: &std::path::Path) -> Command {
+    let binary = std::env::current_exe()
+        .expect("test executable path should be available")
+        .parent()
+        .expect("test executable should have a parent")
+        .parent()
+        .expect("test executable should be in target/debug/deps")
+        .join("gdscript-formatter");
+    let mut command = Command::new(binary);
+    command.current_dir(directory).arg("lsp");
+    command
+}
+
+fn frame_message(content: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+}
+
+/// Splits the server output into the JSON content of each framed message.
+fn split_messages(output: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut remaining = output;
+    while let Some(header_end) = remaining.find("\r\n\r\n") {
+        let header = &remaining[..header_end];
+        let content_length: usize = header
+            .strip_prefix("Content-Length: ")
+            .expect("every message should start with a Content-Length header")
+            .parse()
+            .expect("Content-Length should be a number");
+        let content_start = header_end + 4;
+        messages.push(remaining[content_start..content_start + content_length].to_string());
+        remaining = &remaining[content_start + content_length..];
+    }
+    messages
+}
+
+#[test]
+fn language_server_formats_documents_and_publishes_diagnostics() {
+    let directory = test_directory();
+    let uri = format!("file://{}/player.gd", directory.display());
+    let script = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#
+            .to_string(),
+        r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#.to_string(),
+        // A response from the client gets no answer.
+        r#"{"jsonrpc":"2.0","id":7,"result":null}"#.to_string(),
+        format!(
+            r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"{}","languageId":"gdscript","version":1,"text":"var MyVar=1\nvar b=2\n"}}}}}}"#,
+            uri
+        ),
+        format!(
+            r#"{{"jsonrpc":"2.0","id":2,"method":"textDocument/formatting","params":{{"textDocument":{{"uri":"{}"}},"options":{{"tabSize":4,"insertSpaces":false}}}}}}"#,
+            uri
+        ),
+        format!(
+            r#"{{"jsonrpc":"2.0","id":3,"method":"textDocument/rangeFormatting","params":{{"textDocument":{{"uri":"{}"}},"range":{{"start":{{"line":1,"character":0}},"end":{{"line":2,"character":0}}}},"options":{{"tabSize":4,"insertSpaces":false}}}}}}"#,
+            uri
+        ),
+        format!(
+            r#"{{"jsonrpc":"2.0","id":5,"method":"textDocument/rangeFormatting","params":{{"textDocument":{{"uri":"{}"}},"range":{{"start":{{"line":0,"character":4}},"end":{{"line":0,"character":9}}}},"options":{{"tabSize":4,"insertSpaces":false}}}}}}"#,
+            uri
+        ),
+        r#"{"jsonrpc":"2.0","id":4,"method":"shutdown"}"#.to_string(),
+        r#"{"jsonrpc":"2.0","method":"exit"}"#.to_string(),
+    ];
+    let mut input = String::new();
+    for message in &script {
+        input.push_str(&frame_message(message));
+    }
+
+    let mut child = language_server_command(&directory)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("should start the language server");
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(input.as_bytes())
+        .expect("should write client messages");
+    let output = child
+        .wait_with_output()
+        .expect("should collect language server output");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("server output should be valid UTF-8");
+    let messages = split_messages(&stdout);
+    assert_eq!(messages.len(), 6, "unexpected messages: {:#?}", messages);
+
+    assert!(messages[0].contains(r#""id":1"#));
+    assert!(messages[0].contains(r#""documentFormattingProvider":true"#));
+    assert!(messages[0].contains(r#""documentRangeFormattingProvider":true"#));
+
+    assert!(messages[1].contains(r#""method":"textDocument/publishDiagnostics""#));
+    assert!(messages[1].contains(r#""code":"variable-name""#));
+    assert!(messages[1].contains(r#""start":{"line":0,"character":4}"#));
+
+    assert!(messages[2].contains(r#""id":2"#));
+    assert!(messages[2].contains(r#""newText":"var MyVar = 1\nvar b = 2\n""#));
+    assert!(messages[2].contains(r#""end":{"line":2,"character":0}"#));
+
+    assert!(messages[3].contains(r#""id":3"#));
+    assert!(messages[3].contains(r#""newText":"var MyVar=1\nvar b = 2\n""#));
+
+    // The range covers characters of the first line only.
+    assert!(messages[4].contains(r#""id":5"#));
+    assert!(messages[4].contains(r#""newText":"var MyVar = 1\nvar b=2\n""#));
+
+    assert_eq!(messages[5], r#"{"jsonrpc":"2.0","id":4,"result":null}"#);
+
+    fs::remove_dir_all(directory).expect("should remove temporary test directory");
+}
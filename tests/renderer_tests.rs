@@ -4,8 +4,8 @@
 /// various kinds of intermediate representations of code, notably around line
 /// wrapping.
 use gdscript_formatter::renderer::{
-    GroupParentFit, PrinterConfiguration, RangeRenderElement, RangeSourceBytes, RenderElement,
-    render,
+    GroupParentFit, LineEnding, PrinterConfiguration, RangeRenderElement, RangeSourceBytes,
+    RenderElement, UnformattedSourceOutput, render, render_tracking_source_offsets,
 };
 
 fn text(start_byte: usize, end_byte: usize) -> RenderElement {
@@ -128,6 +128,25 @@ fn balanced_group_distributes_segments_evenly() {
     assert_eq!(out, "aaaaaaa\n+b + ccc\n");
 }
 
+#[test]
+fn balanced_group_measures_tabs_from_the_segment_column() {
+    // After "ab ", the tab advances from column 4 to column 8, so the second
+    // segment ends at column 9 and doesn't fit.
+    let render_elements = vec![
+        balanced_group(1, 4),
+        RenderElement::TextStatic("ab"),
+        RenderElement::BalancedLine,
+        RenderElement::TextStatic("c\td"),
+    ];
+    let config = PrinterConfiguration {
+        max_line_length: 8,
+        ..get_default_printer_configuration()
+    };
+    let mut out = String::new();
+    render(&render_elements, "", &config, &mut out);
+    assert_eq!(out, "ab\nc\td\n");
+}
+
 #[test]
 fn balanced_group_accounts_for_the_current_column() {
     let render_elements = vec![
@@ -1068,3 +1087,120 @@ fn indent_blank_lines_with_tabs() {
     render(&render_elements, "", &config, &mut out);
     assert_eq!(out, "a\n\tb\n\t\n\tc\n");
 }
+
+#[test]
+fn tracked_source_offsets_map_to_the_end_of_preceding_content() {
+    let source = "ab  cd";
+    let render_elements = vec![text(0, 2), RenderElement::HardLine, text(4, 6)];
+    let mut out = String::new();
+    let mut tracked_output_offsets = Vec::new();
+    render_tracking_source_offsets(
+        &render_elements,
+        source,
+        &get_default_printer_configuration(),
+        &[4, 6],
+        &mut out,
+        &mut tracked_output_offsets,
+        &mut Vec::new(),
+    );
+    assert_eq!(out, "ab\ncd\n");
+    assert_eq!(tracked_output_offsets, vec![2, 5]);
+}
+
+#[test]
+fn unformatted_sources_record_their_output_start() {
+    let source = "ab\nx  =1";
+    let render_elements = vec![
+        text(0, 2),
+        RenderElement::HardLine,
+        RenderElement::UnformattedSource {
+            range: RangeSourceBytes {
+                start_byte: 3,
+                end_byte: 8,
+            },
+        },
+    ];
+    let mut out = String::new();
+    let mut unformatted_sources = Vec::new();
+    render_tracking_source_offsets(
+        &render_elements,
+        source,
+        &PrinterConfiguration {
+            line_ending: LineEnding::CrLf,
+            ..get_default_printer_configuration()
+        },
+        &[],
+        &mut out,
+        &mut Vec::new(),
+        &mut unformatted_sources,
+    );
+    assert_eq!(out, "ab\r\nx  =1\r\n");
+    // The offset is in the output before converting line endings.
+    assert_eq!(
+        unformatted_sources,
+        vec![UnformattedSourceOutput {
+            source_start: 3,
+            source_end: 8,
+            output_start: 3,
+        }]
+    );
+}
+
+#[test]
+fn crlf_line_ending_and_byte_order_mark() {
+    let source = "abcd";
+    let render_elements = vec![text(0, 2), RenderElement::HardLine, text(2, 4)];
+    let mut out = String::new();
+    let mut tracked_output_offsets = Vec::new();
+    render_tracking_source_offsets(
+        &render_elements,
+        source,
+        &PrinterConfiguration {
+            line_ending: LineEnding::CrLf,
+            byte_order_mark: true,
+            ..get_default_printer_configuration()
+        },
+        &[2, 4],
+        &mut out,
+        &mut tracked_output_offsets,
+        &mut Vec::new(),
+    );
+    assert_eq!(out, "\u{FEFF}ab\r\ncd\r\n");
+    assert_eq!(tracked_output_offsets, vec![5, 9]);
+}
+
+#[test]
+fn wide_characters_count_by_display_width() {
+    // The string has 7 characters but takes 10 columns.
+    let render_elements = vec![
+        group(1, 4),
+        RenderElement::TextStatic("\"你好世界\""),
+        RenderElement::SoftLine,
+        RenderElement::TextStatic("x"),
+    ];
+    let config = PrinterConfiguration {
+        max_line_length: 9,
+        ..get_default_printer_configuration()
+    };
+    let mut out = String::new();
+    render(&render_elements, "", &config, &mut out);
+    assert_eq!(out, "\"你好世界\"\nx\n");
+}
+
+#[test]
+fn tabs_inside_lines_advance_to_the_next_tab_stop() {
+    // "ab\tcd" takes 6 columns: the tab after "ab" only spans 2 columns.
+    let render_elements = vec![
+        group(1, 4),
+        RenderElement::TextStatic("ab\tc"),
+        RenderElement::SoftLine,
+        RenderElement::TextStatic("d"),
+    ];
+    let config = PrinterConfiguration {
+        max_line_length: 6,
+        ..get_default_printer_configuration()
+    };
+    let mut out = String::new();
+    render(&render_elements, "", &config, &mut out);
+    assert_eq!(out, "ab\tcd\n");
+}
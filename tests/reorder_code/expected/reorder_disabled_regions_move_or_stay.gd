@@ -0,0 +1,24 @@
+extends Node
+
+signal died
+
+# fmt: off
+const TABLE = [
+	1,  2,
+	3,  4,
+]
+# fmt: on
+
+
+func _ready():
+	pass
+
+
+# fmt: off
+var first  = 1
+var second  = 2
+# fmt: on
+
+const SPEED = 5
+
+var health = 10
@@ -0,0 +1,13 @@
+extends Node
+
+@export var speed = 2.0
+@export var _hidden_speed = 1.0
+
+@export_group("Stats")
+@export var health = 10
+@export var _armor = 1
+
+@export_subgroup("Regen")
+@export var regen_rate = 0.5
+
+var temp = 0
@@ -0,0 +1,34 @@
+var top_level = 1
+
+
+class Player:
+	## Emitted when health reaches zero.
+	signal died
+
+	const MAX_HEALTH = 100
+
+	var health = 10
+
+
+	# Called when the node enters the tree.
+	func _ready():
+		pass
+
+
+	func attack():
+		pass
+
+
+	@rpc
+	func _sync():
+		pass
+
+
+	class Weapon:
+		signal fired
+
+		var damage = 1
+
+
+		func fire():
+			pass
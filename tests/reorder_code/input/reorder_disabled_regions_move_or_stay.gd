@@ -0,0 +1,21 @@
+extends Node
+
+func _ready():
+	pass
+
+# fmt: off
+const TABLE = [
+	1,  2,
+	3,  4,
+]
+# fmt: on
+
+signal died
+
+# fmt: off
+var first  = 1
+var second  = 2
+# fmt: on
+
+var health = 10
+const SPEED = 5
@@ -0,0 +1,12 @@
+extends Node
+
+@export var _hidden_speed = 1.0
+@export var speed = 2.0
+
+@export_group("Stats")
+@export var _armor = 1
+var temp = 0
+@export var health = 10
+
+@export_subgroup("Regen")
+@export var regen_rate = 0.5
@@ -0,0 +1,32 @@
+class Player:
+	## Emitted when health reaches zero.
+	signal died
+
+	func attack():
+		pass
+
+
+	var health = 10
+	const MAX_HEALTH = 100
+
+
+	# Called when the node enters the tree.
+	func _ready():
+		pass
+
+
+	@rpc
+	func _sync():
+		pass
+
+
+	class Weapon:
+		func fire():
+			pass
+
+
+		var damage = 1
+		signal fired
+
+
+var top_level = 1